@@ -0,0 +1,129 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{Attribute, Data, DeriveInput, Expr, Fields, Ident, LitInt, LitStr, Member, Type};
+
+/// A byte order fixed by an attribute, overriding the caller's.
+#[derive(Clone, Copy)]
+pub enum Order {
+    Little,
+    Big,
+}
+
+impl Order {
+    pub fn tokens(order: Option<Order>) -> TokenStream {
+        match order {
+            Some(Order::Little) => quote!(::thoo_readext::LittleEndian),
+            Some(Order::Big) => quote!(::thoo_readext::BigEndian),
+            None => quote!(__B),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Encoding {
+    Ansi,
+    Utf16,
+}
+
+#[derive(Default)]
+pub struct ContainerAttrs {
+    pub order: Option<Order>,
+}
+
+#[derive(Default)]
+pub struct FieldAttrs {
+    pub order: Option<Order>,
+    pub encoding: Option<Encoding>,
+    pub lossy: bool,
+    pub len: Option<Type>,
+    pub condition: Option<Expr>,
+    pub pad_before: usize,
+    pub pad_after: usize,
+    pub skip: bool,
+}
+
+pub struct Field<'a> {
+    pub member: Member,
+    pub local: Ident,
+    pub ty: &'a Type,
+    pub attrs: FieldAttrs,
+}
+
+impl ContainerAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = ContainerAttrs::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("read")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("little_endian") {
+                    result.order = Some(Order::Little);
+                } else if meta.path.is_ident("big_endian") {
+                    result.order = Some(Order::Big);
+                } else {
+                    return Err(meta.error("unsupported container attribute"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(result)
+    }
+}
+
+impl FieldAttrs {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = FieldAttrs::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("read")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("little_endian") {
+                    result.order = Some(Order::Little);
+                } else if meta.path.is_ident("big_endian") {
+                    result.order = Some(Order::Big);
+                } else if meta.path.is_ident("ansi") {
+                    result.encoding = Some(Encoding::Ansi);
+                } else if meta.path.is_ident("utf16") {
+                    result.encoding = Some(Encoding::Utf16);
+                } else if meta.path.is_ident("lossy") {
+                    result.lossy = true;
+                } else if meta.path.is_ident("skip") {
+                    result.skip = true;
+                } else if meta.path.is_ident("len") {
+                    result.len = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                } else if meta.path.is_ident("if") {
+                    result.condition = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                } else if meta.path.is_ident("pad_before") {
+                    result.pad_before = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+                } else if meta.path.is_ident("pad_after") {
+                    result.pad_after = meta.value()?.parse::<LitInt>()?.base10_parse()?;
+                } else {
+                    return Err(meta.error("unsupported field attribute"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(result)
+    }
+}
+
+/// Collects the fields of a struct, rejecting enums and unions.
+pub fn fields(input: &DeriveInput) -> syn::Result<(Vec<Field<'_>>, &Fields)> {
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        _ => return Err(syn::Error::new(Span::call_site(), "only structs can be derived")),
+    };
+
+    let mut result = Vec::new();
+    for (index, field) in data.fields.iter().enumerate() {
+        let (member, local) = match &field.ident {
+            Some(ident) => (Member::Named(ident.clone()), format_ident!("__field_{}", ident.unraw())),
+            None => (Member::Unnamed(index.into()), format_ident!("__field_{}", index)),
+        };
+
+        result.push(Field { member, local, ty: &field.ty, attrs: FieldAttrs::parse(&field.attrs)? });
+    }
+
+    Ok((result, &data.fields))
+}
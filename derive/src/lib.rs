@@ -0,0 +1,39 @@
+//! Derive macros for `thoo_readext`. Use them through the `derive` feature of that crate.
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput};
+
+mod attr;
+mod read;
+mod write;
+
+/// Derives `ReadFrom` by reading each field in declaration order.
+///
+/// Field attributes, all under `#[read(...)]`:
+///
+/// - `little_endian` / `big_endian`: read the field in a fixed byte order instead of
+///   the caller's. Also accepted on the struct itself.
+/// - `lossy`: read a `String` field with `read_fstring_lossy`.
+/// - `ansi` / `utf16`: write a `String` field in this encoding; reading accepts both.
+/// - `len = "u32"`: read a `Vec` field with a length prefix of this type instead of `i32`.
+/// - `if = "self.version >= 5"`: only read the field when the condition, which may
+///   refer to earlier fields through `self`, holds; otherwise use `Default::default()`.
+/// - `pad_before = 4` / `pad_after = 4`: skip this many bytes around the field.
+/// - `skip`: don't read the field at all and use `Default::default()`.
+#[proc_macro_derive(ReadFrom, attributes(read))]
+pub fn derive_read_from(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    read::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `WriteTo`, the inverse of `ReadFrom`, honouring the same `#[read(...)]`
+/// attributes. Padding is written as zero bytes.
+#[proc_macro_derive(WriteTo, attributes(read))]
+pub fn derive_write_to(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    write::expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::ext::IdentExt;
+use syn::visit_mut::{self, VisitMut};
+use syn::{parse_quote, DeriveInput, Expr, Fields, Ident, Member};
+
+use crate::attr::{self, ContainerAttrs, Order};
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let container = ContainerAttrs::parse(&input.attrs)?;
+    let (fields, shape) = attr::fields(input)?;
+
+    let mut locals = HashMap::new();
+    let mut statements = Vec::new();
+    for field in &fields {
+        let attrs = &field.attrs;
+        let local = &field.local;
+        let ty = field.ty;
+        let order = Order::tokens(attrs.order.or(container.order));
+
+        let value = if attrs.skip {
+            quote!(::core::default::Default::default())
+        } else {
+            let pad_before = attrs.pad_before;
+            let pad_after = attrs.pad_after;
+            let read = if let Some(len) = &attrs.len {
+                quote!(::thoo_readext::__private::read_vec::<#order, #len, _, _>(__reader)?)
+            } else if attrs.lossy {
+                quote!(::thoo_readext::__private::read_string::<#order, _>(__reader, true)?)
+            } else {
+                quote!(<#ty as ::thoo_readext::ReadFrom>::read_from::<#order, _>(__reader)?)
+            };
+
+            let scope = match &field.member {
+                Member::Named(ident) => ident.unraw().to_string(),
+                Member::Unnamed(index) => index.index.to_string(),
+            };
+            let read = quote! {
+                ::thoo_readext::ReadExt::with_context(__reader, #scope, |__reader| {
+                    ::thoo_readext::__private::skip(__reader, #pad_before)?;
+                    let __value: #ty = #read;
+                    ::thoo_readext::__private::skip(__reader, #pad_after)?;
+                    ::core::result::Result::Ok(__value)
+                })?
+            };
+
+            match &attrs.condition {
+                Some(condition) => {
+                    let mut condition = condition.clone();
+                    ReplaceSelf { locals: &locals }.visit_expr_mut(&mut condition);
+                    quote!(if #condition { #read } else { ::core::default::Default::default() })
+                }
+                None => read,
+            }
+        };
+
+        statements.push(quote!(let #local: #ty = #value;));
+        locals.insert(field.member.clone(), local.clone());
+    }
+
+    let construct = match shape {
+        Fields::Named(_) => {
+            let members = fields.iter().map(|f| &f.member);
+            let locals = fields.iter().map(|f| &f.local);
+            quote!(Self { #(#members: #locals),* })
+        }
+        Fields::Unnamed(_) => {
+            let locals = fields.iter().map(|f| &f.local);
+            quote!(Self(#(#locals),*))
+        }
+        Fields::Unit => quote!(Self),
+    };
+
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::thoo_readext::ReadFrom));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::thoo_readext::ReadFrom for #name #ty_generics #where_clause {
+            fn read_from<__B, __R>(__reader: &mut __R) -> ::thoo_readext::ReaderResult<Self>
+            where
+                __B: ::thoo_readext::ByteOrder,
+                __R: ::thoo_readext::ReadExt + ?::core::marker::Sized,
+            {
+                #(#statements)*
+                ::core::result::Result::Ok(#construct)
+            }
+        }
+    })
+}
+
+/// Rewrites `self.field` in a condition to the local holding the already read field.
+struct ReplaceSelf<'a> {
+    locals: &'a HashMap<Member, Ident>,
+}
+
+impl VisitMut for ReplaceSelf<'_> {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        if let Expr::Field(field) = expr {
+            let is_self = matches!(&*field.base, Expr::Path(path) if path.path.is_ident("self"));
+            if let (true, Some(local)) = (is_self, self.locals.get(&field.member)) {
+                *expr = parse_quote!(#local);
+                return;
+            }
+        }
+
+        visit_mut::visit_expr_mut(self, expr);
+    }
+}
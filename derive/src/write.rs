@@ -0,0 +1,62 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, DeriveInput};
+
+use crate::attr::{self, ContainerAttrs, Encoding, Order};
+
+pub fn expand(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let container = ContainerAttrs::parse(&input.attrs)?;
+    let (fields, _) = attr::fields(input)?;
+
+    let mut statements = Vec::new();
+    for field in fields.iter().filter(|f| !f.attrs.skip) {
+        let attrs = &field.attrs;
+        let member = &field.member;
+        let order = Order::tokens(attrs.order.or(container.order));
+
+        let write = if let Some(len) = &attrs.len {
+            quote!(::thoo_readext::__private::write_vec::<#order, #len, _, _>(__writer, &self.#member)?;)
+        } else if let Some(encoding) = attrs.encoding {
+            let encoding = match encoding {
+                Encoding::Ansi => quote!(::thoo_readext::StringEncoding::Ansi),
+                Encoding::Utf16 => quote!(::thoo_readext::StringEncoding::Utf16),
+            };
+            quote!(::thoo_readext::__private::write_string::<#order, _>(__writer, &self.#member, #encoding)?;)
+        } else {
+            quote!(::thoo_readext::WriteTo::write_to::<#order, _>(&self.#member, __writer)?;)
+        };
+
+        let pad_before = attrs.pad_before;
+        let pad_after = attrs.pad_after;
+        let write = quote! {
+            ::thoo_readext::__private::pad(__writer, #pad_before)?;
+            #write
+            ::thoo_readext::__private::pad(__writer, #pad_after)?;
+        };
+
+        statements.push(match &attrs.condition {
+            Some(condition) => quote!(if #condition { #write }),
+            None => write,
+        });
+    }
+
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    for param in generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::thoo_readext::WriteTo));
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::thoo_readext::WriteTo for #name #ty_generics #where_clause {
+            fn write_to<__B, __W>(&self, __writer: &mut __W) -> ::thoo_readext::WriterResult<()>
+            where
+                __B: ::thoo_readext::ByteOrder,
+                __W: ::thoo_readext::WriteExt + ?::core::marker::Sized,
+            {
+                #(#statements)*
+                ::core::result::Result::Ok(())
+            }
+        }
+    })
+}
@@ -0,0 +1,81 @@
+use std::io::Cursor;
+
+use thoo_readext::{LittleEndian, PathSegment, ReadError, ReadFrom, WriteTo};
+
+#[derive(Debug, Default, PartialEq, ReadFrom, WriteTo)]
+struct Header {
+    version: i32,
+    #[read(big_endian)]
+    magic: u32,
+    #[read(if = "self.version >= 5", utf16)]
+    name: String,
+    #[read(len = "u8", pad_after = 2)]
+    flags: Vec<u16>,
+    #[read(skip)]
+    cached: Option<u64>,
+    enabled: bool,
+}
+
+#[derive(Debug, PartialEq, ReadFrom, WriteTo)]
+#[read(big_endian)]
+struct Pair(u16, i16);
+
+#[derive(Debug, PartialEq, ReadFrom, WriteTo)]
+struct Property {
+    r#type: u8,
+    #[read(if = "self.r#type != 0")]
+    r#ref: u32,
+}
+
+#[test]
+fn derive_round_trip() {
+    let data = vec![
+        5, 0, 0, 0,
+        0xC1, 0x83, 0x2A, 0x9E,
+        0xFE, 0xFF, 0xFF, 0xFF, 0x41, 0x00, 0x00, 0x00,
+        2, 1, 0, 2, 0, 0, 0,
+        1, 0, 0, 0,
+    ];
+
+    let header = Header::read_from::<LittleEndian, _>(&mut Cursor::new(data.clone())).unwrap();
+    assert_eq!(header, Header {
+        version: 5,
+        magic: 0xC1832A9E,
+        name: "A".to_owned(),
+        flags: vec![1, 2],
+        cached: None,
+        enabled: true,
+    });
+
+    let mut output = Vec::new();
+    header.write_to::<LittleEndian, _>(&mut output).unwrap();
+    assert_eq!(output, data);
+}
+
+#[test]
+fn derive_conditional_and_errors() {
+    let data = vec![4, 0, 0, 0, 0xC1, 0x83, 0x2A, 0x9E, 0, 0, 0, 0, 0, 0, 0];
+    let header = Header::read_from::<LittleEndian, _>(&mut Cursor::new(data)).unwrap();
+    assert_eq!(header.name, "");
+    assert!(!header.enabled);
+
+    let pair = Pair::read_from::<LittleEndian, _>(&mut Cursor::new(vec![0, 1, 0xFF, 0xFF])).unwrap();
+    assert_eq!(pair, Pair(1, -1));
+
+    let error = Pair::read_from::<LittleEndian, _>(&mut Cursor::new(vec![0, 1])).unwrap_err();
+    assert!(matches!(error.root(), ReadError::UnexpectedEof { .. }));
+    assert_eq!(error.path(), [PathSegment::Name("1")]);
+}
+
+#[test]
+fn derive_raw_identifiers() {
+    let property = Property::read_from::<LittleEndian, _>(&mut Cursor::new(vec![1, 2, 0, 0, 0])).unwrap();
+    assert_eq!(property, Property { r#type: 1, r#ref: 2 });
+
+    let mut output = Vec::new();
+    property.write_to::<LittleEndian, _>(&mut output).unwrap();
+    assert_eq!(output, [1, 2, 0, 0, 0]);
+
+    let error = Property::read_from::<LittleEndian, _>(&mut Cursor::new(vec![1, 2])).unwrap_err();
+    assert_eq!(error.path(), [PathSegment::Name("ref")]);
+}
@@ -0,0 +1,308 @@
+use byteorder::ByteOrder;
+use tokio::io::AsyncRead;
+
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::fstring;
+use crate::limits;
+use crate::types::{self, FGuid, FName, NameTable, RawFName};
+use crate::version;
+use crate::{ArchiveVersion, Endian, Limits, PackageFileVersion, Primitive, ReadError, ReaderResult};
+
+/// A boxed future returned by the element readers passed to [`AsyncUnrealReadExt::read_array`].
+pub type ReadFuture<'a, T> = Pin<Box<dyn Future<Output = ReaderResult<T>> + Send + 'a>>;
+
+/// The async counterpart of [`ReadExt`](crate::ReadExt), implemented for every
+/// [`tokio::io::AsyncRead`] stream and for [`Reader`](crate::Reader) over one.
+///
+/// Length prefixes are checked against the same [`Limits`] and strings are decoded by
+/// the same code as the blocking readers, so both report identical [`ReadError`]s.
+///
+/// Primitives are read with [`AsyncUnrealReadExt::read_num`], leaving names like
+/// `read_u32_le` to [`tokio::io::AsyncReadExt`], which is usually in scope as well.
+pub trait AsyncUnrealReadExt: Send {
+
+    /// Fills `buffer` completely from the stream.
+    fn read_exact_bytes(&mut self, buffer: &mut [u8]) -> impl Future<Output = ReaderResult<()>> + Send;
+
+    /// Returns the current byte offset in the stream, if the reader tracks it.
+    #[inline]
+    fn offset(&self) -> Option<u64> {
+        None
+    }
+
+    /// Returns the number of bytes left in the stream, if known.
+    #[inline]
+    fn remaining(&self) -> Option<u64> {
+        None
+    }
+
+    /// Returns the limits checked before allocating from a length prefix.
+    #[inline]
+    fn limits(&self) -> &Limits {
+        &Limits::DEFAULT
+    }
+
+    /// Records that `bytes` are about to be allocated, failing if that would exceed
+    /// [`Limits::max_total_bytes`] for the session.
+    #[inline]
+    fn allocate(&mut self, bytes: u64) -> ReaderResult<()> {
+        let _ = bytes;
+        Ok(())
+    }
+
+    /// Returns the byte order of data whose order is only known at runtime.
+    /// See [`ReadExt::endian`](crate::ReadExt::endian).
+    #[inline]
+    fn endian(&self) -> Endian {
+        Endian::Little
+    }
+
+    /// Returns the version state of the data being read.
+    /// See [`ReadExt::version`](crate::ReadExt::version).
+    #[inline]
+    fn version(&self) -> &ArchiveVersion {
+        &version::UNVERSIONED
+    }
+
+    #[inline]
+    fn ue_version(&self) -> PackageFileVersion {
+        self.version().package
+    }
+
+    #[inline]
+    fn licensee_version(&self) -> i32 {
+        self.version().licensee
+    }
+
+    /// Returns the custom version registered for `key`, if any.
+    #[inline]
+    fn custom_version(&self, key: &FGuid) -> Option<i32> {
+        self.version().custom_version(key)
+    }
+
+    /// Returns the name table FNames are resolved against.
+    /// See [`ReadExt::names`](crate::ReadExt::names).
+    #[inline]
+    fn names(&self) -> &NameTable {
+        &types::EMPTY_NAMES
+    }
+
+    /// Reads an FName in the byte order `B` and resolves it against [`AsyncUnrealReadExt::names`].
+    fn read_fname<B: ByteOrder>(&mut self) -> impl Future<Output = ReaderResult<FName>> + Send {
+        async move {
+            let offset = self.offset();
+            let raw = self.read_fname_raw::<B>().await?;
+            self.names().resolve(raw).map_err(|e| e.located(offset))
+        }
+    }
+
+    /// Reads an FName in the byte order `B` without resolving it.
+    #[inline]
+    fn read_fname_raw<B: ByteOrder>(&mut self) -> impl Future<Output = ReaderResult<RawFName>> + Send {
+        async move {
+            Ok(RawFName {
+                index: self.read_num::<B, i32>().await?,
+                number: self.read_num::<B, i32>().await?,
+            })
+        }
+    }
+
+    /// Runs `read` inside the scope `name`, which is added to the path of any error it returns.
+    /// See [`ReadExt::with_context`](crate::ReadExt::with_context).
+    fn with_context<T: Send, F>(&mut self, name: &'static str, read: F) -> impl Future<Output = ReaderResult<T>> + Send
+    where
+        F: for<'r> FnOnce(&'r mut Self) -> ReadFuture<'r, T> + Send
+    {
+        async move {
+            read(self).await.map_err(|e| e.context(name))
+        }
+    }
+
+    /// Reads a primitive in the byte order `B`.
+    #[inline]
+    fn read_num<B: ByteOrder, T: Primitive>(&mut self) -> impl Future<Output = ReaderResult<T>> + Send {
+        async move {
+            let mut buffer = [0u8; 16];
+            let bytes = &mut buffer[..T::SIZE];
+            self.read_exact_bytes(bytes).await?;
+            Ok(T::from_bytes::<B>(bytes))
+        }
+    }
+
+    /// Reads an i32 length prefix in the byte order `B`, followed by that many elements.
+    ///
+    /// ```
+    /// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+    /// use thoo_readext::{AsyncUnrealReadExt, LittleEndian};
+    ///
+    /// let mut data: &[u8] = &[2, 0, 0, 0, 7, 0, 9, 0];
+    /// let values = data.read_array::<LittleEndian, _, _>(|r| Box::pin(r.read_num::<LittleEndian, u16>())).await.unwrap();
+    /// assert_eq!(values, [7, 9]);
+    /// # });
+    /// ```
+    #[inline]
+    fn read_array<B: ByteOrder, T: Send, F>(&mut self, serialize: F) -> impl Future<Output = ReaderResult<Vec<T>>> + Send
+    where
+        F: for<'r> FnMut(&'r mut Self) -> ReadFuture<'r, T> + Send
+    {
+        async move {
+            let length = self.read_num::<B, i32>().await?;
+            self.read_array_with_length(serialize, length).await
+        }
+    }
+
+    /// Reads `length` elements with `serialize`, stopping at the first element that fails.
+    fn read_array_with_length<T: Send, F>(&mut self, mut serialize: F, length: i32) -> impl Future<Output = ReaderResult<Vec<T>>> + Send
+    where
+        F: for<'r> FnMut(&'r mut Self) -> ReadFuture<'r, T> + Send
+    {
+        async move {
+            let count = limits::check_array_len(length, self.limits(), self.offset())?;
+            self.allocate(limits::array_bytes::<T>(count))?;
+
+            let mut result = Vec::with_capacity(limits::preallocation::<T>(count, self.remaining()));
+            for index in 0..count as usize {
+                let item = serialize(self).await.map_err(|e| e.index(index))?;
+                result.push(item);
+            }
+
+            Ok(result)
+        }
+    }
+
+    /// Reads an FString in the byte order `B`. See [`ReadExt::read_fstring`](crate::ReadExt::read_fstring).
+    #[inline]
+    fn read_fstring<B: ByteOrder>(&mut self) -> impl Future<Output = ReaderResult<String>> + Send {
+        read_fstring::<B, Self>(self, false)
+    }
+
+    /// Like [`AsyncUnrealReadExt::read_fstring`], but replaces invalid sequences with U+FFFD.
+    #[inline]
+    fn read_fstring_lossy<B: ByteOrder>(&mut self) -> impl Future<Output = ReaderResult<String>> + Send {
+        read_fstring::<B, Self>(self, true)
+    }
+
+    /// Reads an Unreal `bool`, stored as a 4 byte integer in the byte order `B`.
+    fn read_bool<B: ByteOrder>(&mut self) -> impl Future<Output = ReaderResult<bool>> + Send {
+        async move {
+            let offset = self.offset();
+            match self.read_num::<B, u32>().await? {
+                0 => Ok(false),
+                1 => Ok(true),
+                value => Err(ReadError::InvalidBool { value, offset }),
+            }
+        }
+    }
+
+}
+
+impl<Impl> AsyncUnrealReadExt for Impl
+where
+    Impl: AsyncRead + Unpin + Send
+{
+
+    #[inline]
+    async fn read_exact_bytes(&mut self, buffer: &mut [u8]) -> ReaderResult<()> {
+        tokio::io::AsyncReadExt::read_exact(self, buffer).await?;
+        Ok(())
+    }
+
+}
+
+async fn read_fstring<B: ByteOrder, R: AsyncUnrealReadExt + ?Sized>(reader: &mut R, lossy: bool) -> ReaderResult<String> {
+    let start = reader.offset();
+    let length = reader.read_num::<B, i32>().await?;
+    let (encoding, size) = fstring::parse_header(length, start)?;
+    if size == 0 {
+        return Ok(String::new());
+    }
+
+    let len = limits::check_string_size(size, reader.limits(), reader.remaining(), reader.offset())?;
+    reader.allocate(size)?;
+
+    let mut buffer = vec![0u8; len];
+    reader.read_exact_bytes(buffer.as_mut_slice()).await?;
+    fstring::decode::<B>(encoding, buffer, lossy).map_err(|e| e.located(start))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::future::Future;
+
+    use tokio::io::AsyncReadExt;
+
+    use crate::types::{FGuid, NameTable};
+    use crate::{ArchiveVersion, AsyncUnrealReadExt, BigEndian, Endian, LittleEndian, Limits, PackageFileVersion, ReadError, Reader};
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(future)
+    }
+
+    #[test]
+    fn async_read_matches_sync() {
+        block_on(async {
+            let mut data: &[u8] = &[
+                6, 0, 0, 0, 0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x00,
+                0xFD, 0xFF, 0xFF, 0xFF, 0x48, 0x00, 0x69, 0x00, 0x00, 0x00,
+                2, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0,
+                0x12, 0x34, 1, 0, 0, 0, 9,
+            ];
+
+            assert_eq!(data.read_fstring::<LittleEndian>().await.unwrap(), "Hello");
+            assert_eq!(data.read_fstring::<LittleEndian>().await.unwrap(), "Hi");
+            let values = data.read_array::<LittleEndian, _, _>(|r| Box::pin(r.read_num::<LittleEndian, i32>())).await.unwrap();
+            assert_eq!(values, [3, 4]);
+            assert_eq!(data.read_num::<BigEndian, u16>().await.unwrap(), 0x1234);
+            assert!(data.read_bool::<LittleEndian>().await.unwrap());
+            // Tokio's extension trait is usually imported as well and must not clash.
+            assert_eq!(data.read_u8().await.unwrap(), 9);
+            assert!(matches!(data.read_num::<LittleEndian, u8>().await, Err(ReadError::UnexpectedEof { offset: None })));
+        });
+    }
+
+    #[test]
+    fn async_reader_tracks_offsets_and_limits() {
+        block_on(async {
+            let limits = Limits { max_array_len: 1, ..Limits::DEFAULT };
+            let mut reader = Reader::with_limits(&[0u8, 0, 0, 0, 2, 0, 0, 0][..], limits);
+
+            reader.read_num::<LittleEndian, u32>().await.unwrap();
+            let result = reader.read_array::<LittleEndian, _, _>(|r| Box::pin(r.read_num::<LittleEndian, u8>())).await;
+            assert!(matches!(result, Err(ReadError::AllocationLimit { requested: 2, limit: 1, offset: Some(8) })));
+        });
+    }
+
+    #[test]
+    fn async_reader_versions_reach_element_readers() {
+        const KEY: FGuid = FGuid::new(1, 2, 3, 4);
+
+        async fn read_items(custom_version: i32, data: &[u8]) -> Vec<u32> {
+            let mut version = ArchiveVersion::new(PackageFileVersion::new(522, 1004), 0);
+            version.set_custom_version(KEY, custom_version);
+
+            let mut reader = Reader::new(data);
+            reader.set_version(version);
+            reader.read_array::<LittleEndian, _, _>(|ar| Box::pin(async move {
+                if ar.ue_version() >= PackageFileVersion::new(522, 0) && ar.custom_version(&KEY) >= Some(7) {
+                    ar.read_num::<LittleEndian, u32>().await
+                } else {
+                    ar.read_num::<LittleEndian, u16>().await.map(u32::from)
+                }
+            })).await.unwrap()
+        }
+
+        block_on(async {
+            assert_eq!(read_items(7, &[2, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0]).await, [1, 2]);
+            assert_eq!(read_items(6, &[2, 0, 0, 0, 1, 0, 2, 0]).await, [1, 2]);
+
+            let mut reader = Reader::new(&[1u8, 0, 0, 0, 0, 0, 0, 0][..]);
+            reader.set_endian(Endian::Big);
+            reader.set_names(NameTable::from(vec![String::from("None"), String::from("Foo")]));
+            assert_eq!(reader.endian(), Endian::Big);
+            assert_eq!(reader.read_fname::<LittleEndian>().await.unwrap().name(), "Foo");
+        });
+    }
+
+}
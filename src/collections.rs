@@ -0,0 +1,358 @@
+use byteorder::ByteOrder;
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+#[cfg(any(feature = "std", feature = "indexmap"))]
+use core::hash::{BuildHasher, Hash};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
+
+use crate::limits;
+use crate::{ReadError, ReadExt, ReaderResult};
+
+/// What to do when a TMap or TSet contains the same key more than once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DuplicateKeys {
+    /// Fail with [`ReadError::DuplicateKey`].
+    #[default]
+    Error,
+    /// Keep the first entry and drop later ones.
+    KeepFirst,
+    /// Keep the last entry, in the position of the first one for ordered collections.
+    KeepLast,
+}
+
+/// A collection a TMap can be read into with [`ReadExt::read_map`].
+///
+/// Implemented for `BTreeMap`, `HashMap` with the `std` feature, `IndexMap` with the
+/// `indexmap` feature, and `Vec<(K, V)>`, which like `IndexMap` keeps the file order.
+pub trait MapCollection<K, V>: Sized {
+
+    fn with_capacity(capacity: usize) -> Self;
+
+    /// Returns the value stored for `key`, if any.
+    fn get_mut(&mut self, key: &K) -> Option<&mut V>;
+
+    /// Adds an entry whose key is not in the collection yet.
+    fn insert_new(&mut self, key: K, value: V);
+
+}
+
+/// A collection a TSet can be read into with [`ReadExt::read_set`].
+///
+/// Implemented for the same collections as [`MapCollection`].
+pub trait SetCollection<T>: Sized {
+
+    fn with_capacity(capacity: usize) -> Self;
+
+    fn contains(&self, value: &T) -> bool;
+
+    /// Adds `value`, replacing an equal value already in the collection.
+    fn replace(&mut self, value: T);
+
+}
+
+#[cfg(feature = "std")]
+impl<K: Eq + Hash, V, S: BuildHasher + Default> MapCollection<K, V> for HashMap<K, V, S> {
+
+    #[inline]
+    fn with_capacity(capacity: usize) -> Self {
+        HashMap::with_capacity_and_hasher(capacity, S::default())
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        HashMap::get_mut(self, key)
+    }
+
+    #[inline]
+    fn insert_new(&mut self, key: K, value: V) {
+        self.insert(key, value);
+    }
+
+}
+
+#[cfg(feature = "std")]
+impl<T: Eq + Hash, S: BuildHasher + Default> SetCollection<T> for HashSet<T, S> {
+
+    #[inline]
+    fn with_capacity(capacity: usize) -> Self {
+        HashSet::with_capacity_and_hasher(capacity, S::default())
+    }
+
+    #[inline]
+    fn contains(&self, value: &T) -> bool {
+        HashSet::contains(self, value)
+    }
+
+    #[inline]
+    fn replace(&mut self, value: T) {
+        HashSet::replace(self, value);
+    }
+
+}
+
+#[cfg(feature = "indexmap")]
+impl<K: Eq + Hash, V, S: BuildHasher + Default> MapCollection<K, V> for indexmap::IndexMap<K, V, S> {
+
+    #[inline]
+    fn with_capacity(capacity: usize) -> Self {
+        indexmap::IndexMap::with_capacity_and_hasher(capacity, S::default())
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        indexmap::IndexMap::get_mut(self, key)
+    }
+
+    #[inline]
+    fn insert_new(&mut self, key: K, value: V) {
+        self.insert(key, value);
+    }
+
+}
+
+#[cfg(feature = "indexmap")]
+impl<T: Eq + Hash, S: BuildHasher + Default> SetCollection<T> for indexmap::IndexSet<T, S> {
+
+    #[inline]
+    fn with_capacity(capacity: usize) -> Self {
+        indexmap::IndexSet::with_capacity_and_hasher(capacity, S::default())
+    }
+
+    #[inline]
+    fn contains(&self, value: &T) -> bool {
+        indexmap::IndexSet::contains(self, value)
+    }
+
+    #[inline]
+    fn replace(&mut self, value: T) {
+        indexmap::IndexSet::replace(self, value);
+    }
+
+}
+
+impl<K: Ord, V> MapCollection<K, V> for BTreeMap<K, V> {
+
+    #[inline]
+    fn with_capacity(_capacity: usize) -> Self {
+        BTreeMap::new()
+    }
+
+    #[inline]
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        BTreeMap::get_mut(self, key)
+    }
+
+    #[inline]
+    fn insert_new(&mut self, key: K, value: V) {
+        self.insert(key, value);
+    }
+
+}
+
+impl<T: Ord> SetCollection<T> for BTreeSet<T> {
+
+    #[inline]
+    fn with_capacity(_capacity: usize) -> Self {
+        BTreeSet::new()
+    }
+
+    #[inline]
+    fn contains(&self, value: &T) -> bool {
+        BTreeSet::contains(self, value)
+    }
+
+    #[inline]
+    fn replace(&mut self, value: T) {
+        BTreeSet::replace(self, value);
+    }
+
+}
+
+/// Looks keys up with a linear scan, so reading a map with `n` entries into a `Vec`
+/// takes `O(n²)` comparisons.
+impl<K: PartialEq, V> MapCollection<K, V> for Vec<(K, V)> {
+
+    #[inline]
+    fn with_capacity(capacity: usize) -> Self {
+        Vec::with_capacity(capacity)
+    }
+
+    fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    #[inline]
+    fn insert_new(&mut self, key: K, value: V) {
+        self.push((key, value));
+    }
+
+}
+
+/// Looks values up with a linear scan, like the `Vec` [`MapCollection`].
+impl<T: PartialEq> SetCollection<T> for Vec<T> {
+
+    #[inline]
+    fn with_capacity(capacity: usize) -> Self {
+        Vec::with_capacity(capacity)
+    }
+
+    fn contains(&self, value: &T) -> bool {
+        <[T]>::contains(self, value)
+    }
+
+    fn replace(&mut self, value: T) {
+        match self.iter_mut().find(|v| **v == value) {
+            Some(existing) => *existing = value,
+            None => self.push(value),
+        }
+    }
+
+}
+
+pub(crate) fn read_map<B, M, K, V, R>(
+    reader: &mut R,
+    mut read_key: impl FnMut(&mut R) -> ReaderResult<K>,
+    mut read_value: impl FnMut(&mut R) -> ReaderResult<V>,
+    policy: DuplicateKeys,
+) -> ReaderResult<M>
+where
+    B: ByteOrder,
+    M: MapCollection<K, V>,
+    R: ReadExt + ?Sized,
+{
+    let count = read_count::<B, (K, V), _>(reader)?;
+
+    let mut result = M::with_capacity(limits::preallocation::<(K, V)>(count, reader.remaining()));
+    for index in 0..count as usize {
+        let offset = reader.offset();
+        let key = read_key(reader).map_err(|e| e.index(index))?;
+        let value = read_value(reader).map_err(|e| e.index(index))?;
+
+        match (result.get_mut(&key), policy) {
+            (None, _) => result.insert_new(key, value),
+            (Some(_), DuplicateKeys::Error) => return Err(ReadError::DuplicateKey { index, offset }),
+            (Some(_), DuplicateKeys::KeepFirst) => {}
+            (Some(existing), DuplicateKeys::KeepLast) => *existing = value,
+        }
+    }
+
+    Ok(result)
+}
+
+pub(crate) fn read_set<B, S, T, R>(
+    reader: &mut R,
+    mut serialize: impl FnMut(&mut R) -> ReaderResult<T>,
+    policy: DuplicateKeys,
+) -> ReaderResult<S>
+where
+    B: ByteOrder,
+    S: SetCollection<T>,
+    R: ReadExt + ?Sized,
+{
+    let count = read_count::<B, T, _>(reader)?;
+
+    let mut result = S::with_capacity(limits::preallocation::<T>(count, reader.remaining()));
+    for index in 0..count as usize {
+        let offset = reader.offset();
+        let value = serialize(reader).map_err(|e| e.index(index))?;
+
+        match policy {
+            DuplicateKeys::Error if result.contains(&value) => {
+                return Err(ReadError::DuplicateKey { index, offset });
+            }
+            DuplicateKeys::KeepFirst if result.contains(&value) => {}
+            _ => result.replace(value),
+        }
+    }
+
+    Ok(result)
+}
+
+/// Reads a TSparseArray: its allocation flags as a TBitArray, followed by an element
+/// for every allocated index.
+pub(crate) fn read_sparse_array<B, T, R>(
+    reader: &mut R,
+    mut serialize: impl FnMut(&mut R) -> ReaderResult<T>,
+) -> ReaderResult<Vec<Option<T>>>
+where
+    B: ByteOrder,
+    R: ReadExt + ?Sized,
+{
+    let num_bits = read_count::<B, Option<T>, _>(reader)?;
+    let num_words = num_bits.div_ceil(32);
+
+    let mut words = Vec::with_capacity(limits::preallocation::<u32>(num_words, reader.remaining()));
+    for _ in 0..num_words {
+        words.push(reader.read_num::<B, u32>()?);
+    }
+
+    let mut result = Vec::with_capacity(limits::preallocation::<Option<T>>(num_bits, reader.remaining()));
+    for index in 0..num_bits as usize {
+        let allocated = words[index / 32] & (1 << (index % 32)) != 0;
+        if allocated {
+            result.push(Some(serialize(reader).map_err(|e| e.index(index))?));
+        } else {
+            result.push(None);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Reads and checks an i32 element count, reserving room for that many `T`.
+fn read_count<B: ByteOrder, T, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<u64> {
+    let length = reader.read_num::<B, i32>()?;
+    let count = limits::check_array_len(length, reader.limits(), reader.offset())?;
+    reader.allocate(limits::array_bytes::<T>(count))?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeMap;
+    use alloc::vec::Vec;
+    #[cfg(feature = "std")]
+    use std::collections::HashMap;
+
+    use crate::{DuplicateKeys, LittleEndian, MapCollection, ReadError, ReadExt};
+
+    const MAP: [u8; 28] = [
+        3, 0, 0, 0,
+        2, 0, 0, 0, 20, 0, 0, 0,
+        1, 0, 0, 0, 10, 0, 0, 0,
+        2, 0, 0, 0, 30, 0, 0, 0,
+    ];
+
+    fn read_map<M: MapCollection<i32, i32>>(policy: DuplicateKeys) -> Result<M, ReadError> {
+        (&MAP[..]).read_map::<LittleEndian, M, _, _>(|r| r.read_i32_le(), |r| r.read_i32_le(), policy)
+    }
+
+    #[test]
+    fn read_map_duplicate_policies() {
+        let error = read_map::<BTreeMap<_, _>>(DuplicateKeys::Error).unwrap_err();
+        assert!(matches!(error, ReadError::DuplicateKey { index: 2, .. }));
+        #[cfg(feature = "std")]
+        assert!(matches!(read_map::<HashMap<_, _>>(DuplicateKeys::Error), Err(ReadError::DuplicateKey { index: 2, .. })));
+
+        assert_eq!(read_map::<Vec<_>>(DuplicateKeys::KeepFirst).unwrap(), [(2, 20), (1, 10)]);
+        assert_eq!(read_map::<Vec<_>>(DuplicateKeys::KeepLast).unwrap(), [(2, 30), (1, 10)]);
+        assert_eq!(read_map::<BTreeMap<_, _>>(DuplicateKeys::KeepLast).unwrap()[&2], 30);
+        #[cfg(feature = "std")]
+        assert_eq!(read_map::<HashMap<_, _>>(DuplicateKeys::KeepLast).unwrap()[&2], 30);
+
+        let set: Vec<i32> = (&[3u8, 0, 0, 0, 5, 0, 0, 0, 5, 0, 0, 0, 6, 0, 0, 0][..])
+            .read_set::<LittleEndian, _, _>(|r| r.read_i32_le(), DuplicateKeys::KeepFirst)
+            .unwrap();
+        assert_eq!(set, [5, 6]);
+    }
+
+    #[test]
+    fn read_sparse_array() {
+        let data = [5u8, 0, 0, 0, 0b10101, 0, 0, 0, 7, 8, 9];
+        let values = (&data[..]).read_sparse_array::<LittleEndian, _>(|r| r.read_num::<LittleEndian, u8>()).unwrap();
+        assert_eq!(values, [Some(7), None, Some(8), None, Some(9)]);
+    }
+
+}
@@ -0,0 +1,193 @@
+//! Decompression of the block formats Unreal containers use.
+//!
+//! Every method except [`CompressionMethod::None`] sits behind a cargo feature of the
+//! same name; without it, decompressing fails with [`ReadError::UnsupportedCompression`].
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{ReadError, ReaderResult};
+
+/// A compression method, named as Unreal names it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CompressionMethod {
+    None,
+    Zlib,
+    Gzip,
+    Lz4,
+    Zstd,
+    Brotli,
+    /// Oodle is proprietary, so data compressed with it can never be decompressed here.
+    Oodle,
+}
+
+impl CompressionMethod {
+
+    /// Returns the name Unreal uses for the method.
+    pub fn name(self) -> &'static str {
+        match self {
+            CompressionMethod::None => "None",
+            CompressionMethod::Zlib => "Zlib",
+            CompressionMethod::Gzip => "Gzip",
+            CompressionMethod::Lz4 => "LZ4",
+            CompressionMethod::Zstd => "Zstd",
+            CompressionMethod::Brotli => "Brotli",
+            CompressionMethod::Oodle => "Oodle",
+        }
+    }
+
+    /// Looks a method up by its Unreal name, ignoring case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            CompressionMethod::None,
+            CompressionMethod::Zlib,
+            CompressionMethod::Gzip,
+            CompressionMethod::Lz4,
+            CompressionMethod::Zstd,
+            CompressionMethod::Brotli,
+            CompressionMethod::Oodle,
+        ]
+        .into_iter()
+            .find(|method| method.name().eq_ignore_ascii_case(name))
+    }
+
+}
+
+impl fmt::Display for CompressionMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Decompresses `input`, which must expand to exactly `uncompressed_size` bytes.
+///
+/// The caller is responsible for checking `uncompressed_size` against its limits.
+pub fn decompress(method: CompressionMethod, input: &[u8], uncompressed_size: usize) -> ReaderResult<Vec<u8>> {
+    let output = match method {
+        CompressionMethod::None => input.to_vec(),
+        #[cfg(feature = "zlib")]
+        CompressionMethod::Zlib => decompress_zlib(input, uncompressed_size)?,
+        #[cfg(feature = "gzip")]
+        CompressionMethod::Gzip => decompress_gzip(input, uncompressed_size)?,
+        #[cfg(feature = "lz4")]
+        CompressionMethod::Lz4 => decompress_lz4(input, uncompressed_size)?,
+        #[cfg(feature = "zstd")]
+        CompressionMethod::Zstd => decompress_zstd(input, uncompressed_size)?,
+        #[cfg(feature = "brotli")]
+        CompressionMethod::Brotli => decompress_brotli(input, uncompressed_size)?,
+        _ => return Err(ReadError::UnsupportedCompression { method: method.name().to_string(), offset: None }),
+    };
+
+    if output.len() != uncompressed_size {
+        return Err(ReadError::Decompression { method: method.name(), offset: None });
+    }
+    Ok(output)
+}
+
+#[cfg(feature = "zlib")]
+fn decompress_zlib(input: &[u8], uncompressed_size: usize) -> ReaderResult<Vec<u8>> {
+    miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(input, uncompressed_size)
+        .map_err(|_| ReadError::Decompression { method: "Zlib", offset: None })
+}
+
+/// Inflates a gzip member, skipping its header. The trailing CRC is not checked.
+#[cfg(feature = "gzip")]
+fn decompress_gzip(input: &[u8], uncompressed_size: usize) -> ReaderResult<Vec<u8>> {
+    const FHCRC: u8 = 0x02;
+    const FEXTRA: u8 = 0x04;
+    const FNAME: u8 = 0x08;
+    const FCOMMENT: u8 = 0x10;
+
+    let invalid = || ReadError::Decompression { method: "Gzip", offset: None };
+    let skip_string = |position: usize| -> Option<usize> {
+        Some(position + input.get(position..)?.iter().position(|&b| b == 0)? + 1)
+    };
+
+    if input.len() < 10 || input[..3] != [0x1F, 0x8B, 0x08] {
+        return Err(invalid());
+    }
+    let flags = input[3];
+    let mut position = 10;
+    if flags & FEXTRA != 0 {
+        let length = input.get(position..position + 2).ok_or_else(invalid)?;
+        position += 2 + usize::from(u16::from_le_bytes([length[0], length[1]]));
+    }
+    if flags & FNAME != 0 {
+        position = skip_string(position).ok_or_else(invalid)?;
+    }
+    if flags & FCOMMENT != 0 {
+        position = skip_string(position).ok_or_else(invalid)?;
+    }
+    if flags & FHCRC != 0 {
+        position += 2;
+    }
+
+    let deflated = input.get(position..).ok_or_else(invalid)?;
+    miniz_oxide::inflate::decompress_to_vec_with_limit(deflated, uncompressed_size).map_err(|_| invalid())
+}
+
+#[cfg(feature = "lz4")]
+fn decompress_lz4(input: &[u8], uncompressed_size: usize) -> ReaderResult<Vec<u8>> {
+    let mut output = alloc::vec![0; uncompressed_size];
+    let size = lz4_flex::block::decompress_into(input, &mut output)
+        .map_err(|_| ReadError::Decompression { method: "LZ4", offset: None })?;
+    output.truncate(size);
+    Ok(output)
+}
+
+#[cfg(feature = "zstd")]
+fn decompress_zstd(input: &[u8], uncompressed_size: usize) -> ReaderResult<Vec<u8>> {
+    let mut output = Vec::with_capacity(uncompressed_size);
+    ruzstd::decoding::FrameDecoder::new()
+        .decode_all_to_vec(input, &mut output)
+        .map_err(|_| ReadError::Decompression { method: "Zstd", offset: None })?;
+    Ok(output)
+}
+
+#[cfg(feature = "brotli")]
+fn decompress_brotli(input: &[u8], uncompressed_size: usize) -> ReaderResult<Vec<u8>> {
+    use brotli_decompressor::BrotliResult;
+
+    let mut output = alloc::vec![0; uncompressed_size];
+    let info = brotli_decompressor::brotli_decode(input, &mut output);
+    if !matches!(info.result, BrotliResult::ResultSuccess) {
+        return Err(ReadError::Decompression { method: "Brotli", offset: None });
+    }
+
+    output.truncate(info.decoded_size);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::compression::{decompress, CompressionMethod};
+    use crate::ReadError;
+
+    #[test]
+    fn decompress_methods() {
+        assert_eq!(decompress(CompressionMethod::None, b"abc", 3).unwrap(), b"abc");
+        assert!(matches!(decompress(CompressionMethod::None, b"abc", 4), Err(ReadError::Decompression { .. })));
+        assert!(matches!(
+            decompress(CompressionMethod::Oodle, b"abc", 3),
+            Err(ReadError::UnsupportedCompression { method, .. }) if method == "Oodle"
+        ));
+        assert_eq!(CompressionMethod::from_name("zstd"), Some(CompressionMethod::Zstd));
+
+        #[cfg(feature = "zlib")]
+        {
+            let data = b"unreal unreal unreal unreal".repeat(8);
+            let compressed = miniz_oxide::deflate::compress_to_vec_zlib(&data, 6);
+            assert_eq!(decompress(CompressionMethod::Zlib, &compressed, data.len()).unwrap(), data);
+        }
+
+        #[cfg(feature = "zstd")]
+        {
+            let data = b"unreal unreal unreal unreal".repeat(8);
+            let compressed = ruzstd::encoding::compress_to_vec(&data[..], ruzstd::encoding::CompressionLevel::Fastest);
+            assert_eq!(decompress(CompressionMethod::Zstd, &compressed, data.len()).unwrap(), data);
+        }
+    }
+
+}
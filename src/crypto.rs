@@ -0,0 +1,193 @@
+//! AES-256 decryption of encrypted archive data, behind the `aes` cargo feature.
+//!
+//! Unreal encrypts with AES-256 in ECB mode, padding the data to whole 16 byte blocks.
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+use aes::cipher::generic_array::GenericArray;
+use aes::cipher::{BlockDecrypt, KeyInit};
+use aes::Aes256;
+use core::{cmp, fmt};
+
+/// The size of an AES block, which encrypted data is padded to.
+pub const BLOCK_SIZE: usize = 16;
+
+/// A 256 bit AES key.
+#[derive(Clone)]
+pub struct AesKey {
+    cipher: Aes256,
+}
+
+impl AesKey {
+
+    pub fn new(key: &[u8; 32]) -> Self {
+        Self { cipher: Aes256::new(key.into()) }
+    }
+
+    /// Parses a key written as 64 hex digits, with or without a `0x` prefix, as
+    /// Unreal's crypto settings and most tools print it.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix("0x").or_else(|| hex.strip_prefix("0X")).unwrap_or(hex);
+        if hex.len() != 64 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let mut key = [0; 32];
+        for (byte, digits) in key.iter_mut().zip(hex.as_bytes().chunks_exact(2)) {
+            *byte = u8::from_str_radix(core::str::from_utf8(digits).ok()?, 16).ok()?;
+        }
+        Some(Self::new(&key))
+    }
+
+    /// Decrypts `data` in place. Bytes after the last whole block are left as they are.
+    pub fn decrypt(&self, data: &mut [u8]) {
+        for block in data.chunks_exact_mut(BLOCK_SIZE) {
+            self.cipher.decrypt_block(GenericArray::from_mut_slice(block));
+        }
+    }
+
+}
+
+impl fmt::Debug for AesKey {
+
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("AesKey(..)")
+    }
+
+}
+
+/// A reader decrypting a seekable stream encrypted as a whole, so that
+/// [`ReadExt`](crate::ReadExt) methods read the plain data.
+///
+/// Reads and seeks are in plain data positions; the stream is read a whole block at a
+/// time. A partial block at the end of the stream cannot be decrypted and is left out.
+#[derive(Debug)]
+pub struct AesReader<R> {
+    inner: R,
+    key: AesKey,
+    length: u64,
+    position: u64,
+    block: [u8; BLOCK_SIZE],
+    /// The index of the block decrypted into `block`, if any.
+    block_index: Option<u64>,
+}
+
+impl<R: Read + Seek> AesReader<R> {
+
+    pub fn new(mut inner: R, key: AesKey) -> io::Result<Self> {
+        let length = inner.seek(SeekFrom::End(0))?;
+        inner.seek(SeekFrom::Start(0))?;
+        Ok(Self {
+            inner,
+            key,
+            length: length - length % BLOCK_SIZE as u64,
+            position: 0,
+            block: [0; BLOCK_SIZE],
+            block_index: None,
+        })
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads whole blocks into `buffer` and decrypts them in place, for reads starting
+    /// on a block boundary.
+    fn read_blocks(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.length - self.position;
+        let length = cmp::min(buffer.len() as u64, remaining) as usize / BLOCK_SIZE * BLOCK_SIZE;
+        self.inner.seek(SeekFrom::Start(self.position))?;
+        self.inner.read_exact(&mut buffer[..length])?;
+        self.key.decrypt(&mut buffer[..length]);
+        self.position += length as u64;
+        Ok(length)
+    }
+
+}
+
+impl<R: Read + Seek> Read for AesReader<R> {
+
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        if buffer.is_empty() || self.position >= self.length {
+            return Ok(0);
+        }
+
+        let offset = (self.position % BLOCK_SIZE as u64) as usize;
+        if offset == 0 && buffer.len() >= BLOCK_SIZE {
+            return self.read_blocks(buffer);
+        }
+
+        let index = self.position / BLOCK_SIZE as u64;
+        if self.block_index != Some(index) {
+            self.inner.seek(SeekFrom::Start(index * BLOCK_SIZE as u64))?;
+            self.inner.read_exact(&mut self.block)?;
+            self.key.decrypt(&mut self.block);
+            self.block_index = Some(index);
+        }
+
+        let length = cmp::min(buffer.len(), BLOCK_SIZE - offset);
+        buffer[..length].copy_from_slice(&self.block[offset..offset + length]);
+        self.position += length as u64;
+        Ok(length)
+    }
+
+}
+
+impl<R: Read + Seek> Seek for AesReader<R> {
+
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        let position = match position {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.length.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        };
+        self.position = position
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "seek to a negative position"))?;
+        Ok(self.position)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use aes::cipher::generic_array::GenericArray;
+    use aes::cipher::{BlockEncrypt, KeyInit};
+    use byteorder::LittleEndian;
+
+    use crate::crypto::{AesKey, AesReader};
+    use crate::{ReadExt, WriteExt};
+
+    #[test]
+    fn read_encrypted_stream() {
+        let key = [7; 32];
+        let mut data = Vec::new();
+        data.write_u32_le(0xDEADBEEF).unwrap();
+        data.write_fstring::<LittleEndian>("Encrypted").unwrap();
+        data.resize(data.len().next_multiple_of(16), 0);
+        data.extend_from_slice(&[0xAB; 20]);
+        data.resize(64, 0);
+
+        let mut encrypted = data.clone();
+        let cipher = aes::Aes256::new(&key.into());
+        for block in encrypted.chunks_exact_mut(16) {
+            cipher.encrypt_block(GenericArray::from_mut_slice(block));
+        }
+
+        let hex: String = key.iter().map(|b| format!("{:02X}", b)).collect();
+        let key = AesKey::from_hex(&format!("0x{}", hex)).unwrap();
+        let mut reader = AesReader::new(Cursor::new(encrypted), key).unwrap();
+        assert_eq!(reader.read_u32_le().unwrap(), 0xDEADBEEF);
+        assert_eq!(reader.read_fstring::<LittleEndian>().unwrap(), "Encrypted");
+
+        let mut plain = Vec::new();
+        std::io::Seek::seek(&mut reader, std::io::SeekFrom::Start(3)).unwrap();
+        std::io::Read::read_to_end(&mut reader, &mut plain).unwrap();
+        assert_eq!(plain, data[3..]);
+        assert!(AesKey::from_hex("0x1234").is_none());
+        // `from_str_radix` alone would take "+A" as a digit pair.
+        assert!(AesKey::from_hex(&"+A".repeat(32)).is_none());
+        assert!(AesKey::from_hex(&"0G".repeat(32)).is_none());
+    }
+}
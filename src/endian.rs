@@ -0,0 +1,66 @@
+use core::fmt;
+
+/// A byte order known only at runtime, for example from a header magic.
+///
+/// Readers report theirs through [`ReadExt::endian`](crate::ReadExt::endian); use
+/// [`with_endian!`](crate::with_endian) to turn it into a [`ByteOrder`](crate::ByteOrder)
+/// type parameter so a single generic code path handles both orders.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Endian {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endian {
+
+    /// The byte order of the target platform.
+    pub const NATIVE: Endian = if cfg!(target_endian = "little") { Endian::Little } else { Endian::Big };
+
+    /// Returns the opposite byte order.
+    #[inline]
+    pub fn swapped(self) -> Endian {
+        match self {
+            Endian::Little => Endian::Big,
+            Endian::Big => Endian::Little,
+        }
+    }
+
+}
+
+impl fmt::Display for Endian {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Endian::Little => f.write_str("little-endian"),
+            Endian::Big => f.write_str("big-endian"),
+        }
+    }
+}
+
+/// Evaluates `$body` with `$order` bound to the [`ByteOrder`](crate::ByteOrder) type
+/// matching the runtime [`Endian`] value.
+///
+/// ```
+/// use thoo_readext::{with_endian, Endian, ReadExt, Reader};
+///
+/// let mut reader = Reader::new(&[0u8, 0, 0, 42][..]);
+/// reader.set_endian(Endian::Big);
+///
+/// let value = with_endian!(reader.endian(), B => reader.read_num::<B, i32>()).unwrap();
+/// assert_eq!(value, 42);
+/// ```
+#[macro_export]
+macro_rules! with_endian {
+    ($endian:expr, $order:ident => $body:expr) => {
+        match $endian {
+            $crate::Endian::Little => {
+                type $order = $crate::LittleEndian;
+                $body
+            }
+            $crate::Endian::Big => {
+                type $order = $crate::BigEndian;
+                $body
+            }
+        }
+    };
+}
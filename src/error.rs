@@ -0,0 +1,278 @@
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::error;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::StringEncoding;
+
+/// The error type returned by every [`ReadExt`](crate::ReadExt) method.
+///
+/// Each variant carries the byte offset at which the error happened, when the
+/// reader is able to report it.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ReadError {
+    /// The underlying reader failed.
+    #[cfg(feature = "std")]
+    Io { source: io::Error, offset: Option<u64> },
+    /// The stream ended before the value was fully read.
+    UnexpectedEof { offset: Option<u64> },
+    /// A length prefix was negative where only positive lengths are allowed.
+    NegativeLength { length: i64, offset: Option<u64> },
+    /// A length prefix does not fit in memory on this platform.
+    LengthOverflow { length: i64, offset: Option<u64> },
+    /// A string was not valid in its encoding.
+    InvalidString { encoding: StringEncoding, offset: Option<u64> },
+    /// An FString did not end with a null terminator.
+    MissingNullTerminator { offset: Option<u64> },
+    /// An allocation would exceed the configured limit.
+    AllocationLimit { requested: u64, limit: u64, offset: Option<u64> },
+    /// A serialized `bool` was neither 0 nor 1.
+    InvalidBool { value: u32, offset: Option<u64> },
+    /// A file did not start with the magic number of its format.
+    InvalidMagic { found: u32, offset: Option<u64> },
+    /// A file format version is older or newer than this crate can read.
+    UnsupportedVersion { version: i32, offset: Option<u64> },
+    /// The element at `index` of a TMap or TSet repeated an earlier key.
+    DuplicateKey { index: usize, offset: Option<u64> },
+    /// An FName index was outside the reader's name table of `len` entries.
+    InvalidNameIndex { index: i32, len: usize, offset: Option<u64> },
+    /// A value was of a type this crate cannot decode.
+    UnsupportedType { name: String, offset: Option<u64> },
+    /// Values were nested deeper than `limit` levels.
+    NestingLimit { limit: u32, offset: Option<u64> },
+    /// Data was compressed with a method this crate was built without.
+    UnsupportedCompression { method: String, offset: Option<u64> },
+    /// Compressed data was corrupt or did not decompress to its expected size.
+    Decompression { method: &'static str, offset: Option<u64> },
+    /// Mappings had no schema for a struct or one of its properties.
+    MissingSchema { name: String, offset: Option<u64> },
+    /// An FPackageIndex pointed past the import or export map, or an outer chain looped.
+    InvalidPackageIndex { index: i32, offset: Option<u64> },
+    /// Data did not match the SHA1 hash recorded for it.
+    HashMismatch { offset: Option<u64> },
+    /// Data is encrypted and no key was given to decrypt it.
+    Encrypted { offset: Option<u64> },
+    /// Data decrypted with the given key did not validate, so the key is wrong.
+    InvalidKey { offset: Option<u64> },
+    /// An archive has no entry at `path`.
+    EntryNotFound { path: String, offset: Option<u64> },
+    /// An error raised inside the named scopes and array elements in `path`, outermost first.
+    Context { path: Vec<PathSegment>, source: Box<ReadError> },
+}
+
+/// One step of the path to the value being read when an error happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// A scope entered with [`ReadExt::with_context`](crate::ReadExt::with_context).
+    Name(&'static str),
+    /// An element of an array.
+    Index(usize),
+}
+
+impl ReadError {
+
+    /// Returns the byte offset at which the error happened, if known.
+    pub fn offset(&self) -> Option<u64> {
+        match self {
+            #[cfg(feature = "std")]
+            ReadError::Io { offset, .. } => *offset,
+            ReadError::UnexpectedEof { offset }
+            | ReadError::NegativeLength { offset, .. }
+            | ReadError::LengthOverflow { offset, .. }
+            | ReadError::InvalidString { offset, .. }
+            | ReadError::MissingNullTerminator { offset }
+            | ReadError::AllocationLimit { offset, .. }
+            | ReadError::InvalidBool { offset, .. }
+            | ReadError::InvalidMagic { offset, .. }
+            | ReadError::UnsupportedVersion { offset, .. }
+            | ReadError::DuplicateKey { offset, .. }
+            | ReadError::InvalidNameIndex { offset, .. }
+            | ReadError::UnsupportedType { offset, .. }
+            | ReadError::NestingLimit { offset, .. }
+            | ReadError::UnsupportedCompression { offset, .. }
+            | ReadError::Decompression { offset, .. }
+            | ReadError::MissingSchema { offset, .. }
+            | ReadError::InvalidPackageIndex { offset, .. }
+            | ReadError::HashMismatch { offset }
+            | ReadError::Encrypted { offset }
+            | ReadError::InvalidKey { offset }
+            | ReadError::EntryNotFound { offset, .. } => *offset,
+            ReadError::Context { source, .. } => source.offset(),
+        }
+    }
+
+    /// Returns the path to the value being read when the error happened, outermost first.
+    pub fn path(&self) -> &[PathSegment] {
+        match self {
+            ReadError::Context { path, .. } => path,
+            _ => &[],
+        }
+    }
+
+    /// Returns the underlying error, without its path.
+    pub fn root(&self) -> &ReadError {
+        match self {
+            ReadError::Context { source, .. } => source,
+            _ => self,
+        }
+    }
+
+    /// Adds the named scope `name` to the front of the error's path.
+    pub fn context(self, name: &'static str) -> Self {
+        self.within(PathSegment::Name(name))
+    }
+
+    /// Adds the array element `index` to the front of the error's path.
+    pub fn index(self, index: usize) -> Self {
+        self.within(PathSegment::Index(index))
+    }
+
+    fn within(self, segment: PathSegment) -> Self {
+        match self {
+            ReadError::Context { mut path, source } => {
+                path.insert(0, segment);
+                ReadError::Context { path, source }
+            }
+            source => ReadError::Context { path: vec![segment], source: Box::new(source) },
+        }
+    }
+
+    /// Sets the byte offset of the error unless one is already known.
+    pub fn at(mut self, position: u64) -> Self {
+        self.offset_mut().get_or_insert(position);
+        self
+    }
+
+    fn offset_mut(&mut self) -> &mut Option<u64> {
+        match self {
+            #[cfg(feature = "std")]
+            ReadError::Io { offset, .. } => offset,
+            ReadError::UnexpectedEof { offset }
+            | ReadError::NegativeLength { offset, .. }
+            | ReadError::LengthOverflow { offset, .. }
+            | ReadError::InvalidString { offset, .. }
+            | ReadError::MissingNullTerminator { offset }
+            | ReadError::AllocationLimit { offset, .. }
+            | ReadError::InvalidBool { offset, .. }
+            | ReadError::InvalidMagic { offset, .. }
+            | ReadError::UnsupportedVersion { offset, .. }
+            | ReadError::DuplicateKey { offset, .. }
+            | ReadError::InvalidNameIndex { offset, .. }
+            | ReadError::UnsupportedType { offset, .. }
+            | ReadError::NestingLimit { offset, .. }
+            | ReadError::UnsupportedCompression { offset, .. }
+            | ReadError::Decompression { offset, .. }
+            | ReadError::MissingSchema { offset, .. }
+            | ReadError::InvalidPackageIndex { offset, .. }
+            | ReadError::HashMismatch { offset }
+            | ReadError::Encrypted { offset }
+            | ReadError::InvalidKey { offset }
+            | ReadError::EntryNotFound { offset, .. } => offset,
+            ReadError::Context { source, .. } => source.offset_mut(),
+        }
+    }
+
+    /// Like [`ReadError::at`], for readers that may not know their position.
+    pub(crate) fn located(self, position: Option<u64>) -> Self {
+        match position {
+            Some(position) => self.at(position),
+            None => self,
+        }
+    }
+
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            ReadError::Io { source, .. } => write!(f, "I/O error: {}", source)?,
+            ReadError::UnexpectedEof { .. } => f.write_str("unexpected end of stream")?,
+            ReadError::NegativeLength { length, .. } => write!(f, "negative length {}", length)?,
+            ReadError::LengthOverflow { length, .. } => write!(f, "length {} is out of range", length)?,
+            ReadError::InvalidString { encoding, .. } => write!(f, "invalid {} string", encoding)?,
+            ReadError::MissingNullTerminator { .. } => f.write_str("FString is not null-terminated")?,
+            ReadError::AllocationLimit { requested, limit, .. } => {
+                write!(f, "allocation of {} exceeds the limit of {}", requested, limit)?
+            }
+            ReadError::InvalidBool { value, .. } => write!(f, "invalid bool value {}", value)?,
+            ReadError::InvalidMagic { found, .. } => write!(f, "invalid magic {:#010x}", found)?,
+            ReadError::UnsupportedVersion { version, .. } => write!(f, "unsupported file version {}", version)?,
+            ReadError::DuplicateKey { index, .. } => write!(f, "duplicate key at element {}", index)?,
+            ReadError::InvalidNameIndex { index, len, .. } => {
+                write!(f, "name index {} is out of range for a name table of {} entries", index, len)?
+            }
+            ReadError::UnsupportedType { name, .. } => write!(f, "unsupported type {}", name)?,
+            ReadError::NestingLimit { limit, .. } => write!(f, "nesting exceeds the limit of {} levels", limit)?,
+            ReadError::UnsupportedCompression { method, .. } => write!(f, "unsupported compression method {}", method)?,
+            ReadError::Decompression { method, .. } => write!(f, "invalid {} compressed data", method)?,
+            ReadError::MissingSchema { name, .. } => write!(f, "no mappings for {}", name)?,
+            ReadError::InvalidPackageIndex { index, .. } => write!(f, "invalid package index {}", index)?,
+            ReadError::HashMismatch { .. } => f.write_str("SHA1 hash mismatch")?,
+            ReadError::Encrypted { .. } => f.write_str("data is encrypted and no key was given")?,
+            ReadError::InvalidKey { .. } => f.write_str("wrong decryption key")?,
+            ReadError::EntryNotFound { path, .. } => write!(f, "no entry at {}", path)?,
+            ReadError::Context { path, source } => {
+                for (i, segment) in path.iter().enumerate() {
+                    match segment {
+                        PathSegment::Name(name) if i == 0 => f.write_str(name)?,
+                        PathSegment::Name(name) => write!(f, ".{}", name)?,
+                        PathSegment::Index(index) => write!(f, "[{}]", index)?,
+                    }
+                }
+
+                return write!(f, ": {}", source);
+            }
+        }
+
+        match self.offset() {
+            Some(offset) => write!(f, " at offset {:#x}", offset),
+            None => Ok(()),
+        }
+    }
+}
+
+impl error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            ReadError::Io { source, .. } => Some(source),
+            ReadError::Context { source, .. } => source.source(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for ReadError {
+    fn from(source: io::Error) -> Self {
+        // Unwrap errors of ours that passed through an `io::Read` implementation.
+        if source.get_ref().is_some_and(|inner| inner.is::<ReadError>()) {
+            if let Ok(error) = source.into_inner().unwrap().downcast::<ReadError>() {
+                return *error;
+            }
+            unreachable!();
+        }
+
+        match source.kind() {
+            io::ErrorKind::UnexpectedEof => ReadError::UnexpectedEof { offset: None },
+            _ => ReadError::Io { source, offset: None },
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ReadError> for io::Error {
+    fn from(error: ReadError) -> Self {
+        match error {
+            ReadError::Io { source, .. } => source,
+            ReadError::UnexpectedEof { .. } => io::Error::new(io::ErrorKind::UnexpectedEof, error),
+            _ => io::Error::new(io::ErrorKind::InvalidData, error),
+        }
+    }
+}
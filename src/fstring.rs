@@ -0,0 +1,130 @@
+use byteorder::ByteOrder;
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::char;
+use core::fmt;
+use core::str;
+
+use crate::limits;
+use crate::{ReadError, ReadExt, ReaderResult};
+
+/// The on-disk encoding of an FString.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StringEncoding {
+    /// Single-byte characters, signalled by a positive length prefix.
+    Ansi,
+    /// UTF-16 code units, signalled by a negative length prefix.
+    Utf16,
+}
+
+impl fmt::Display for StringEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StringEncoding::Ansi => f.write_str("ANSI"),
+            StringEncoding::Utf16 => f.write_str("UTF-16"),
+        }
+    }
+}
+
+pub(crate) fn read<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R, lossy: bool) -> ReaderResult<String> {
+    let start = reader.offset();
+    let (encoding, size) = read_header::<B, _>(reader)?;
+    if size == 0 {
+        return Ok(String::new());
+    }
+
+    let buffer = read_buffer(reader, size)?;
+    decode::<B>(encoding, buffer, lossy).map_err(|e| e.located(start))
+}
+
+/// Reads an FString length prefix, returning the encoding and the payload size in bytes.
+pub(crate) fn read_header<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<(StringEncoding, u64)> {
+    let start = reader.offset();
+    let length = reader.read_num::<B, i32>()?;
+    parse_header(length, start)
+}
+
+/// Interprets an FString length prefix, returning the encoding and the payload size in bytes.
+pub(crate) fn parse_header(length: i32, offset: Option<u64>) -> ReaderResult<(StringEncoding, u64)> {
+    if length == i32::MIN {
+        return Err(ReadError::LengthOverflow { length: length.into(), offset });
+    }
+
+    if length < 0 {
+        Ok((StringEncoding::Utf16, u64::from(length.unsigned_abs()) * 2))
+    } else {
+        Ok((StringEncoding::Ansi, length as u64))
+    }
+}
+
+/// Reads `size` bytes for a string, checking the reader's limits before allocating.
+fn read_buffer<R: ReadExt + ?Sized>(reader: &mut R, size: u64) -> ReaderResult<Vec<u8>> {
+    let len = limits::check_string_size(size, reader.limits(), reader.remaining(), reader.offset())?;
+    reader.allocate(size)?;
+
+    let mut buffer = vec![0u8; len];
+    reader.read_exact_bytes(buffer.as_mut_slice())?;
+    Ok(buffer)
+}
+
+/// Decodes the payload of an FString in the byte order `B`, including its null terminator.
+pub(crate) fn decode<B: ByteOrder>(encoding: StringEncoding, buffer: Vec<u8>, lossy: bool) -> ReaderResult<String> {
+    match encoding {
+        StringEncoding::Ansi => decode_ansi(buffer, lossy),
+        StringEncoding::Utf16 => decode_utf16::<B>(&buffer, lossy),
+    }
+}
+
+/// Decodes the payload of an ANSI FString, including its null terminator, reusing
+/// the buffer for the string.
+pub(crate) fn decode_ansi(mut buffer: Vec<u8>, lossy: bool) -> ReaderResult<String> {
+    strip_terminator(&buffer, 0u8)?;
+    buffer.pop();
+
+    match String::from_utf8(buffer) {
+        Ok(result) => Ok(result),
+        Err(e) if lossy => Ok(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+        Err(_) => Err(ReadError::InvalidString { encoding: StringEncoding::Ansi, offset: None }),
+    }
+}
+
+/// Like [`decode_ansi`], but borrows the string from `buffer`.
+pub(crate) fn decode_ansi_str(buffer: &[u8]) -> ReaderResult<&str> {
+    let content = strip_terminator(buffer, 0u8)?;
+    str::from_utf8(content)
+        .map_err(|_| ReadError::InvalidString { encoding: StringEncoding::Ansi, offset: None })
+}
+
+/// Like [`decode_ansi`] with `lossy` set, but borrows the string from `buffer` when it is valid.
+pub(crate) fn decode_ansi_lossy(buffer: &[u8]) -> ReaderResult<Cow<'_, str>> {
+    let content = strip_terminator(buffer, 0u8)?;
+    Ok(String::from_utf8_lossy(content))
+}
+
+/// Decodes the payload of a UTF-16 FString in the byte order `B`, including its null terminator.
+///
+/// Surrogate pairs are combined; unpaired surrogates are an error unless `lossy`
+/// is set, in which case they are replaced by U+FFFD.
+pub(crate) fn decode_utf16<B: ByteOrder>(buffer: &[u8], lossy: bool) -> ReaderResult<String> {
+    let units: Vec<u16> = buffer.chunks_exact(2).map(B::read_u16).collect();
+    let content = strip_terminator(&units, 0u16)?;
+
+    let decoded = char::decode_utf16(content.iter().copied());
+    if lossy {
+        return Ok(decoded.map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)).collect());
+    }
+
+    decoded
+        .collect::<Result<String, _>>()
+        .map_err(|_| ReadError::InvalidString { encoding: StringEncoding::Utf16, offset: None })
+}
+
+fn strip_terminator<T: PartialEq>(buffer: &[T], terminator: T) -> ReaderResult<&[T]> {
+    match buffer.split_last() {
+        Some((last, content)) if *last == terminator => Ok(content),
+        _ => Err(ReadError::MissingNullTerminator { offset: None }),
+    }
+}
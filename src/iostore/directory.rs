@@ -0,0 +1,79 @@
+use byteorder::LittleEndian;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{ReadError, ReadExt, ReaderResult, SliceReader};
+
+/// Marks a missing directory, file or name in the directory index.
+const INVALID_INDEX: u32 = u32::MAX;
+
+/// The decoded directory index of a container, `FIoDirectoryIndexResource` in Unreal.
+#[derive(Debug, Default)]
+pub(crate) struct DirectoryIndex {
+    pub(crate) mount_point: String,
+    /// The path of every file relative to the mount point, with the index of its chunk.
+    pub(crate) files: Vec<(String, u32)>,
+}
+
+impl DirectoryIndex {
+
+    /// Reads the index and walks its tree from the root directory.
+    pub(crate) fn read(reader: &mut SliceReader<'_>) -> ReaderResult<Self> {
+        let mount_point = reader.with_context("MountPoint", |r| r.read_fstring::<LittleEndian>())?;
+        let directories = reader.with_context("DirectoryEntries", |r| {
+            r.read_array::<LittleEndian, _>(|r| Ok([r.read_u32_le()?, r.read_u32_le()?, r.read_u32_le()?, r.read_u32_le()?]))
+        })?;
+        let files = reader.with_context("FileEntries", |r| {
+            r.read_array::<LittleEndian, _>(|r| Ok([r.read_u32_le()?, r.read_u32_le()?, r.read_u32_le()?]))
+        })?;
+        let names = reader.with_context("StringTable", |r| r.read_array::<LittleEndian, _>(|r| r.read_fstring::<LittleEndian>()))?;
+
+        let invalid = || ReadError::UnexpectedEof { offset: None };
+        let name = |index: u32| names.get(index as usize).ok_or_else(invalid);
+
+        // Directories are walked without recursion, counting visits so that a cycle
+        // cannot loop forever.
+        let mut paths = Vec::new();
+        let mut pending = Vec::new();
+        if !directories.is_empty() {
+            pending.push((0, String::new()));
+        }
+        let mut visits = 0;
+        while let Some((index, parent)) = pending.pop() {
+            visits += 1;
+            if visits > directories.len() {
+                return Err(invalid().context("DirectoryEntries"));
+            }
+
+            let [directory_name, first_child, next_sibling, first_file] = *directories.get(index as usize).ok_or_else(invalid)?;
+            let path = match directory_name {
+                INVALID_INDEX => parent.clone(),
+                directory_name => format!("{}{}/", parent, name(directory_name)?),
+            };
+
+            let mut file = first_file;
+            let mut file_visits = 0;
+            while file != INVALID_INDEX {
+                file_visits += 1;
+                let [file_name, next_file, chunk_index] = *files.get(file as usize).ok_or_else(invalid)?;
+                if file_visits > files.len() {
+                    return Err(invalid().context("FileEntries"));
+                }
+                paths.push((format!("{}{}", path, name(file_name)?), chunk_index));
+                file = next_file;
+            }
+
+            if next_sibling != INVALID_INDEX {
+                pending.push((next_sibling, parent));
+            }
+            if first_child != INVALID_INDEX {
+                pending.push((first_child, path));
+            }
+        }
+
+        Ok(Self { mount_point, files: paths })
+    }
+
+}
@@ -0,0 +1,353 @@
+//! IoStore containers, the `.utoc` table of contents and the `.ucas` partitions holding
+//! the chunks it lists.
+//!
+//! As with [paks](crate::pak), decompressing chunks needs the cargo feature of their
+//! compression method, and encrypted containers need the `aes` feature and a key.
+
+mod directory;
+mod stream;
+mod toc;
+
+pub use stream::IoChunkReader;
+pub use toc::{ChunkId, ChunkMeta, CompressedBlockEntry, OffsetAndLength, Toc, TocHeader};
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::iostore::directory::DirectoryIndex;
+use crate::limits;
+use crate::pak::{decrypt, has_valid_mount_point, AesKey};
+use crate::{Limits, ReadError, ReadExt, ReaderResult, SliceReader};
+
+/// An IoStore container opened for reading.
+///
+/// Chunks are opened by [`ChunkId`], and in indexed containers also by their path
+/// relative to the [mount point](IoStoreReader::mount_point).
+pub struct IoStoreReader<R> {
+    toc: Toc,
+    partitions: Vec<R>,
+    partition_lengths: Vec<u64>,
+    limits: Limits,
+    mount_point: String,
+    files: BTreeMap<String, u32>,
+    chunks: BTreeMap<ChunkId, u32>,
+    key: Option<AesKey>,
+}
+
+impl IoStoreReader<File> {
+
+    /// Opens the `.utoc` file at `path` with its `.ucas` partitions beside it, named
+    /// `Name.ucas`, `Name_s1.ucas`, `Name_s2.ucas` and so on.
+    pub fn open(path: impl AsRef<Path>) -> ReaderResult<Self> {
+        let path = path.as_ref();
+        let toc = File::open(path)?;
+        let toc = read_toc(toc, &Limits::DEFAULT)?;
+
+        let mut partitions = Vec::new();
+        for index in 0..toc.header.partition_count {
+            let name = match index {
+                0 => path.with_extension("ucas"),
+                index => {
+                    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                    path.with_file_name(alloc::format!("{}_s{}.ucas", stem, index))
+                }
+            };
+            partitions.push(File::open(name)?);
+        }
+        Self::from_toc(toc, partitions, Limits::DEFAULT, None)
+    }
+
+}
+
+impl<R: Read + Seek> IoStoreReader<R> {
+
+    /// Reads the table of contents from `toc`, with the container's `.ucas` partitions
+    /// in order.
+    pub fn new<T: Read>(toc: T, partitions: Vec<R>) -> ReaderResult<Self> {
+        Self::with_limits(toc, partitions, Limits::DEFAULT)
+    }
+
+    pub fn with_limits<T: Read>(toc: T, partitions: Vec<R>, limits: Limits) -> ReaderResult<Self> {
+        let toc = read_toc(toc, &limits)?;
+        Self::from_toc(toc, partitions, limits, None)
+    }
+
+    /// Reads a container encrypted with `key`.
+    ///
+    /// A wrong key is reported as [`ReadError::InvalidKey`] when the decrypted directory
+    /// index does not start with a mount point. Containers without a directory index
+    /// are not checked.
+    #[cfg(feature = "aes")]
+    pub fn with_key<T: Read>(toc: T, partitions: Vec<R>, key: AesKey) -> ReaderResult<Self> {
+        Self::with_key_and_limits(toc, partitions, key, Limits::DEFAULT)
+    }
+
+    /// Reads a container encrypted with `key`, checking sizes against `limits`.
+    #[cfg(feature = "aes")]
+    pub fn with_key_and_limits<T: Read>(toc: T, partitions: Vec<R>, key: AesKey, limits: Limits) -> ReaderResult<Self> {
+        let toc = read_toc(toc, &limits)?;
+        Self::from_toc(toc, partitions, limits, Some(key))
+    }
+
+    fn from_toc(toc: Toc, mut partitions: Vec<R>, limits: Limits, key: Option<AesKey>) -> ReaderResult<Self> {
+        let partition_lengths = partitions
+            .iter_mut()
+            .map(|partition| partition.seek(SeekFrom::End(0)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut reader = Self {
+            chunks: toc.chunk_ids.iter().enumerate().map(|(i, &id)| (id, i as u32)).collect(),
+            toc,
+            partitions,
+            partition_lengths,
+            limits,
+            mount_point: String::new(),
+            files: BTreeMap::new(),
+            key,
+        };
+
+        if !reader.toc.directory_index.is_empty() {
+            let mut data = reader.toc.directory_index.clone();
+            if reader.toc.header.is_encrypted() {
+                decrypt(reader.key.as_ref(), &mut data, 0)?;
+                if !has_valid_mount_point(&data) {
+                    return Err(ReadError::InvalidKey { offset: None });
+                }
+            }
+
+            let index = SliceReader::new(&data).with_context("DirectoryIndex", DirectoryIndex::read)?;
+            reader.mount_point = index.mount_point;
+            for (i, (path, chunk)) in index.files.into_iter().enumerate() {
+                if chunk as usize >= reader.toc.chunk_ids.len() {
+                    return Err(ReadError::UnexpectedEof { offset: None }.context("DirectoryIndex").index(i));
+                }
+                reader.files.insert(path, chunk);
+            }
+        }
+
+        Ok(reader)
+    }
+
+    #[inline]
+    pub fn toc(&self) -> &Toc {
+        &self.toc
+    }
+
+    /// Returns the path the container's files are mounted at, empty if it has no
+    /// directory index.
+    #[inline]
+    pub fn mount_point(&self) -> &str {
+        &self.mount_point
+    }
+
+    /// Returns the paths of the container's files in order.
+    pub fn files(&self) -> impl Iterator<Item = &str> + '_ {
+        self.files.keys().map(String::as_str)
+    }
+
+    /// Returns the ID of the chunk holding the file at `path`.
+    pub fn chunk_id(&self, path: &str) -> Option<ChunkId> {
+        self.files.get(path).map(|&chunk| self.toc.chunk_ids[chunk as usize])
+    }
+
+    /// Opens the data of the chunk `id` for reading.
+    pub fn open_chunk(&mut self, id: ChunkId) -> ReaderResult<IoChunkReader<'_, R>> {
+        let Some(&chunk) = self.chunks.get(&id) else {
+            return Err(ReadError::EntryNotFound { path: id.to_string(), offset: None });
+        };
+        let location = self.toc.chunk_offset_lengths.get(chunk as usize).copied();
+        let location = location.ok_or(ReadError::UnexpectedEof { offset: None })?;
+        let key = match (&self.key, self.toc.header.is_encrypted()) {
+            (Some(key), true) => Some(key),
+            (None, true) => return Err(ReadError::Encrypted { offset: None }),
+            (_, false) => None,
+        };
+
+        let block_size = u64::from(self.toc.header.compression_block_size);
+        let mut blocks = Vec::new();
+        if location.length > 0 {
+            if block_size == 0 {
+                return Err(ReadError::UnexpectedEof { offset: None });
+            }
+            let first = location.offset / block_size;
+            let last = (location.offset + location.length - 1) / block_size;
+            for index in first..=last {
+                let block = self.toc.compression_blocks.get(index as usize).copied();
+                let block = block.ok_or(ReadError::UnexpectedEof { offset: None })?;
+                blocks.push((block, self.toc.compression_method(block.compression_method)?));
+            }
+        }
+
+        let partition_size = match self.toc.header.partition_size {
+            0 => u64::MAX,
+            size => size,
+        };
+        Ok(IoChunkReader::new(
+            &mut self.partitions,
+            &self.partition_lengths,
+            partition_size,
+            self.limits,
+            blocks,
+            location.offset % block_size.max(1),
+            location.length,
+            key,
+        ))
+    }
+
+    /// Opens the data of the file at `path` for reading.
+    pub fn open_entry(&mut self, path: &str) -> ReaderResult<IoChunkReader<'_, R>> {
+        match self.chunk_id(path) {
+            Some(id) => self.open_chunk(id),
+            None => Err(ReadError::EntryNotFound { path: path.to_string(), offset: None }),
+        }
+    }
+
+    /// Reads the whole data of the chunk `id`.
+    pub fn read_chunk(&mut self, id: ChunkId) -> ReaderResult<Vec<u8>> {
+        let limits = self.limits;
+        let mut reader = self.open_chunk(id)?;
+        limits::check_buffer_size(reader.len(), &limits, None)?;
+
+        let mut data = Vec::with_capacity(reader.len() as usize);
+        reader.read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    /// Reads the whole data of the file at `path`.
+    pub fn read_entry(&mut self, path: &str) -> ReaderResult<Vec<u8>> {
+        match self.chunk_id(path) {
+            Some(id) => self.read_chunk(id),
+            None => Err(ReadError::EntryNotFound { path: path.to_string(), offset: None }),
+        }
+    }
+
+    /// Returns the partitions the container was opened with.
+    pub fn into_inner(self) -> Vec<R> {
+        self.partitions
+    }
+
+}
+
+/// Reads a whole `.utoc` file, checking its size against `limits` first.
+fn read_toc<T: Read>(toc: T, limits: &Limits) -> ReaderResult<Toc> {
+    let limit = limits.max_buffer_bytes;
+    let mut data = Vec::new();
+    toc.take(limit.saturating_add(1)).read_to_end(&mut data)?;
+    if data.len() as u64 > limit {
+        return Err(ReadError::AllocationLimit { requested: data.len() as u64, limit, offset: None });
+    }
+    SliceReader::new(&data).with_context("Toc", Toc::read)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use byteorder::LittleEndian;
+
+    use crate::iostore::{ChunkId, IoStoreReader, TocHeader};
+    use crate::{ReadError, WriteExt};
+
+    fn write_u40_be(data: &mut Vec<u8>, value: u64) {
+        data.extend_from_slice(&value.to_be_bytes()[3..]);
+    }
+
+    fn write_block(data: &mut Vec<u8>, offset: u64, size: u32) {
+        data.extend_from_slice(&offset.to_le_bytes()[..5]);
+        data.extend_from_slice(&size.to_le_bytes()[..3]);
+        data.extend_from_slice(&size.to_le_bytes()[..3]);
+        data.write_num::<LittleEndian, u8>(0).unwrap();
+    }
+
+    /// Builds a version 8 table of contents of two uncompressed chunks, the second in
+    /// the container's second partition.
+    fn write_toc() -> Vec<u8> {
+        let mut directory_index = Vec::new();
+        directory_index.write_fstring::<LittleEndian>("../../../").unwrap();
+        directory_index.write_i32_le(2).unwrap();
+        for entry in [[u32::MAX, 1, u32::MAX, u32::MAX], [0, u32::MAX, u32::MAX, 0]] {
+            entry.iter().for_each(|&value| directory_index.write_u32_le(value).unwrap());
+        }
+        directory_index.write_i32_le(2).unwrap();
+        for entry in [[1, 1, 0], [2, u32::MAX, 1]] {
+            entry.iter().for_each(|&value| directory_index.write_u32_le(value).unwrap());
+        }
+        directory_index.write_i32_le(3).unwrap();
+        for name in ["Game", "a.txt", "b.bin"] {
+            directory_index.write_fstring::<LittleEndian>(name).unwrap();
+        }
+
+        let mut toc = Vec::new();
+        toc.extend_from_slice(&TocHeader::MAGIC);
+        toc.write_num::<LittleEndian, u8>(TocHeader::VERSION_LATEST).unwrap();
+        toc.write_num::<LittleEndian, u8>(0).unwrap();
+        toc.write_u16_le(0).unwrap();
+        for value in [TocHeader::SIZE, 2, 2, 12, 1, 32, 0x10000, directory_index.len() as u32, 2] {
+            toc.write_u32_le(value).unwrap();
+        }
+        toc.write_u64_le(7).unwrap();
+        toc.extend_from_slice(&[0; 16]);
+        toc.write_num::<LittleEndian, u8>(TocHeader::FLAG_INDEXED).unwrap();
+        toc.write_num::<LittleEndian, u8>(0).unwrap();
+        toc.write_u16_le(0).unwrap();
+        toc.write_u32_le(0).unwrap();
+        toc.write_u64_le(64).unwrap();
+        toc.write_u32_le(0).unwrap();
+        toc.write_u32_le(0).unwrap();
+        toc.extend_from_slice(&[0; 40]);
+
+        for id in [1, 2] {
+            toc.write_u64_le(id).unwrap();
+            toc.extend_from_slice(&[0, 0, 0, ChunkId::TYPE_EXPORT_BUNDLE_DATA]);
+        }
+        for (offset, length) in [(0, 8), (0x10000, 5)] {
+            write_u40_be(&mut toc, offset);
+            write_u40_be(&mut toc, length);
+        }
+        write_block(&mut toc, 0, 8);
+        write_block(&mut toc, 64, 5);
+        let mut name = [0; 32];
+        name[..4].copy_from_slice(b"Zlib");
+        toc.extend_from_slice(&name);
+        toc.extend_from_slice(&directory_index);
+        toc.extend_from_slice(&[0; 2 * 21]);
+        toc
+    }
+
+    #[test]
+    fn read_partitioned_container() {
+        let partitions = vec![Cursor::new(b"hello io".to_vec()), Cursor::new(b"part2".to_vec())];
+        let mut container = IoStoreReader::new(Cursor::new(write_toc()), partitions).unwrap();
+        assert_eq!(container.toc().header.container_id, 7);
+        assert_eq!(container.toc().compression_methods, ["Zlib"]);
+        assert_eq!(container.mount_point(), "../../../");
+        assert_eq!(container.files().collect::<Vec<_>>(), ["Game/a.txt", "Game/b.bin"]);
+
+        assert_eq!(container.read_entry("Game/a.txt").unwrap(), b"hello io");
+        let id = ChunkId::new(2, 0, ChunkId::TYPE_EXPORT_BUNDLE_DATA);
+        assert_eq!(container.chunk_id("Game/b.bin"), Some(id));
+        assert_eq!(container.read_chunk(id).unwrap(), b"part2");
+        assert!(matches!(container.read_entry("Game/c.txt"), Err(ReadError::EntryNotFound { .. })));
+    }
+
+    #[cfg(feature = "aes")]
+    #[test]
+    fn keyed_container_limits() {
+        use crate::crypto::AesKey;
+        use crate::Limits;
+
+        let partitions = || vec![Cursor::new(b"hello io".to_vec()), Cursor::new(b"part2".to_vec())];
+        let key = AesKey::new(&[0x42; 32]);
+        let mut container = IoStoreReader::with_key(Cursor::new(write_toc()), partitions(), key.clone()).unwrap();
+        assert_eq!(container.read_entry("Game/a.txt").unwrap(), b"hello io");
+
+        let limits = Limits { max_buffer_bytes: 16, ..Limits::DEFAULT };
+        let limited = IoStoreReader::with_key_and_limits(Cursor::new(write_toc()), partitions(), key, limits);
+        assert!(matches!(limited, Err(ReadError::AllocationLimit { limit: 16, .. })));
+    }
+}
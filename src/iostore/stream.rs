@@ -0,0 +1,127 @@
+use std::io::{self, Read, Seek};
+
+use alloc::vec::{self, Vec};
+use core::cmp;
+
+use crate::compression::{self, CompressionMethod};
+use crate::iostore::CompressedBlockEntry;
+use crate::pak::{read_region, AesKey};
+use crate::{Limits, ReadError, ReaderResult};
+
+/// A reader over the data of one chunk of an IoStore container, returned by
+/// [`IoStoreReader::open_chunk`](crate::iostore::IoStoreReader::open_chunk).
+///
+/// Compression blocks are read, decrypted and decompressed one at a time as the data
+/// is read. Chunk hashes are not checked.
+pub struct IoChunkReader<'a, R> {
+    partitions: &'a mut [R],
+    partition_lengths: &'a [u64],
+    partition_size: u64,
+    limits: Limits,
+    blocks: vec::IntoIter<(CompressedBlockEntry, CompressionMethod)>,
+    /// The bytes to drop from the start of the next block.
+    skip: u64,
+    length: u64,
+    remaining: u64,
+    buffer: Vec<u8>,
+    position: usize,
+    key: Option<&'a AesKey>,
+}
+
+impl<'a, R: Read + Seek> IoChunkReader<'a, R> {
+
+    /// `blocks` are the compression blocks the chunk's data spans, the first `skip`
+    /// bytes of which come before it.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        partitions: &'a mut [R],
+        partition_lengths: &'a [u64],
+        partition_size: u64,
+        limits: Limits,
+        blocks: Vec<(CompressedBlockEntry, CompressionMethod)>,
+        skip: u64,
+        length: u64,
+        key: Option<&'a AesKey>,
+    ) -> Self {
+        Self {
+            partitions,
+            partition_lengths,
+            partition_size,
+            limits,
+            blocks: blocks.into_iter(),
+            skip,
+            length,
+            remaining: length,
+            buffer: Vec::new(),
+            position: 0,
+            key,
+        }
+    }
+
+    /// Returns the size of the chunk's data.
+    #[inline]
+    pub fn len(&self) -> u64 {
+        self.length
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    /// Reads and decompresses the next block into the buffer, returning false at the end
+    /// of the data.
+    fn next_block(&mut self) -> ReaderResult<bool> {
+        if self.remaining == 0 {
+            return Ok(false);
+        }
+        let Some((block, method)) = self.blocks.next() else {
+            return Err(ReadError::UnexpectedEof { offset: None });
+        };
+
+        // Partitions are addressed as if laid end to end, each `partition_size` long.
+        let partition = (block.offset / self.partition_size) as usize;
+        let offset = block.offset % self.partition_size;
+        let (Some(inner), Some(&length)) = (self.partitions.get_mut(partition), self.partition_lengths.get(partition)) else {
+            return Err(ReadError::UnexpectedEof { offset: Some(block.offset) });
+        };
+
+        let size = u64::from(block.compressed_size);
+        let padded_size = if self.key.is_some() { size.next_multiple_of(16) } else { size };
+        let mut stored = read_region(inner, &self.limits, length, offset, padded_size)?;
+        if let Some(key) = self.key {
+            key.decrypt(&mut stored);
+            stored.truncate(size as usize);
+        }
+
+        let mut data = compression::decompress(method, &stored, block.uncompressed_size as usize)
+            .map_err(|e| e.at(block.offset))?;
+        let start = cmp::min(self.skip, data.len() as u64);
+        let end = cmp::min(data.len() as u64, start + self.remaining);
+        data.truncate(end as usize);
+        self.buffer = data;
+        self.position = start as usize;
+        self.remaining -= end - start;
+        self.skip = 0;
+        Ok(true)
+    }
+
+}
+
+impl<R: Read + Seek> Read for IoChunkReader<'_, R> {
+
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.buffer.len() {
+            if !self.next_block()? {
+                return Ok(0);
+            }
+        }
+
+        let available = &self.buffer[self.position..];
+        let length = cmp::min(buffer.len(), available.len());
+        buffer[..length].copy_from_slice(&available[..length]);
+        self.position += length;
+        Ok(length)
+    }
+
+}
@@ -0,0 +1,404 @@
+use byteorder::LittleEndian;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::compression::CompressionMethod;
+use crate::types::FGuid;
+use crate::{ReadError, ReadExt, ReadFrom, ReaderResult, SliceReader};
+
+/// The header of a `.utoc` file, `FIoStoreTocHeader` in Unreal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TocHeader {
+    pub version: u8,
+    pub entry_count: u32,
+    pub compressed_block_entry_count: u32,
+    pub compression_method_name_count: u32,
+    pub compression_method_name_length: u32,
+    /// The uncompressed size of every compression block but the last of a chunk.
+    pub compression_block_size: u32,
+    pub directory_index_size: u32,
+    /// The number of `.ucas` partitions, 1 before partitions were added.
+    pub partition_count: u32,
+    pub container_id: u64,
+    /// The GUID of the key the container is encrypted with.
+    pub encryption_key_guid: FGuid,
+    pub container_flags: u8,
+    pub perfect_hash_seeds_count: u32,
+    /// The maximum size of a partition, `u64::MAX` before partitions were added.
+    pub partition_size: u64,
+    pub chunks_without_perfect_hash_count: u32,
+}
+
+impl TocHeader {
+
+    pub const MAGIC: [u8; 16] = *b"-==--==--==--==-";
+    pub const SIZE: u32 = 144;
+
+    pub const VERSION_INITIAL: u8 = 1;
+    pub const VERSION_DIRECTORY_INDEX: u8 = 2;
+    pub const VERSION_PARTITION_SIZE: u8 = 3;
+    pub const VERSION_PERFECT_HASH: u8 = 4;
+    pub const VERSION_PERFECT_HASH_WITH_OVERFLOW: u8 = 5;
+    pub const VERSION_ON_DEMAND_META_DATA: u8 = 6;
+    pub const VERSION_REMOVED_ON_DEMAND_META_DATA: u8 = 7;
+    pub const VERSION_REPLACE_IO_CHUNK_HASH_WITH_IO_HASH: u8 = 8;
+    pub const VERSION_LATEST: u8 = 8;
+
+    pub const FLAG_COMPRESSED: u8 = 0x01;
+    pub const FLAG_ENCRYPTED: u8 = 0x02;
+    pub const FLAG_SIGNED: u8 = 0x04;
+    pub const FLAG_INDEXED: u8 = 0x08;
+    pub const FLAG_ON_DEMAND: u8 = 0x10;
+
+    /// The size of a compression block entry, which the header repeats as a check.
+    const COMPRESSED_BLOCK_ENTRY_SIZE: u32 = 12;
+
+    #[inline]
+    pub fn is_encrypted(&self) -> bool {
+        self.container_flags & Self::FLAG_ENCRYPTED != 0
+    }
+
+    #[inline]
+    pub fn is_signed(&self) -> bool {
+        self.container_flags & Self::FLAG_SIGNED != 0
+    }
+
+    #[inline]
+    pub fn is_indexed(&self) -> bool {
+        self.container_flags & Self::FLAG_INDEXED != 0
+    }
+
+    pub(crate) fn read(reader: &mut SliceReader<'_>) -> ReaderResult<Self> {
+        let start = reader.offset();
+        let magic = reader.read_bytes(Self::MAGIC.len())?;
+        if magic != Self::MAGIC {
+            let found = u32::from_le_bytes(magic[..4].try_into().unwrap());
+            return Err(ReadError::InvalidMagic { found, offset: start });
+        }
+
+        let version_offset = reader.offset();
+        let version = reader.read_num::<LittleEndian, u8>()?;
+        if !(Self::VERSION_INITIAL..=Self::VERSION_LATEST).contains(&version) {
+            return Err(ReadError::UnsupportedVersion { version: version.into(), offset: version_offset });
+        }
+        let _reserved = reader.read_num::<LittleEndian, u8>()?;
+        let _reserved = reader.read_u16_le()?;
+
+        let header_size = reader.read_u32_le()?;
+        let entry_count = reader.read_u32_le()?;
+        let compressed_block_entry_count = reader.read_u32_le()?;
+        let compressed_block_entry_size = reader.read_u32_le()?;
+        if header_size != Self::SIZE || compressed_block_entry_size != Self::COMPRESSED_BLOCK_ENTRY_SIZE {
+            return Err(ReadError::UnsupportedVersion { version: version.into(), offset: version_offset });
+        }
+
+        let compression_method_name_count = reader.read_u32_le()?;
+        let compression_method_name_length = reader.read_u32_le()?;
+        let compression_block_size = reader.read_u32_le()?;
+        let directory_index_size = reader.read_u32_le()?;
+        let partition_count = reader.read_u32_le()?;
+        let container_id = reader.read_u64_le()?;
+        let encryption_key_guid = FGuid::read_from::<LittleEndian, _>(reader)?;
+        let container_flags = reader.read_num::<LittleEndian, u8>()?;
+        let _reserved = reader.read_num::<LittleEndian, u8>()?;
+        let _reserved = reader.read_u16_le()?;
+        let perfect_hash_seeds_count = reader.read_u32_le()?;
+        let partition_size = reader.read_u64_le()?;
+        let chunks_without_perfect_hash_count = reader.read_u32_le()?;
+        let _reserved = reader.read_u32_le()?;
+        let _reserved = reader.read_bytes(5 * 8)?;
+
+        let (partition_count, partition_size) = if version < Self::VERSION_PARTITION_SIZE {
+            (1, u64::MAX)
+        } else {
+            (partition_count, partition_size)
+        };
+
+        Ok(Self {
+            version,
+            entry_count,
+            compressed_block_entry_count,
+            compression_method_name_count,
+            compression_method_name_length,
+            compression_block_size,
+            directory_index_size,
+            partition_count,
+            container_id,
+            encryption_key_guid,
+            container_flags,
+            perfect_hash_seeds_count,
+            partition_size,
+            chunks_without_perfect_hash_count,
+        })
+    }
+
+}
+
+/// The ID of a chunk in an IoStore container, `FIoChunkId` in Unreal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ChunkId {
+    /// The ID of what the chunk belongs to, such as the package ID of export data.
+    pub id: u64,
+    pub index: u16,
+    pub chunk_type: u8,
+}
+
+impl ChunkId {
+
+    pub const TYPE_INVALID: u8 = 0;
+    pub const TYPE_EXPORT_BUNDLE_DATA: u8 = 1;
+    pub const TYPE_BULK_DATA: u8 = 2;
+    pub const TYPE_OPTIONAL_BULK_DATA: u8 = 3;
+    pub const TYPE_MEMORY_MAPPED_BULK_DATA: u8 = 4;
+    pub const TYPE_SCRIPT_OBJECTS: u8 = 5;
+    pub const TYPE_CONTAINER_HEADER: u8 = 6;
+    pub const TYPE_EXTERNAL_FILE: u8 = 7;
+    pub const TYPE_SHADER_CODE_LIBRARY: u8 = 8;
+    pub const TYPE_SHADER_CODE: u8 = 9;
+    pub const TYPE_PACKAGE_STORE_ENTRY: u8 = 10;
+    pub const TYPE_DERIVED_DATA: u8 = 11;
+    pub const TYPE_EDITOR_DERIVED_DATA: u8 = 12;
+    pub const TYPE_PACKAGE_RESOURCE: u8 = 13;
+
+    pub const fn new(id: u64, index: u16, chunk_type: u8) -> Self {
+        Self { id, index, chunk_type }
+    }
+
+    /// Returns the 12 bytes the ID is saved as, with its index in big endian.
+    pub fn to_bytes(&self) -> [u8; 12] {
+        let mut bytes = [0; 12];
+        bytes[..8].copy_from_slice(&self.id.to_le_bytes());
+        bytes[8..10].copy_from_slice(&self.index.to_be_bytes());
+        bytes[11] = self.chunk_type;
+        bytes
+    }
+
+    pub(crate) fn read(reader: &mut SliceReader<'_>) -> ReaderResult<Self> {
+        let id = reader.read_u64_le()?;
+        let index = reader.read_u16_be()?;
+        let _padding = reader.read_num::<LittleEndian, u8>()?;
+        let chunk_type = reader.read_num::<LittleEndian, u8>()?;
+        Ok(Self { id, index, chunk_type })
+    }
+
+}
+
+/// Prints the saved bytes in hex, as Unreal does.
+impl fmt::Display for ChunkId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.to_bytes().iter().try_for_each(|b| write!(f, "{:02x}", b))
+    }
+}
+
+/// Where the data of a chunk lies in the uncompressed data of its container,
+/// `FIoOffsetAndLength` in Unreal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OffsetAndLength {
+    pub offset: u64,
+    pub length: u64,
+}
+
+impl OffsetAndLength {
+
+    /// Reads the offset and the length, each saved in 5 bytes big endian.
+    pub(crate) fn read(reader: &mut SliceReader<'_>) -> ReaderResult<Self> {
+        let bytes = reader.read_bytes(10)?;
+        let read_u40 = |bytes: &[u8]| bytes.iter().fold(0, |value, &b| value << 8 | u64::from(b));
+        Ok(Self { offset: read_u40(&bytes[..5]), length: read_u40(&bytes[5..]) })
+    }
+
+}
+
+/// A compression block of a container, `FIoStoreTocCompressedBlockEntry` in Unreal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressedBlockEntry {
+    /// The offset of the block in the container's partitions laid end to end, each
+    /// [`partition_size`](TocHeader::partition_size) long.
+    pub offset: u64,
+    pub compressed_size: u32,
+    pub uncompressed_size: u32,
+    /// The compression method, an index into
+    /// [`compression_methods`](Toc::compression_methods) counted from 1, or 0 for none.
+    pub compression_method: u8,
+}
+
+impl CompressedBlockEntry {
+
+    /// Reads the packed layout: a 5 byte offset, 3 byte sizes and a 1 byte method, all
+    /// little endian.
+    pub(crate) fn read(reader: &mut SliceReader<'_>) -> ReaderResult<Self> {
+        let bytes = reader.read_bytes(12)?;
+        let read_le = |bytes: &[u8]| bytes.iter().rev().fold(0, |value, &b| value << 8 | u64::from(b));
+        Ok(Self {
+            offset: read_le(&bytes[..5]),
+            compressed_size: read_le(&bytes[5..8]) as u32,
+            uncompressed_size: read_le(&bytes[8..11]) as u32,
+            compression_method: bytes[11],
+        })
+    }
+
+}
+
+/// The metadata of a chunk, `FIoStoreTocEntryMeta` in Unreal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkMeta {
+    /// The hash of the chunk's uncompressed data.
+    pub hash: [u8; 20],
+    pub flags: u8,
+}
+
+impl ChunkMeta {
+
+    pub const FLAG_COMPRESSED: u8 = 0x01;
+    pub const FLAG_MEMORY_MAPPED: u8 = 0x02;
+
+    fn read(reader: &mut SliceReader<'_>, version: u8) -> ReaderResult<Self> {
+        let mut hash = [0; 20];
+        reader.read_exact_bytes(&mut hash)?;
+        if version < TocHeader::VERSION_REPLACE_IO_CHUNK_HASH_WITH_IO_HASH {
+            // The older hash type kept 20 bytes in a 32 byte field.
+            let _padding = reader.read_bytes(12)?;
+        }
+        Ok(Self { hash, flags: reader.read_num::<LittleEndian, u8>()? })
+    }
+
+}
+
+/// The table of contents of an IoStore container, the `.utoc` file, which locates
+/// every chunk in the `.ucas` partitions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Toc {
+    pub header: TocHeader,
+    pub chunk_ids: Vec<ChunkId>,
+    /// The location of every chunk, in the order of [`chunk_ids`](Toc::chunk_ids).
+    pub chunk_offset_lengths: Vec<OffsetAndLength>,
+    pub chunk_perfect_hash_seeds: Vec<i32>,
+    pub chunks_without_perfect_hash: Vec<i32>,
+    pub compression_blocks: Vec<CompressedBlockEntry>,
+    pub compression_methods: Vec<String>,
+    /// The directory index as saved, encrypted if the container is.
+    pub directory_index: Vec<u8>,
+    /// The metadata of every chunk, in the order of [`chunk_ids`](Toc::chunk_ids).
+    pub chunk_metas: Vec<ChunkMeta>,
+}
+
+impl Toc {
+
+    /// Reads a whole `.utoc` file. Signatures of signed containers are skipped.
+    pub fn read(reader: &mut SliceReader<'_>) -> ReaderResult<Self> {
+        let header = reader.with_context("Header", TocHeader::read)?;
+        let entry_count = header.entry_count;
+        let block_count = header.compressed_block_entry_count;
+
+        let chunk_ids = reader.with_context("ChunkIds", |r| read_entries(r, entry_count, ChunkId::read))?;
+        let chunk_offset_lengths =
+            reader.with_context("ChunkOffsetLengths", |r| read_entries(r, entry_count, OffsetAndLength::read))?;
+
+        let mut chunk_perfect_hash_seeds = Vec::new();
+        let mut chunks_without_perfect_hash = Vec::new();
+        if header.version >= TocHeader::VERSION_PERFECT_HASH {
+            chunk_perfect_hash_seeds = reader.with_context("ChunkPerfectHashSeeds", |r| {
+                read_entries(r, header.perfect_hash_seeds_count, |r| r.read_i32_le())
+            })?;
+        }
+        if header.version >= TocHeader::VERSION_PERFECT_HASH_WITH_OVERFLOW {
+            chunks_without_perfect_hash = reader.with_context("ChunksWithoutPerfectHash", |r| {
+                read_entries(r, header.chunks_without_perfect_hash_count, |r| r.read_i32_le())
+            })?;
+        }
+
+        let compression_blocks =
+            reader.with_context("CompressionBlocks", |r| read_entries(r, block_count, CompressedBlockEntry::read))?;
+        let compression_methods = reader.with_context("CompressionMethods", |r| {
+            let length = header.compression_method_name_length as usize;
+            read_entries(r, header.compression_method_name_count, |r| {
+                let name = r.read_bytes(length)?;
+                let length = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+                Ok(String::from_utf8_lossy(&name[..length]).into_owned())
+            })
+        })?;
+
+        if header.is_signed() {
+            reader.with_context("Signatures", |r| {
+                let offset = r.offset();
+                let hash_size = r.read_i32_le()?;
+                let hash_size = usize::try_from(hash_size)
+                    .map_err(|_| ReadError::NegativeLength { length: hash_size.into(), offset })?;
+                let _toc_signature = r.read_bytes(hash_size)?;
+                let _block_signature = r.read_bytes(hash_size)?;
+                read_entries(r, block_count, |r| r.read_bytes(20).map(drop))
+            })?;
+        }
+
+        let mut directory_index = Vec::new();
+        if header.version >= TocHeader::VERSION_DIRECTORY_INDEX && header.is_indexed() && header.directory_index_size > 0 {
+            let size = header.directory_index_size as usize;
+            directory_index = reader.with_context("DirectoryIndex", |r| r.read_bytes(size))?.to_vec();
+        }
+
+        let chunk_metas =
+            reader.with_context("ChunkMetas", |r| read_entries(r, entry_count, |r| ChunkMeta::read(r, header.version)))?;
+
+        Ok(Self {
+            header,
+            chunk_ids,
+            chunk_offset_lengths,
+            chunk_perfect_hash_seeds,
+            chunks_without_perfect_hash,
+            compression_blocks,
+            compression_methods,
+            directory_index,
+            chunk_metas,
+        })
+    }
+
+    /// Resolves the compression method of a block, an index into
+    /// [`compression_methods`](Toc::compression_methods) counted from 1.
+    pub fn compression_method(&self, stored: u8) -> ReaderResult<CompressionMethod> {
+        if stored == 0 {
+            return Ok(CompressionMethod::None);
+        }
+
+        let name = self.compression_methods.get(usize::from(stored) - 1).map(String::as_str).unwrap_or_default();
+        CompressionMethod::from_name(name)
+            .ok_or_else(|| ReadError::UnsupportedCompression { method: name.into(), offset: None })
+    }
+
+}
+
+/// Reads `count` entries of a table whose length the header gives.
+fn read_entries<'a, T>(
+    reader: &mut SliceReader<'a>,
+    count: u32,
+    read: impl FnMut(&mut SliceReader<'a>) -> ReaderResult<T>,
+) -> ReaderResult<Vec<T>> {
+    let length = i32::try_from(count).map_err(|_| ReadError::LengthOverflow { length: count.into(), offset: reader.offset() })?;
+    reader.read_array_with_length(read, length)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::iostore::{ChunkId, CompressedBlockEntry, OffsetAndLength};
+    use crate::SliceReader;
+
+    #[test]
+    fn read_packed_entries() {
+        let data = [
+            0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x00, 0x02, 0x00, 0x01,
+            0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x02, 0x10,
+            0x00, 0x00, 0x01, 0x00, 0x00, 0x34, 0x12, 0x00, 0x00, 0x00, 0x01, 0x02,
+        ];
+        let mut reader = SliceReader::new(&data);
+
+        let id = ChunkId::read(&mut reader).unwrap();
+        assert_eq!(id, ChunkId::new(0x8877665544332211, 2, ChunkId::TYPE_EXPORT_BUNDLE_DATA));
+        assert_eq!(id.to_string(), "112233445566778800020001");
+        assert_eq!(OffsetAndLength::read(&mut reader).unwrap(), OffsetAndLength { offset: 0x100, length: 0x210 });
+        assert_eq!(
+            CompressedBlockEntry::read(&mut reader).unwrap(),
+            CompressedBlockEntry { offset: 0x10000, compressed_size: 0x1234, uncompressed_size: 0x10000, compression_method: 2 },
+        );
+    }
+}
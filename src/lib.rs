@@ -1,164 +1,485 @@
-use byteorder::{ReadBytesExt, BigEndian, LittleEndian};
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::result;
+
+use types::{FGuid, FName, NameTable, RawFName};
+
+#[cfg(feature = "tokio")]
+mod async_read;
+mod collections;
+pub mod compression;
+#[cfg(feature = "aes")]
+pub mod crypto;
+mod endian;
+mod error;
+mod fstring;
+mod limits;
+mod primitive;
+mod reader;
+mod serialize;
+mod slice;
+mod source;
+mod version;
+#[cfg(feature = "std")]
+mod write;
+
+#[cfg(feature = "std")]
+pub mod iostore;
+pub mod package;
+#[cfg(feature = "std")]
+pub mod pak;
+pub mod property;
+pub mod types;
+pub mod usmap;
+
+pub use byteorder::{ByteOrder, BigEndian, LittleEndian, BE, LE};
+
+#[cfg(feature = "tokio")]
+pub use async_read::{AsyncUnrealReadExt, ReadFuture};
+pub use collections::{DuplicateKeys, MapCollection, SetCollection};
+pub use endian::Endian;
+pub use error::{PathSegment, ReadError};
+pub use fstring::StringEncoding;
+pub use limits::Limits;
+pub use primitive::Primitive;
+pub use reader::Reader;
+pub use serialize::ReadFrom;
+#[cfg(feature = "std")]
+pub use serialize::WriteTo;
+pub use slice::SliceReader;
+pub use source::ByteSource;
+pub use version::{ArchiveVersion, CustomVersion, EditorObjectVersion, ObjectVersion, ObjectVersionUE5, PackageFileVersion};
+#[cfg(feature = "std")]
+pub use write::{WriteExt, Writer, WriterResult};
+
+#[cfg(feature = "derive")]
+pub use thoo_readext_derive::{ReadFrom, WriteTo};
+
+#[doc(hidden)]
+pub use serialize::__private;
+
+pub type ReaderResult<T> = result::Result<T, ReadError>;
 
-use std::result;
-use std::error;
-use std::io;
+pub trait ReadExt {
 
-pub type ReaderResult<T> = result::Result<T, Box<dyn error::Error>>;
+    /// Fills `buffer` completely from the stream.
+    fn read_exact_bytes(&mut self, buffer: &mut [u8]) -> ReaderResult<()>;
 
-pub trait ReadExt {
+    /// Returns the current byte offset in the stream, if the reader tracks it.
+    #[inline]
+    fn offset(&self) -> Option<u64> {
+        None
+    }
 
-    fn read_array<T, F>(&mut self, serialize: F) -> ReaderResult<Vec<T>>
-    where F: Fn(&mut Self) -> T;
+    /// Returns the number of bytes left in the stream, if known.
+    #[inline]
+    fn remaining(&self) -> Option<u64> {
+        None
+    }
 
-    fn read_array_be<T, F>(&mut self, serialize: F) -> ReaderResult<Vec<T>>
-    where F: Fn(&mut Self) -> T;
+    /// Returns the limits checked before allocating from a length prefix.
+    #[inline]
+    fn limits(&self) -> &Limits {
+        &Limits::DEFAULT
+    }
 
-    fn read_array_with_length<T, F>(&mut self, serialize: F, length: i32) -> ReaderResult<Vec<T>>
-    where F: Fn(&mut Self) -> T;
+    /// Records that `bytes` are about to be allocated, failing if that would exceed
+    /// [`Limits::max_total_bytes`] for the session.
+    #[inline]
+    fn allocate(&mut self, bytes: u64) -> ReaderResult<()> {
+        let _ = bytes;
+        Ok(())
+    }
 
-    fn read_fstring(&mut self) -> ReaderResult<String>;
+    /// Returns the number of bytes recorded by [`ReadExt::allocate`] so far.
+    #[inline]
+    fn allocated(&self) -> u64 {
+        0
+    }
 
-    fn read_i32_le(&mut self) -> ReaderResult<i32>;
-    fn read_u32_le(&mut self) -> ReaderResult<u32>;
+    /// Returns the byte order of data whose order is only known at runtime.
+    /// Plain readers are little-endian; see [`Reader::set_endian`] and [`with_endian!`].
+    #[inline]
+    fn endian(&self) -> Endian {
+        Endian::Little
+    }
 
-    fn read_i64_le(&mut self) -> ReaderResult<i64>;
-    fn read_u64_le(&mut self) -> ReaderResult<u64>;
+    /// Returns the version state of the data being read. Plain readers report
+    /// [`ArchiveVersion::UNVERSIONED`]; see [`Reader::set_version`].
+    #[inline]
+    fn version(&self) -> &ArchiveVersion {
+        &version::UNVERSIONED
+    }
 
-    fn read_i32_be(&mut self) -> ReaderResult<i32>;
-    fn read_u32_be(&mut self) -> ReaderResult<u32>;
+    #[inline]
+    fn ue_version(&self) -> PackageFileVersion {
+        self.version().package
+    }
 
-    fn read_i64_be(&mut self) -> ReaderResult<i64>;
-    fn read_u64_be(&mut self) -> ReaderResult<u64>;
+    #[inline]
+    fn licensee_version(&self) -> i32 {
+        self.version().licensee
+    }
 
-}
+    /// Returns the custom version registered for `key`, if any.
+    #[inline]
+    fn custom_version(&self, key: &FGuid) -> Option<i32> {
+        self.version().custom_version(key)
+    }
 
-impl<Impl> ReadExt for Impl
-where
-    Impl: ReadBytesExt + io::Read
-{
+    /// Runs `read` inside the scope `name`, which is added to the path of any error it returns.
+    ///
+    /// ```
+    /// use thoo_readext::{LittleEndian, ReadExt};
+    ///
+    /// let mut data: &[u8] = &[1, 0, 0, 0, 5, 0, 0, 0];
+    /// let error = data.with_context("ExportMap", |r| {
+    ///     r.read_array::<LittleEndian, _>(|r| r.with_context("ObjectName", |r| r.read_fstring::<LittleEndian>()))
+    /// }).unwrap_err();
+    /// assert_eq!(error.to_string(), "ExportMap[0].ObjectName: unexpected end of stream");
+    /// ```
+    #[inline]
+    fn with_context<T>(&mut self, name: &'static str, read: impl FnOnce(&mut Self) -> ReaderResult<T>) -> ReaderResult<T> {
+        read(self).map_err(|e| e.context(name))
+    }
 
+    /// Returns the name table FNames are resolved against. Plain readers have an empty
+    /// table; see [`Reader::set_names`].
     #[inline]
-    fn read_array<T, F>(&mut self, serialize: F) -> ReaderResult<Vec<T>>
-    where 
-        F: Fn(&mut Self) -> T 
-    {
-        let length = self.read_i32_le()?;
+    fn names(&self) -> &NameTable {
+        &types::EMPTY_NAMES
+    }
+
+    /// Reads an FName in the byte order `B` and resolves it against [`ReadExt::names`].
+    fn read_fname<B: ByteOrder>(&mut self) -> ReaderResult<FName> {
+        let offset = self.offset();
+        let raw = self.read_fname_raw::<B>()?;
+        self.names().resolve(raw).map_err(|e| e.located(offset))
+    }
+
+    /// Reads an FName in the byte order `B` without resolving it.
+    #[inline]
+    fn read_fname_raw<B: ByteOrder>(&mut self) -> ReaderResult<RawFName> {
+        RawFName::read_from::<B, _>(self)
+    }
+
+    /// Reads a primitive in the byte order `B`.
+    #[inline(always)]
+    fn read_num<B: ByteOrder, T: Primitive>(&mut self) -> ReaderResult<T> {
+        let mut buffer = [0u8; 16];
+        let bytes = &mut buffer[..T::SIZE];
+        self.read_exact_bytes(bytes)?;
+        Ok(T::from_bytes::<B>(bytes))
+    }
+
+    /// Reads an i32 length prefix in the byte order `B`, followed by that many elements.
+    #[inline]
+    fn read_array<B: ByteOrder, T>(&mut self, serialize: impl FnMut(&mut Self) -> ReaderResult<T>) -> ReaderResult<Vec<T>> {
+        let length = self.read_num::<B, i32>()?;
         self.read_array_with_length(serialize, length)
     }
 
+    /// Reads a TMap in the byte order `B` into any [`MapCollection`], handling keys that
+    /// appear more than once according to `policy`.
+    #[inline]
+    fn read_map<B: ByteOrder, M: MapCollection<K, V>, K, V>(
+        &mut self,
+        key: impl FnMut(&mut Self) -> ReaderResult<K>,
+        value: impl FnMut(&mut Self) -> ReaderResult<V>,
+        policy: DuplicateKeys,
+    ) -> ReaderResult<M> {
+        collections::read_map::<B, M, K, V, Self>(self, key, value, policy)
+    }
+
+    /// Reads a TSet in the byte order `B` into any [`SetCollection`], handling values that
+    /// appear more than once according to `policy`.
+    #[inline]
+    fn read_set<B: ByteOrder, S: SetCollection<T>, T>(
+        &mut self,
+        serialize: impl FnMut(&mut Self) -> ReaderResult<T>,
+        policy: DuplicateKeys,
+    ) -> ReaderResult<S> {
+        collections::read_set::<B, S, T, Self>(self, serialize, policy)
+    }
+
+    /// Reads a TSparseArray in the byte order `B`, with `None` for unallocated indices.
+    #[inline]
+    fn read_sparse_array<B: ByteOrder, T>(&mut self, serialize: impl FnMut(&mut Self) -> ReaderResult<T>) -> ReaderResult<Vec<Option<T>>> {
+        collections::read_sparse_array::<B, T, Self>(self, serialize)
+    }
+
+    #[deprecated(note = "use `read_array::<BigEndian, _>` instead")]
     #[inline(always)]
     fn read_array_be<T, F>(&mut self, serialize: F) -> ReaderResult<Vec<T>>
-    where 
-        F: Fn(&mut Self) -> T 
+    where
+        F: FnMut(&mut Self) -> ReaderResult<T>
     {
-        let length = self.read_i32::<BigEndian>()?;
-        self.read_array_with_length(serialize, length)
+        self.read_array::<BigEndian, T>(serialize)
     }
 
-    fn read_array_with_length<T, F>(&mut self, serialize: F, length: i32) -> ReaderResult<Vec<T>>
-    where 
-        F: Fn(&mut Self) -> T 
+    /// Reads `length` elements with `serialize`, stopping at the first element that fails.
+    fn read_array_with_length<T, F>(&mut self, mut serialize: F, length: i32) -> ReaderResult<Vec<T>>
+    where
+        F: FnMut(&mut Self) -> ReaderResult<T>
     {
-        let mut result = Vec::with_capacity(usize::try_from(length)?);
-        for _ in 0..length {
-            let item = serialize(self);
+        let count = limits::check_array_len(length, self.limits(), self.offset())?;
+        self.allocate(limits::array_bytes::<T>(count))?;
+
+        let mut result = Vec::with_capacity(limits::preallocation::<T>(count, self.remaining()));
+        for index in 0..count as usize {
+            let item = serialize(self).map_err(|e| e.index(index))?;
             result.push(item);
         }
 
         Ok(result)
     }
 
-    fn read_fstring(&mut self) -> ReaderResult<String> {
-        let length = self.read_i32_le()?;
-        if length == 0 {
-            return Ok(String::from(""));
-        }
-
-        if length < 0  {
-            if length == i32::MIN {
-                panic!("Invalid FString")
-            }
+    /// Reads an FString in the byte order `B`. A positive length prefix denotes a
+    /// null-terminated ANSI string, a negative one a null-terminated UTF-16 string of
+    /// `-length` code units.
+    #[inline]
+    fn read_fstring<B: ByteOrder>(&mut self) -> ReaderResult<String> {
+        fstring::read::<B, _>(self, false)
+    }
 
-            let len = -length * 2;
-            let mut buffer: Vec<u8> = vec![0; usize::try_from(len)?]; 
-            self.read_exact(buffer.as_mut_slice())?;
+    /// Like [`ReadExt::read_fstring`], but replaces invalid sequences with U+FFFD
+    /// instead of failing.
+    #[inline]
+    fn read_fstring_lossy<B: ByteOrder>(&mut self) -> ReaderResult<String> {
+        fstring::read::<B, _>(self, true)
+    }
 
-            // TODO
-            panic!("Unicode FString's are not supported yet.");
+    /// Reads an Unreal `bool`, stored as a 4 byte integer in the byte order `B`
+    /// that must be 0 or 1.
+    fn read_bool<B: ByteOrder>(&mut self) -> ReaderResult<bool> {
+        let offset = self.offset();
+        match self.read_num::<B, u32>()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            value => Err(ReadError::InvalidBool { value, offset }),
         }
+    }
 
-        let len = usize::try_from(length - 1)?;
-        let mut buffer = vec![0u8; usize::try_from(length)?];
-        self.read_exact(buffer.as_mut_slice())?;
+    #[inline(always)]
+    fn read_i16_le(&mut self) -> ReaderResult<i16> {
+        self.read_num::<LittleEndian, i16>()
+    }
 
-        Ok(String::from_utf8(buffer[0..len].to_vec())?)
+    #[inline(always)]
+    fn read_u16_le(&mut self) -> ReaderResult<u16> {
+        self.read_num::<LittleEndian, u16>()
     }
 
     #[inline(always)]
     fn read_i32_le(&mut self) -> ReaderResult<i32> {
-        Ok(self.read_i32::<LittleEndian>()?)
+        self.read_num::<LittleEndian, i32>()
     }
 
     #[inline(always)]
     fn read_u32_le(&mut self) -> ReaderResult<u32> {
-        Ok(self.read_u32::<LittleEndian>()?)
+        self.read_num::<LittleEndian, u32>()
     }
 
     #[inline(always)]
     fn read_i64_le(&mut self) -> ReaderResult<i64> {
-        Ok(self.read_i64::<LittleEndian>()?)
+        self.read_num::<LittleEndian, i64>()
     }
 
     #[inline(always)]
     fn read_u64_le(&mut self) -> ReaderResult<u64> {
-        Ok(self.read_u64::<LittleEndian>()?)
+        self.read_num::<LittleEndian, u64>()
+    }
+
+    #[inline(always)]
+    fn read_i128_le(&mut self) -> ReaderResult<i128> {
+        self.read_num::<LittleEndian, i128>()
+    }
+
+    #[inline(always)]
+    fn read_u128_le(&mut self) -> ReaderResult<u128> {
+        self.read_num::<LittleEndian, u128>()
+    }
+
+    #[inline(always)]
+    fn read_f32_le(&mut self) -> ReaderResult<f32> {
+        self.read_num::<LittleEndian, f32>()
+    }
+
+    #[inline(always)]
+    fn read_f64_le(&mut self) -> ReaderResult<f64> {
+        self.read_num::<LittleEndian, f64>()
+    }
+
+    #[inline(always)]
+    fn read_i16_be(&mut self) -> ReaderResult<i16> {
+        self.read_num::<BigEndian, i16>()
+    }
+
+    #[inline(always)]
+    fn read_u16_be(&mut self) -> ReaderResult<u16> {
+        self.read_num::<BigEndian, u16>()
     }
 
     #[inline(always)]
     fn read_i32_be(&mut self) -> ReaderResult<i32> {
-        Ok(self.read_i32::<BigEndian>()?)
+        self.read_num::<BigEndian, i32>()
     }
 
     #[inline(always)]
     fn read_u32_be(&mut self) -> ReaderResult<u32> {
-        Ok(self.read_u32::<BigEndian>()?)
+        self.read_num::<BigEndian, u32>()
     }
 
     #[inline(always)]
     fn read_i64_be(&mut self) -> ReaderResult<i64> {
-        Ok(self.read_i64::<BigEndian>()?)
+        self.read_num::<BigEndian, i64>()
     }
 
     #[inline(always)]
     fn read_u64_be(&mut self) -> ReaderResult<u64> {
-        Ok(self.read_u64::<BigEndian>()?)
+        self.read_num::<BigEndian, u64>()
+    }
+
+    #[inline(always)]
+    fn read_i128_be(&mut self) -> ReaderResult<i128> {
+        self.read_num::<BigEndian, i128>()
+    }
+
+    #[inline(always)]
+    fn read_u128_be(&mut self) -> ReaderResult<u128> {
+        self.read_num::<BigEndian, u128>()
+    }
+
+    #[inline(always)]
+    fn read_f32_be(&mut self) -> ReaderResult<f32> {
+        self.read_num::<BigEndian, f32>()
+    }
+
+    #[inline(always)]
+    fn read_f64_be(&mut self) -> ReaderResult<f64> {
+        self.read_num::<BigEndian, f64>()
+    }
+}
+
+impl<Impl> ReadExt for Impl
+where
+    Impl: ByteSource
+{
+
+    #[inline(always)]
+    fn read_exact_bytes(&mut self, buffer: &mut [u8]) -> ReaderResult<()> {
+        self.read_into(buffer)
     }
 
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use std::io::Cursor;
 
-    use byteorder::{ReadBytesExt, LittleEndian};
+    use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
 
-    use crate::ReadExt;
+    use crate::{PathSegment, ReadError, ReadExt};
 
     #[test]
     fn read_array() {
         let mut cursor = Cursor::new(vec![2, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0]);
-        let result = cursor.read_array(|r| r.read_i32::<LittleEndian>().unwrap()).unwrap();
+        let result = cursor.read_array::<LittleEndian, _>(|r| r.read_i32_le()).unwrap();
+
+        assert_eq!(result.as_slice(), &[3, 4]);
+    }
+
+    #[test]
+    fn read_array_big_endian() {
+        let mut cursor = Cursor::new(vec![0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 4]);
+        let result = cursor.read_array::<BigEndian, _>(|r| r.read_num::<BigEndian, i32>()).unwrap();
 
         assert_eq!(result.as_slice(), &[3, 4]);
     }
 
+    #[test]
+    fn read_primitives() {
+        let mut cursor = Cursor::new(vec![
+            0xFF, 0x34, 0x12, 0x12, 0x34, 0x00, 0x00, 0x80, 0x3F, 0x3F, 0xF0, 0, 0, 0, 0, 0, 0,
+            1, 0, 0, 0, 2, 0, 0, 0,
+        ]);
+
+        // Single bytes come from `ReadBytesExt`, which callers usually import as well.
+        assert_eq!(cursor.read_i8().unwrap(), -1);
+        assert_eq!(cursor.read_u16_le().unwrap(), 0x1234);
+        assert_eq!(cursor.read_u16_be().unwrap(), 0x1234);
+        assert_eq!(cursor.read_f32_le().unwrap(), 1.0);
+        assert_eq!(cursor.read_f64_be().unwrap(), 1.0);
+        assert!(cursor.read_bool::<LittleEndian>().unwrap());
+
+        let error = cursor.read_bool::<LittleEndian>().unwrap_err();
+        assert!(matches!(error, ReadError::InvalidBool { value: 2, .. }));
+    }
+
+    #[test]
+    fn read_array_fallible() {
+        let mut cursor = Cursor::new(vec![3, 0, 0, 0, 3, 0, 0, 0, 4, 0]);
+        let mut index = 0;
+        let error = cursor.read_array::<LittleEndian, _>(|r| {
+            index += 1;
+            r.read_i32_le()
+        }).unwrap_err();
+
+        assert!(matches!(error.root(), ReadError::UnexpectedEof { .. }));
+        assert_eq!(error.path(), [PathSegment::Index(1)]);
+        assert_eq!(index, 2);
+    }
+
     #[test]
     fn read_fstring() {
         let mut cursor = Cursor::new(vec![6u8, 0, 0, 0, 0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x00]);
-        let result = cursor.read_fstring().unwrap();
+        let result = cursor.read_fstring::<LittleEndian>().unwrap();
 
         assert_eq!(result, "Hello")
     }
 
+    #[test]
+    fn read_fstring_utf16() {
+        // "H\u{1F600}" followed by the null terminator, 4 UTF-16 code units.
+        let mut cursor = Cursor::new(vec![0xFC, 0xFF, 0xFF, 0xFF, 0x48, 0x00, 0x3D, 0xD8, 0x00, 0xDE, 0x00, 0x00]);
+        let result = cursor.read_fstring::<LittleEndian>().unwrap();
+
+        assert_eq!(result, "H\u{1F600}")
+    }
+
+    #[test]
+    fn read_fstring_utf16_lossy() {
+        // A lone high surrogate followed by the null terminator.
+        let data = vec![0xFE, 0xFF, 0xFF, 0xFF, 0x3D, 0xD8, 0x00, 0x00];
+        assert!(Cursor::new(data.clone()).read_fstring::<LittleEndian>().is_err());
+
+        let result = Cursor::new(data).read_fstring_lossy::<LittleEndian>().unwrap();
+        assert_eq!(result, "\u{FFFD}")
+    }
+
+    #[test]
+    fn read_fstring_missing_terminator() {
+        let mut cursor = Cursor::new(vec![0xFF, 0xFF, 0xFF, 0xFF, 0x48, 0x00]);
+        assert!(cursor.read_fstring::<LittleEndian>().is_err());
+    }
+
+    #[test]
+    fn read_error_kinds() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ReadError>();
+
+        let mut cursor = Cursor::new(vec![0xFF, 0xFF, 0xFF, 0xFF]);
+        let error = cursor.read_array::<LittleEndian, _>(|r| r.read_i32_le()).unwrap_err();
+        assert!(matches!(error, ReadError::NegativeLength { length: -1, .. }));
+
+        let mut cursor = Cursor::new(vec![4, 0, 0, 0, 0x48]);
+        let error = cursor.read_fstring::<LittleEndian>().unwrap_err();
+        assert!(matches!(error, ReadError::UnexpectedEof { .. }));
+        assert_eq!(error.at(4).offset(), Some(4));
+    }
+
 }
@@ -0,0 +1,152 @@
+use core::cmp;
+use core::mem;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{ReadError, ReadExt, ReaderResult};
+
+/// Upper bound on the bytes preallocated for an array. Larger arrays still load, growing
+/// as their elements are actually read.
+const MAX_PREALLOCATION: u64 = 1024 * 1024;
+
+/// Upper bounds on what a reader may allocate based on length prefixes read from the stream.
+///
+/// Limits are checked before anything is allocated, and a violation is reported as
+/// [`ReadError::AllocationLimit`](crate::ReadError::AllocationLimit).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    /// Maximum number of elements in a single array.
+    pub max_array_len: u64,
+    /// Maximum size in bytes of a single string, including its null terminator.
+    pub max_string_bytes: u64,
+    /// Maximum size in bytes of a single raw buffer, such as an opaque payload, a
+    /// compressed block or a whole file read from an archive.
+    pub max_buffer_bytes: u64,
+    /// Maximum number of bytes reserved by array and string buffers over the lifetime
+    /// of a [`Reader`](crate::Reader). Plain readers have no session and ignore this.
+    pub max_total_bytes: u64,
+}
+
+impl Limits {
+
+    pub const DEFAULT: Limits = Limits {
+        max_array_len: 64 * 1024 * 1024,
+        max_string_bytes: 16 * 1024 * 1024,
+        max_buffer_bytes: 256 * 1024 * 1024,
+        max_total_bytes: u64::MAX,
+    };
+
+    pub const UNLIMITED: Limits = Limits {
+        max_array_len: u64::MAX,
+        max_string_bytes: u64::MAX,
+        max_buffer_bytes: u64::MAX,
+        max_total_bytes: u64::MAX,
+    };
+
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits::DEFAULT
+    }
+}
+
+/// Validates an array length prefix against `limits`, returning the element count.
+pub(crate) fn check_array_len(length: i32, limits: &Limits, offset: Option<u64>) -> ReaderResult<u64> {
+    let count = u64::try_from(length)
+        .map_err(|_| ReadError::NegativeLength { length: length.into(), offset })?;
+
+    let limit = limits.max_array_len;
+    if count > limit {
+        return Err(ReadError::AllocationLimit { requested: count, limit, offset });
+    }
+
+    Ok(count)
+}
+
+/// Validates the payload size of a string against `limits` and the bytes left in the stream.
+pub(crate) fn check_string_size(size: u64, limits: &Limits, remaining: Option<u64>, offset: Option<u64>) -> ReaderResult<usize> {
+    let limit = limits.max_string_bytes;
+    if size > limit {
+        return Err(ReadError::AllocationLimit { requested: size, limit, offset });
+    }
+
+    if remaining.is_some_and(|remaining| remaining < size) {
+        return Err(ReadError::UnexpectedEof { offset });
+    }
+
+    usize::try_from(size).map_err(|_| ReadError::LengthOverflow { length: size as i64, offset })
+}
+
+/// Validates the size of a raw buffer against `limits`.
+pub(crate) fn check_buffer_size(size: u64, limits: &Limits, offset: Option<u64>) -> ReaderResult<()> {
+    let limit = limits.max_buffer_bytes;
+    if size > limit {
+        return Err(ReadError::AllocationLimit { requested: size, limit, offset });
+    }
+
+    Ok(())
+}
+
+/// Reads `size` bytes into a new buffer, checking `size` against the reader's limits
+/// and the bytes left in the stream before allocating.
+pub(crate) fn read_buffer<R: ReadExt + ?Sized>(reader: &mut R, size: u64) -> ReaderResult<Vec<u8>> {
+    let offset = reader.offset();
+    check_buffer_size(size, reader.limits(), offset)?;
+    if reader.remaining().is_some_and(|remaining| remaining < size) {
+        return Err(ReadError::UnexpectedEof { offset });
+    }
+    reader.allocate(size)?;
+
+    let length = usize::try_from(size).map_err(|_| ReadError::LengthOverflow { length: size as i64, offset })?;
+    let mut buffer = vec![0; length];
+    reader.read_exact_bytes(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// Returns the number of bytes an array of `count` elements of `T` reserves.
+#[inline]
+pub(crate) fn array_bytes<T>(count: u64) -> u64 {
+    count.saturating_mul(element_size::<T>())
+}
+
+/// Returns the capacity to reserve up front for `count` elements of `T`, bounded by
+/// [`MAX_PREALLOCATION`] and, when known, by the bytes left in the stream.
+#[inline]
+pub(crate) fn preallocation<T>(count: u64, remaining: Option<u64>) -> usize {
+    let size = element_size::<T>();
+    let bound = cmp::min(remaining.map_or(u64::MAX, |remaining| remaining / size), MAX_PREALLOCATION / size);
+    cmp::min(count, bound) as usize
+}
+
+#[inline]
+fn element_size<T>() -> u64 {
+    cmp::max(mem::size_of::<T>() as u64, 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{preallocation, read_buffer, MAX_PREALLOCATION};
+    use crate::{Limits, ReadError, Reader};
+
+    #[test]
+    fn preallocation_is_bounded_in_bytes() {
+        assert_eq!(preallocation::<u8>(10, Some(100)), 10);
+        assert_eq!(preallocation::<[u8; 64]>(1 << 30, Some(16 * 1024 * 1024)), (MAX_PREALLOCATION / 64) as usize);
+        assert_eq!(preallocation::<[u8; 64]>(1 << 30, Some(640)), 10);
+        assert_eq!(preallocation::<u32>(1 << 30, None), (MAX_PREALLOCATION / 4) as usize);
+    }
+
+    #[test]
+    fn buffers_are_limited_in_bytes() {
+        let data = [0u8; 16];
+        let limits = Limits { max_array_len: 1, max_buffer_bytes: 8, ..Limits::DEFAULT };
+        let mut reader = Reader::with_limits(&data[..], limits);
+
+        assert_eq!(read_buffer(&mut reader, 8).unwrap(), [0; 8]);
+        let error = read_buffer(&mut reader, 9).unwrap_err();
+        assert!(matches!(error, ReadError::AllocationLimit { requested: 9, limit: 8, offset: Some(8) }));
+    }
+
+}
@@ -0,0 +1,93 @@
+use byteorder::ByteOrder;
+
+use core::fmt;
+
+use crate::{ReadExt, ReadFrom, ReaderResult};
+#[cfg(feature = "std")]
+use crate::{WriteExt, WriteTo, WriterResult};
+
+/// A reference to an object of a package, `FPackageIndex` in Unreal.
+///
+/// Negative values refer to the import map, positive values to the export map, both
+/// offset by one, and zero is the null reference.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FPackageIndex(i32);
+
+impl FPackageIndex {
+
+    pub const NULL: FPackageIndex = FPackageIndex(0);
+
+    #[inline]
+    pub const fn new(value: i32) -> Self {
+        Self(value)
+    }
+
+    #[inline]
+    pub const fn from_import(index: usize) -> Self {
+        Self(-(index as i32) - 1)
+    }
+
+    #[inline]
+    pub const fn from_export(index: usize) -> Self {
+        Self(index as i32 + 1)
+    }
+
+    /// Returns the value as serialized.
+    #[inline]
+    pub const fn value(self) -> i32 {
+        self.0
+    }
+
+    #[inline]
+    pub const fn is_null(self) -> bool {
+        self.0 == 0
+    }
+
+    #[inline]
+    pub const fn is_import(self) -> bool {
+        self.0 < 0
+    }
+
+    #[inline]
+    pub const fn is_export(self) -> bool {
+        self.0 > 0
+    }
+
+    /// Returns the position in the import map, if this refers to an import.
+    #[inline]
+    pub const fn to_import(self) -> Option<usize> {
+        if self.is_import() { Some((-(self.0 as i64) - 1) as usize) } else { None }
+    }
+
+    /// Returns the position in the export map, if this refers to an export.
+    #[inline]
+    pub const fn to_export(self) -> Option<usize> {
+        if self.is_export() { Some(self.0 as usize - 1) } else { None }
+    }
+
+}
+
+impl ReadFrom for FPackageIndex {
+    #[inline]
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        reader.read_num::<B, i32>().map(FPackageIndex)
+    }
+}
+
+#[cfg(feature = "std")]
+impl WriteTo for FPackageIndex {
+    #[inline]
+    fn write_to<B: ByteOrder, W: WriteExt + ?Sized>(&self, writer: &mut W) -> WriterResult<()> {
+        writer.write_num::<B, i32>(self.0)
+    }
+}
+
+impl fmt::Debug for FPackageIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.to_import(), self.to_export()) {
+            (Some(index), _) => write!(f, "Import({})", index),
+            (_, Some(index)) => write!(f, "Export({})", index),
+            _ => f.write_str("Null"),
+        }
+    }
+}
@@ -0,0 +1,17 @@
+//! Package structures: legacy `.uasset`/`.umap` packages, and the Zen packages stored
+//! in IoStore containers.
+
+mod index;
+mod object;
+mod resolve;
+mod summary;
+mod zen;
+
+pub use index::FPackageIndex;
+pub use object::{FObjectExport, FObjectImport};
+pub use resolve::{ObjectRef, ObjectResolver};
+pub use summary::{CompressedChunk, EngineVersion, GenerationInfo, PackageFileSummary};
+pub use zen::{
+    FDependencyBundleHeader, FExportBundleEntry, FExportMapEntry, FPackageObjectIndex, ScriptObjectEntry, ScriptObjects,
+    ZenPackageHeader, ZenPackageSummary, ZenPackageVersioningInfo,
+};
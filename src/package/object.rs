@@ -0,0 +1,260 @@
+use byteorder::ByteOrder;
+
+use alloc::vec::Vec;
+
+use crate::package::{FPackageIndex, PackageFileSummary};
+use crate::types::{FGuid, FName};
+use crate::{with_endian, ObjectVersion, ObjectVersionUE5, ReadExt, ReadFrom, ReaderResult};
+
+/// An object a package depends on, `FObjectImport` in Unreal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FObjectImport {
+    pub class_package: FName,
+    pub class_name: FName,
+    pub outer_index: FPackageIndex,
+    pub object_name: FName,
+    /// The package the import lives in when it is not its outermost object, only saved
+    /// in editor packages.
+    pub package_name: Option<FName>,
+    pub import_optional: bool,
+}
+
+impl FObjectImport {
+
+    /// Reads an import with the reader's versions. `filter_editor_only` comes from
+    /// [`PackageFileSummary::filter_editor_only`].
+    pub fn read<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R, filter_editor_only: bool) -> ReaderResult<Self> {
+        let version = reader.ue_version();
+
+        Ok(Self {
+            class_package: reader.with_context("ClassPackage", |r| r.read_fname::<B>())?,
+            class_name: reader.with_context("ClassName", |r| r.read_fname::<B>())?,
+            outer_index: reader.with_context("OuterIndex", |r| FPackageIndex::read_from::<B, _>(r))?,
+            object_name: reader.with_context("ObjectName", |r| r.read_fname::<B>())?,
+            package_name: if !filter_editor_only && version.ue4 >= ObjectVersion::NON_OUTER_PACKAGE_IMPORT {
+                Some(reader.with_context("PackageName", |r| r.read_fname::<B>())?)
+            } else {
+                None
+            },
+            import_optional: if version.ue5 >= ObjectVersionUE5::OPTIONAL_RESOURCES {
+                reader.with_context("bImportOptional", |r| r.read_bool::<B>())?
+            } else {
+                false
+            },
+        })
+    }
+
+}
+
+/// An object saved in a package, `FObjectExport` in Unreal.
+///
+/// The dependency counts are `-1` in packages saved before preload dependencies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FObjectExport {
+    pub class_index: FPackageIndex,
+    pub super_index: FPackageIndex,
+    pub template_index: FPackageIndex,
+    pub outer_index: FPackageIndex,
+    pub object_name: FName,
+    pub object_flags: u32,
+    pub serial_size: i64,
+    pub serial_offset: i64,
+    pub forced_export: bool,
+    pub not_for_client: bool,
+    pub not_for_server: bool,
+    pub package_guid: FGuid,
+    pub is_inherited_instance: bool,
+    pub package_flags: u32,
+    pub not_always_loaded_for_editor_game: bool,
+    pub is_asset: bool,
+    pub generate_public_hash: bool,
+    pub first_export_dependency: i32,
+    pub serialization_before_serialization_dependencies: i32,
+    pub create_before_serialization_dependencies: i32,
+    pub serialization_before_create_dependencies: i32,
+    pub create_before_create_dependencies: i32,
+    pub script_serialization_start_offset: i64,
+    pub script_serialization_end_offset: i64,
+}
+
+impl ReadFrom for FObjectExport {
+    /// Reads an export with the reader's versions.
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        let version = reader.ue_version();
+
+        let class_index = reader.with_context("ClassIndex", |r| FPackageIndex::read_from::<B, _>(r))?;
+        let super_index = reader.with_context("SuperIndex", |r| FPackageIndex::read_from::<B, _>(r))?;
+        let template_index = if version.ue4 >= ObjectVersion::TEMPLATE_INDEX_IN_COOKED_EXPORTS {
+            reader.with_context("TemplateIndex", |r| FPackageIndex::read_from::<B, _>(r))?
+        } else {
+            FPackageIndex::NULL
+        };
+        let outer_index = reader.with_context("OuterIndex", |r| FPackageIndex::read_from::<B, _>(r))?;
+        let object_name = reader.with_context("ObjectName", |r| r.read_fname::<B>())?;
+        let object_flags = reader.with_context("ObjectFlags", |r| r.read_num::<B, u32>())?;
+
+        let (serial_size, serial_offset) = if version.ue4 >= ObjectVersion::SIXTY_FOUR_BIT_EXPORTMAP_SERIALSIZES {
+            (
+                reader.with_context("SerialSize", |r| r.read_num::<B, i64>())?,
+                reader.with_context("SerialOffset", |r| r.read_num::<B, i64>())?,
+            )
+        } else {
+            (
+                reader.with_context("SerialSize", |r| r.read_num::<B, i32>())? as i64,
+                reader.with_context("SerialOffset", |r| r.read_num::<B, i32>())? as i64,
+            )
+        };
+
+        let forced_export = reader.with_context("bForcedExport", |r| r.read_bool::<B>())?;
+        let not_for_client = reader.with_context("bNotForClient", |r| r.read_bool::<B>())?;
+        let not_for_server = reader.with_context("bNotForServer", |r| r.read_bool::<B>())?;
+        let package_guid = if version.ue5 < ObjectVersionUE5::REMOVE_OBJECT_EXPORT_PACKAGE_GUID {
+            reader.with_context("PackageGuid", |r| FGuid::read_from::<B, _>(r))?
+        } else {
+            FGuid::default()
+        };
+        let is_inherited_instance = version.ue5 >= ObjectVersionUE5::TRACK_OBJECT_EXPORT_IS_INHERITED
+            && reader.with_context("bIsInheritedInstance", |r| r.read_bool::<B>())?;
+        let package_flags = reader.with_context("PackageFlags", |r| r.read_num::<B, u32>())?;
+        let not_always_loaded_for_editor_game = version.ue4 >= ObjectVersion::LOAD_FOR_EDITOR_GAME
+            && reader.with_context("bNotAlwaysLoadedForEditorGame", |r| r.read_bool::<B>())?;
+        let is_asset = version.ue4 >= ObjectVersion::COOKED_ASSETS_IN_EDITOR_SUPPORT
+            && reader.with_context("bIsAsset", |r| r.read_bool::<B>())?;
+        let generate_public_hash = version.ue5 >= ObjectVersionUE5::OPTIONAL_RESOURCES
+            && reader.with_context("bGeneratePublicHash", |r| r.read_bool::<B>())?;
+
+        let mut dependencies = [-1; 5];
+        if version.ue4 >= ObjectVersion::PRELOAD_DEPENDENCIES_IN_COOKED_EXPORTS {
+            for dependency in &mut dependencies {
+                *dependency = reader.with_context("Dependencies", |r| r.read_num::<B, i32>())?;
+            }
+        }
+        let [
+            first_export_dependency,
+            serialization_before_serialization_dependencies,
+            create_before_serialization_dependencies,
+            serialization_before_create_dependencies,
+            create_before_create_dependencies,
+        ] = dependencies;
+
+        let (script_serialization_start_offset, script_serialization_end_offset) =
+            if version.ue5 >= ObjectVersionUE5::SCRIPT_SERIALIZATION_OFFSET {
+                (
+                    reader.with_context("ScriptSerializationStartOffset", |r| r.read_num::<B, i64>())?,
+                    reader.with_context("ScriptSerializationEndOffset", |r| r.read_num::<B, i64>())?,
+                )
+            } else {
+                (0, 0)
+            };
+
+        Ok(Self {
+            class_index,
+            super_index,
+            template_index,
+            outer_index,
+            object_name,
+            object_flags,
+            serial_size,
+            serial_offset,
+            forced_export,
+            not_for_client,
+            not_for_server,
+            package_guid,
+            is_inherited_instance,
+            package_flags,
+            not_always_loaded_for_editor_game,
+            is_asset,
+            generate_public_hash,
+            first_export_dependency,
+            serialization_before_serialization_dependencies,
+            create_before_serialization_dependencies,
+            serialization_before_create_dependencies,
+            create_before_create_dependencies,
+            script_serialization_start_offset,
+            script_serialization_end_offset,
+        })
+    }
+}
+
+impl PackageFileSummary {
+
+    /// Reads the `import_count` imports starting at the reader's position, which the
+    /// caller seeks to `import_offset`. The reader must have the package's versions and
+    /// name table set.
+    pub fn read_import_map<R: ReadExt + ?Sized>(&self, reader: &mut R) -> ReaderResult<Vec<FObjectImport>> {
+        let filter_editor_only = self.filter_editor_only();
+        reader.with_context("ImportMap", |reader| {
+            with_endian!(self.endian, B => reader.read_array_with_length(
+                |r| FObjectImport::read::<B, _>(r, filter_editor_only),
+                self.import_count,
+            ))
+        })
+    }
+
+    /// Reads the `export_count` exports starting at the reader's position, like
+    /// [`PackageFileSummary::read_import_map`].
+    pub fn read_export_map<R: ReadExt + ?Sized>(&self, reader: &mut R) -> ReaderResult<Vec<FObjectExport>> {
+        reader.with_context("ExportMap", |reader| {
+            with_endian!(self.endian, B => reader.read_array_with_length(
+                |r| FObjectExport::read_from::<B, _>(r),
+                self.export_count,
+            ))
+        })
+    }
+
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::package::{FPackageIndex, PackageFileSummary};
+    use crate::types::NameTable;
+    use crate::{ArchiveVersion, PackageFileVersion, PathSegment, ReadError, Reader, WriteExt};
+
+    #[test]
+    fn read_object_maps() {
+        let mut data = Vec::new();
+        // One import of class /Script/CoreUObject.Package named /Script/Engine.
+        for value in [1, 0, 2, 0, 0, 3, 0] {
+            data.write_i32_le(value).unwrap();
+        }
+        // One export with a 64-bit serial size and preload dependencies.
+        for value in [-1, 0, 0, 0, 4, 0, 8] {
+            data.write_i32_le(value).unwrap();
+        }
+        data.write_i64_le(0x40).unwrap();
+        data.write_i64_le(0x1000).unwrap();
+        for value in [0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0] {
+            data.write_i32_le(value).unwrap();
+        }
+
+        let names = ["None", "/Script/CoreUObject", "Package", "/Script/Engine", "Foo"];
+        let mut reader = Reader::new(&data[..]);
+        reader.set_version(ArchiveVersion::new(PackageFileVersion::new(522, 0), 0));
+        reader.set_names(NameTable::from(names.map(String::from).to_vec()));
+
+        let summary = PackageFileSummary {
+            package_flags: PackageFileSummary::PKG_FILTER_EDITOR_ONLY,
+            import_count: 1,
+            export_count: 1,
+            ..PackageFileSummary::default()
+        };
+        let imports = summary.read_import_map(&mut reader).unwrap();
+        assert_eq!(imports[0].class_name.to_string(), "Package");
+        assert_eq!(imports[0].object_name.to_string(), "/Script/Engine");
+        assert_eq!(imports[0].package_name, None);
+
+        let exports = summary.read_export_map(&mut reader).unwrap();
+        assert_eq!(exports[0].class_index, FPackageIndex::from_import(0));
+        assert_eq!(exports[0].object_name.to_string(), "Foo");
+        assert_eq!((exports[0].object_flags, exports[0].serial_size, exports[0].serial_offset), (8, 0x40, 0x1000));
+        assert!(exports[0].is_asset);
+        assert_eq!(exports[0].first_export_dependency, 0);
+
+        let mut reader = Reader::new(&data[28..]);
+        reader.set_version(ArchiveVersion::new(PackageFileVersion::new(522, 0), 0));
+        let error = summary.read_export_map(&mut reader).unwrap_err();
+        assert!(matches!(error.root(), ReadError::InvalidNameIndex { index: 4, .. }));
+        assert_eq!(error.path(), [PathSegment::Name("ExportMap"), PathSegment::Index(0), PathSegment::Name("ObjectName")]);
+    }
+
+}
@@ -0,0 +1,208 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::package::{FObjectExport, FObjectImport, FPackageIndex};
+use crate::types::FName;
+use crate::{ReadError, ReaderResult};
+
+/// An import or export an [`FPackageIndex`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ObjectRef<'a> {
+    Import(&'a FObjectImport),
+    Export(&'a FObjectExport),
+}
+
+impl<'a> ObjectRef<'a> {
+
+    #[inline]
+    pub fn object_name(self) -> &'a FName {
+        match self {
+            ObjectRef::Import(import) => &import.object_name,
+            ObjectRef::Export(export) => &export.object_name,
+        }
+    }
+
+    #[inline]
+    pub fn outer_index(self) -> FPackageIndex {
+        match self {
+            ObjectRef::Import(import) => import.outer_index,
+            ObjectRef::Export(export) => export.outer_index,
+        }
+    }
+
+}
+
+/// Resolves [`FPackageIndex`] values against a package's import and export maps.
+///
+/// Exports without an outer live in the package itself, so their paths start with the
+/// package name, while imports without an outer are packages and start with their own name.
+#[derive(Debug, Clone, Copy)]
+pub struct ObjectResolver<'a> {
+    package_name: &'a str,
+    imports: &'a [FObjectImport],
+    exports: &'a [FObjectExport],
+}
+
+impl<'a> ObjectResolver<'a> {
+
+    /// `package_name` is the long package name, such as `/Game/Foo`.
+    pub fn new(package_name: &'a str, imports: &'a [FObjectImport], exports: &'a [FObjectExport]) -> Self {
+        Self { package_name, imports, exports }
+    }
+
+    /// Returns the object `index` refers to, or `None` for the null index.
+    pub fn resolve(&self, index: FPackageIndex) -> ReaderResult<Option<ObjectRef<'a>>> {
+        let object = match (index.to_import(), index.to_export()) {
+            (Some(i), _) => self.imports.get(i).map(ObjectRef::Import),
+            (_, Some(i)) => self.exports.get(i).map(ObjectRef::Export),
+            _ => return Ok(None),
+        };
+
+        match object {
+            Some(object) => Ok(Some(object)),
+            None => Err(ReadError::InvalidPackageIndex { index: index.value(), offset: None }),
+        }
+    }
+
+    /// Returns the objects from `index` out to its outermost object, innermost first.
+    pub fn outer_chain(&self, index: FPackageIndex) -> ReaderResult<Vec<ObjectRef<'a>>> {
+        let mut chain = Vec::new();
+        let mut current = index;
+        while let Some(object) = self.resolve(current)? {
+            if chain.len() > self.imports.len() + self.exports.len() {
+                return Err(ReadError::InvalidPackageIndex { index: index.value(), offset: None });
+            }
+
+            chain.push(object);
+            current = object.outer_index();
+        }
+
+        Ok(chain)
+    }
+
+    /// Returns the path of an object, such as `/Game/Foo.Foo:Component`, or `None` for
+    /// the null index.
+    pub fn path_name(&self, index: FPackageIndex) -> ReaderResult<String> {
+        let chain = self.outer_chain(index)?;
+        let Some(outermost) = chain.last() else {
+            return Ok("None".to_string());
+        };
+
+        let mut components: Vec<String> = Vec::with_capacity(chain.len() + 1);
+        if let ObjectRef::Export(_) = outermost {
+            components.push(self.package_name.to_string());
+        }
+        components.extend(chain.iter().rev().map(|object| object.object_name().to_string()));
+
+        let mut path = String::new();
+        for (i, component) in components.iter().enumerate() {
+            // Subobjects of a package's top-level objects are separated with a colon.
+            match i {
+                0 => {}
+                2 => path.push(':'),
+                _ => path.push('.'),
+            }
+            path.push_str(component);
+        }
+
+        Ok(path)
+    }
+
+    /// Returns the path of an object's class, such as `/Script/Engine.StaticMesh`.
+    pub fn class_path(&self, index: FPackageIndex) -> ReaderResult<String> {
+        match self.resolve(index)? {
+            None => Ok("None".to_string()),
+            Some(ObjectRef::Import(import)) => Ok(alloc::format!("{}.{}", import.class_package, import.class_name)),
+            Some(ObjectRef::Export(export)) if export.class_index.is_null() => {
+                Ok("/Script/CoreUObject.Class".to_string())
+            }
+            Some(ObjectRef::Export(export)) => self.path_name(export.class_index),
+        }
+    }
+
+    /// Returns the class and path of an object as Unreal quotes object references, such
+    /// as `/Script/Engine.StaticMesh'/Game/Foo.Foo'`, or `None` for the null index.
+    pub fn full_name(&self, index: FPackageIndex) -> ReaderResult<String> {
+        if index.is_null() {
+            return Ok("None".to_string());
+        }
+        Ok(alloc::format!("{}'{}'", self.class_path(index)?, self.path_name(index)?))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::package::{FObjectExport, FObjectImport, FPackageIndex, ObjectResolver};
+    use crate::types::FName;
+    use crate::ReadError;
+
+    fn import(class_package: &str, class_name: &str, outer_index: FPackageIndex, object_name: &str) -> FObjectImport {
+        FObjectImport {
+            class_package: FName::new(class_package, 0),
+            class_name: FName::new(class_name, 0),
+            outer_index,
+            object_name: FName::new(object_name, 0),
+            package_name: None,
+            import_optional: false,
+        }
+    }
+
+    fn export(class_index: FPackageIndex, outer_index: FPackageIndex, object_name: &str) -> FObjectExport {
+        FObjectExport {
+            class_index,
+            super_index: FPackageIndex::NULL,
+            template_index: FPackageIndex::NULL,
+            outer_index,
+            object_name: FName::new(object_name, 0),
+            object_flags: 0,
+            serial_size: 0,
+            serial_offset: 0,
+            forced_export: false,
+            not_for_client: false,
+            not_for_server: false,
+            package_guid: Default::default(),
+            is_inherited_instance: false,
+            package_flags: 0,
+            not_always_loaded_for_editor_game: false,
+            is_asset: false,
+            generate_public_hash: false,
+            first_export_dependency: -1,
+            serialization_before_serialization_dependencies: -1,
+            create_before_serialization_dependencies: -1,
+            serialization_before_create_dependencies: -1,
+            create_before_create_dependencies: -1,
+            script_serialization_start_offset: 0,
+            script_serialization_end_offset: 0,
+        }
+    }
+
+    #[test]
+    fn resolve_object_paths() {
+        let imports = [
+            import("/Script/CoreUObject", "Package", FPackageIndex::NULL, "/Script/Engine"),
+            import("/Script/CoreUObject", "Class", FPackageIndex::from_import(0), "StaticMesh"),
+            import("/Script/CoreUObject", "Class", FPackageIndex::from_import(0), "BodySetup"),
+        ];
+        let exports = [
+            export(FPackageIndex::from_import(1), FPackageIndex::NULL, "Foo"),
+            export(FPackageIndex::from_import(2), FPackageIndex::from_export(0), "BodySetup_0"),
+            export(FPackageIndex::NULL, FPackageIndex::from_export(1), "Inner"),
+        ];
+        let resolver = ObjectResolver::new("/Game/Foo", &imports, &exports);
+
+        assert_eq!(resolver.full_name(FPackageIndex::from_export(0)).unwrap(), "/Script/Engine.StaticMesh'/Game/Foo.Foo'");
+        assert_eq!(resolver.path_name(FPackageIndex::from_export(1)).unwrap(), "/Game/Foo.Foo:BodySetup_0");
+        assert_eq!(resolver.path_name(FPackageIndex::from_export(2)).unwrap(), "/Game/Foo.Foo:BodySetup_0.Inner");
+        assert_eq!(resolver.full_name(FPackageIndex::from_import(1)).unwrap(), "/Script/CoreUObject.Class'/Script/Engine.StaticMesh'");
+        assert_eq!(resolver.full_name(FPackageIndex::NULL).unwrap(), "None");
+
+        let error = resolver.path_name(FPackageIndex::from_export(7)).unwrap_err();
+        assert!(matches!(error, ReadError::InvalidPackageIndex { index: 8, .. }));
+
+        let looped = [export(FPackageIndex::NULL, FPackageIndex::from_export(0), "Loop")];
+        let resolver = ObjectResolver::new("/Game/Loop", &[], &looped);
+        assert!(resolver.outer_chain(FPackageIndex::from_export(0)).is_err());
+    }
+
+}
@@ -0,0 +1,441 @@
+use byteorder::{ByteOrder, LittleEndian};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::types::FGuid;
+use crate::{with_endian, ArchiveVersion, CustomVersion, Endian, ObjectVersion, ObjectVersionUE5, PackageFileVersion};
+use crate::{ReadError, ReadExt, ReadFrom, ReaderResult};
+
+/// The engine build that saved a package, `FEngineVersion` in Unreal.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct EngineVersion {
+    pub major: u16,
+    pub minor: u16,
+    pub patch: u16,
+    pub changelist: u32,
+    pub branch: String,
+}
+
+impl fmt::Display for EngineVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}-{}+{}", self.major, self.minor, self.patch, self.changelist, self.branch)
+    }
+}
+
+impl ReadFrom for EngineVersion {
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        Ok(Self {
+            major: reader.read_num::<B, u16>()?,
+            minor: reader.read_num::<B, u16>()?,
+            patch: reader.read_num::<B, u16>()?,
+            changelist: reader.read_num::<B, u32>()?,
+            branch: reader.read_fstring::<B>()?,
+        })
+    }
+}
+
+/// The number of exports and names a package had when it was saved by an earlier build.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct GenerationInfo {
+    pub export_count: i32,
+    pub name_count: i32,
+}
+
+impl ReadFrom for GenerationInfo {
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        Ok(Self {
+            export_count: reader.read_num::<B, i32>()?,
+            name_count: reader.read_num::<B, i32>()?,
+        })
+    }
+}
+
+/// A compressed region of a package saved with the long-removed package compression.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CompressedChunk {
+    pub uncompressed_offset: i32,
+    pub uncompressed_size: i32,
+    pub compressed_offset: i32,
+    pub compressed_size: i32,
+}
+
+impl ReadFrom for CompressedChunk {
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        Ok(Self {
+            uncompressed_offset: reader.read_num::<B, i32>()?,
+            uncompressed_size: reader.read_num::<B, i32>()?,
+            compressed_offset: reader.read_num::<B, i32>()?,
+            compressed_size: reader.read_num::<B, i32>()?,
+        })
+    }
+}
+
+/// The header at the start of every `.uasset` and `.umap`, `FPackageFileSummary` in Unreal.
+///
+/// Fields that the package's version predates hold Unreal's defaults: zero, or `-1` for
+/// offsets Unreal marks as absent.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PackageFileSummary {
+    /// The byte order of the package, detected from its magic.
+    pub endian: Endian,
+    pub legacy_file_version: i32,
+    pub legacy_ue3_version: i32,
+    pub file_version: PackageFileVersion,
+    pub licensee_version: i32,
+    /// True if the package was saved without versions, and the versions above were taken
+    /// from the reader instead.
+    pub unversioned: bool,
+    pub custom_versions: Vec<CustomVersion>,
+    pub saved_hash: Option<[u8; 20]>,
+    pub total_header_size: i32,
+    pub folder_name: String,
+    pub package_flags: u32,
+    pub name_count: i32,
+    pub name_offset: i32,
+    pub soft_object_paths_count: i32,
+    pub soft_object_paths_offset: i32,
+    pub localization_id: Option<String>,
+    pub gatherable_text_data_count: i32,
+    pub gatherable_text_data_offset: i32,
+    pub export_count: i32,
+    pub export_offset: i32,
+    pub import_count: i32,
+    pub import_offset: i32,
+    pub cell_export_count: i32,
+    pub cell_export_offset: i32,
+    pub cell_import_count: i32,
+    pub cell_import_offset: i32,
+    pub meta_data_offset: i32,
+    pub depends_offset: i32,
+    pub soft_package_references_count: i32,
+    pub soft_package_references_offset: i32,
+    pub searchable_names_offset: i32,
+    pub thumbnail_table_offset: i32,
+    pub guid: FGuid,
+    pub persistent_guid: FGuid,
+    pub generations: Vec<GenerationInfo>,
+    pub saved_by_engine_version: EngineVersion,
+    pub compatible_with_engine_version: EngineVersion,
+    pub compression_flags: u32,
+    pub compressed_chunks: Vec<CompressedChunk>,
+    pub package_source: u32,
+    pub additional_packages_to_cook: Vec<String>,
+    pub asset_registry_data_offset: i32,
+    pub bulk_data_start_offset: i64,
+    pub world_tile_info_data_offset: i32,
+    pub chunk_ids: Vec<i32>,
+    pub preload_dependency_count: i32,
+    pub preload_dependency_offset: i32,
+    pub names_referenced_from_export_data_count: i32,
+    pub payload_toc_offset: i64,
+    pub data_resource_offset: i32,
+}
+
+impl PackageFileSummary {
+
+    pub const PACKAGE_FILE_TAG: u32 = 0x9E2A83C1;
+    pub const PACKAGE_FILE_TAG_SWAPPED: u32 = 0xC1832A9E;
+
+    /// The newest legacy file version this crate can read, that of UE 5.4 and later.
+    /// Versions from -2 up to this one are accepted.
+    pub const CURRENT_LEGACY_FILE_VERSION: i32 = -9;
+
+    /// Set on cooked packages whose editor-only data has been stripped, `PKG_FilterEditorOnly`.
+    pub const PKG_FILTER_EDITOR_ONLY: u32 = 0x8000_0000;
+
+    /// Reads a summary, detecting the byte order from its magic. Unversioned packages
+    /// take their versions from [`ReadExt::version`].
+    pub fn read<R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        let offset = reader.offset();
+        let endian = match reader.read_num::<LittleEndian, u32>()? {
+            Self::PACKAGE_FILE_TAG => Endian::Little,
+            Self::PACKAGE_FILE_TAG_SWAPPED => Endian::Big,
+            found => return Err(ReadError::InvalidMagic { found, offset }),
+        };
+
+        with_endian!(endian, B => Self::read_body::<B, R>(reader, endian))
+    }
+
+    /// Returns true if the package is cooked with its editor-only data stripped.
+    #[inline]
+    pub fn filter_editor_only(&self) -> bool {
+        self.package_flags & Self::PKG_FILTER_EDITOR_ONLY != 0
+    }
+
+    /// Returns the version state the rest of the package is read with.
+    pub fn archive_version(&self) -> ArchiveVersion {
+        ArchiveVersion {
+            package: self.file_version,
+            licensee: self.licensee_version,
+            custom_versions: self.custom_versions.clone(),
+        }
+    }
+
+    fn read_body<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R, endian: Endian) -> ReaderResult<Self> {
+        let mut summary = PackageFileSummary { endian, ..PackageFileSummary::default() };
+
+        let offset = reader.offset();
+        summary.legacy_file_version = reader.read_num::<B, i32>()?;
+        let legacy = summary.legacy_file_version;
+        if !(Self::CURRENT_LEGACY_FILE_VERSION..=-2).contains(&legacy) {
+            return Err(ReadError::UnsupportedVersion { version: legacy, offset });
+        }
+
+        if legacy != -4 {
+            summary.legacy_ue3_version = reader.read_num::<B, i32>()?;
+        }
+        let version_offset = reader.offset();
+        summary.file_version.ue4 = reader.read_num::<B, i32>()?;
+        if legacy <= -8 {
+            summary.file_version.ue5 = reader.read_num::<B, i32>()?;
+        }
+        summary.licensee_version = reader.read_num::<B, i32>()?;
+
+        if summary.file_version == PackageFileVersion::default() && summary.licensee_version == 0 {
+            summary.unversioned = true;
+            summary.file_version = reader.ue_version();
+            summary.licensee_version = reader.licensee_version();
+        }
+        let version = summary.file_version;
+        if version.ue4 < ObjectVersion::OLDEST_LOADABLE_PACKAGE || version.ue4 > ObjectVersion::AUTOMATIC_VERSION {
+            return Err(ReadError::UnsupportedVersion { version: version.ue4, offset: version_offset });
+        }
+
+        if version.ue5 >= ObjectVersionUE5::PACKAGE_SAVED_HASH {
+            let mut hash = [0u8; 20];
+            reader.read_exact_bytes(&mut hash)?;
+            summary.saved_hash = Some(hash);
+            summary.total_header_size = reader.read_num::<B, i32>()?;
+        }
+
+        summary.custom_versions = read_custom_versions::<B, R>(reader, legacy)?;
+        if summary.unversioned && summary.custom_versions.is_empty() {
+            summary.custom_versions = reader.version().custom_versions.clone();
+        }
+
+        if version.ue5 < ObjectVersionUE5::PACKAGE_SAVED_HASH {
+            summary.total_header_size = reader.read_num::<B, i32>()?;
+        }
+        summary.folder_name = reader.read_fstring::<B>()?;
+        summary.package_flags = reader.read_num::<B, u32>()?;
+        summary.name_count = reader.read_num::<B, i32>()?;
+        summary.name_offset = reader.read_num::<B, i32>()?;
+
+        if version.ue5 >= ObjectVersionUE5::ADD_SOFTOBJECTPATH_LIST {
+            summary.soft_object_paths_count = reader.read_num::<B, i32>()?;
+            summary.soft_object_paths_offset = reader.read_num::<B, i32>()?;
+        }
+        if !summary.filter_editor_only() && version.ue4 >= ObjectVersion::ADDED_PACKAGE_SUMMARY_LOCALIZATION_ID {
+            summary.localization_id = Some(reader.read_fstring::<B>()?);
+        }
+        if version.ue4 >= ObjectVersion::SERIALIZE_TEXT_IN_PACKAGES {
+            summary.gatherable_text_data_count = reader.read_num::<B, i32>()?;
+            summary.gatherable_text_data_offset = reader.read_num::<B, i32>()?;
+        }
+
+        summary.export_count = reader.read_num::<B, i32>()?;
+        summary.export_offset = reader.read_num::<B, i32>()?;
+        summary.import_count = reader.read_num::<B, i32>()?;
+        summary.import_offset = reader.read_num::<B, i32>()?;
+        if version.ue5 >= ObjectVersionUE5::VERSE_CELLS {
+            summary.cell_export_count = reader.read_num::<B, i32>()?;
+            summary.cell_export_offset = reader.read_num::<B, i32>()?;
+            summary.cell_import_count = reader.read_num::<B, i32>()?;
+            summary.cell_import_offset = reader.read_num::<B, i32>()?;
+        }
+        summary.meta_data_offset = -1;
+        if version.ue5 >= ObjectVersionUE5::METADATA_SERIALIZATION_OFFSET {
+            summary.meta_data_offset = reader.read_num::<B, i32>()?;
+        }
+        summary.depends_offset = reader.read_num::<B, i32>()?;
+
+        if version.ue4 >= ObjectVersion::ADD_STRING_ASSET_REFERENCES_MAP {
+            summary.soft_package_references_count = reader.read_num::<B, i32>()?;
+            summary.soft_package_references_offset = reader.read_num::<B, i32>()?;
+        }
+        if version.ue4 >= ObjectVersion::ADDED_SEARCHABLE_NAMES {
+            summary.searchable_names_offset = reader.read_num::<B, i32>()?;
+        }
+        summary.thumbnail_table_offset = reader.read_num::<B, i32>()?;
+
+        if version.ue5 < ObjectVersionUE5::PACKAGE_SAVED_HASH {
+            summary.guid = FGuid::read_from::<B, R>(reader)?;
+        }
+        if !summary.filter_editor_only() && version.ue4 >= ObjectVersion::ADDED_PACKAGE_OWNER {
+            summary.persistent_guid = FGuid::read_from::<B, R>(reader)?;
+            if version.ue4 < ObjectVersion::NON_OUTER_PACKAGE_IMPORT {
+                FGuid::read_from::<B, R>(reader)?;
+            }
+        }
+
+        summary.generations = reader.read_array::<B, _>(|r| GenerationInfo::read_from::<B, _>(r))?;
+
+        if version.ue4 >= ObjectVersion::ENGINE_VERSION_OBJECT {
+            summary.saved_by_engine_version = EngineVersion::read_from::<B, R>(reader)?;
+        } else {
+            let changelist = reader.read_num::<B, u32>()?;
+            summary.saved_by_engine_version = EngineVersion { major: 4, changelist, ..EngineVersion::default() };
+        }
+        summary.compatible_with_engine_version = if version.ue4 >= ObjectVersion::PACKAGE_SUMMARY_HAS_COMPATIBLE_ENGINE_VERSION {
+            EngineVersion::read_from::<B, R>(reader)?
+        } else {
+            summary.saved_by_engine_version.clone()
+        };
+
+        summary.compression_flags = reader.read_num::<B, u32>()?;
+        summary.compressed_chunks = reader.read_array::<B, _>(|r| CompressedChunk::read_from::<B, _>(r))?;
+        summary.package_source = reader.read_num::<B, u32>()?;
+        summary.additional_packages_to_cook = reader.read_array::<B, _>(|r| r.read_fstring::<B>())?;
+        if legacy > -7 {
+            // Texture allocations, always empty in packages that still load.
+            reader.read_num::<B, i32>()?;
+        }
+
+        summary.asset_registry_data_offset = reader.read_num::<B, i32>()?;
+        summary.bulk_data_start_offset = reader.read_num::<B, i64>()?;
+        if version.ue4 >= ObjectVersion::WORLD_LEVEL_INFO {
+            summary.world_tile_info_data_offset = reader.read_num::<B, i32>()?;
+        }
+
+        if version.ue4 >= ObjectVersion::CHANGED_CHUNKID_TO_BE_AN_ARRAY_OF_CHUNKIDS {
+            summary.chunk_ids = reader.read_array::<B, _>(|r| r.read_num::<B, i32>())?;
+        } else if version.ue4 >= ObjectVersion::ADDED_CHUNKID_TO_ASSETDATA_AND_UPACKAGE {
+            let chunk_id = reader.read_num::<B, i32>()?;
+            if chunk_id >= 0 {
+                summary.chunk_ids.push(chunk_id);
+            }
+        }
+
+        summary.preload_dependency_offset = -1;
+        if version.ue4 >= ObjectVersion::PRELOAD_DEPENDENCIES_IN_COOKED_EXPORTS {
+            summary.preload_dependency_count = reader.read_num::<B, i32>()?;
+            summary.preload_dependency_offset = reader.read_num::<B, i32>()?;
+        }
+
+        summary.names_referenced_from_export_data_count = if version.ue5 >= ObjectVersionUE5::NAMES_REFERENCED_FROM_EXPORT_DATA {
+            reader.read_num::<B, i32>()?
+        } else {
+            summary.name_count
+        };
+        summary.payload_toc_offset = if version.ue5 >= ObjectVersionUE5::PAYLOAD_TOC {
+            reader.read_num::<B, i64>()?
+        } else {
+            -1
+        };
+        summary.data_resource_offset = if version.ue5 >= ObjectVersionUE5::DATA_RESOURCES {
+            reader.read_num::<B, i32>()?
+        } else {
+            -1
+        };
+
+        Ok(summary)
+    }
+
+}
+
+/// Reads the custom version container in the format used by the legacy file version.
+fn read_custom_versions<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R, legacy: i32) -> ReaderResult<Vec<CustomVersion>> {
+    match legacy {
+        // Keyed by an enum tag instead of a GUID.
+        -2 => reader.read_array::<B, _>(|r| {
+            let tag = r.read_num::<B, u32>()?;
+            let version = r.read_num::<B, i32>()?;
+            Ok(CustomVersion { key: FGuid::new(0, 0, 0, tag), version })
+        }),
+        // Followed by a friendly name.
+        -5..=-3 => reader.read_array::<B, _>(|r| {
+            let custom = CustomVersion::read_from::<B, _>(r)?;
+            r.read_fstring::<B>()?;
+            Ok(custom)
+        }),
+        _ => reader.read_array::<B, _>(|r| CustomVersion::read_from::<B, _>(r)),
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use byteorder::ByteOrder;
+
+    use crate::package::PackageFileSummary;
+    use crate::types::FGuid;
+    use crate::{BigEndian, Endian, LittleEndian, PackageFileVersion, ReadError, Reader, WriteExt};
+
+    /// A UE 4.27 summary with one custom version and one generation.
+    fn summary_bytes<B: ByteOrder>() -> Vec<u8> {
+        let mut data = Vec::new();
+        data.write_num::<B, u32>(PackageFileSummary::PACKAGE_FILE_TAG).unwrap();
+        for value in [-7, 864, 522, 0, 1, 1, 2, 3, 4, 7, 0x400] {
+            data.write_num::<B, i32>(value).unwrap();
+        }
+        data.write_fstring::<B>("/Game/Test").unwrap();
+        data.write_num::<B, u32>(0).unwrap();
+        for value in [12, 0x200] {
+            data.write_num::<B, i32>(value).unwrap();
+        }
+        data.write_fstring::<B>("").unwrap();
+        // Gatherable text, exports, imports, depends, soft package references,
+        // searchable names, thumbnails, then the package GUID and persistent GUID.
+        for value in [0, 0, 3, 0x300, 2, 0x280, 0x380, 0, 0, 0, 0, 0, 0, 0, 0, 9, 9, 9, 9] {
+            data.write_num::<B, i32>(value).unwrap();
+        }
+        // Generations.
+        for value in [1, 3, 12] {
+            data.write_num::<B, i32>(value).unwrap();
+        }
+        for _ in 0..2 {
+            for value in [4u16, 27, 2] {
+                data.write_num::<B, u16>(value).unwrap();
+            }
+            data.write_num::<B, u32>(18319896).unwrap();
+            data.write_fstring::<B>("++UE4+Release-4.27").unwrap();
+        }
+        // Compression, package source, additional packages, asset registry.
+        for value in [0, 0, 0x12345678, 0, 0x390] {
+            data.write_num::<B, i32>(value).unwrap();
+        }
+        data.write_num::<B, i64>(0x1000).unwrap();
+        for value in [0, 0, 0, -1] {
+            data.write_num::<B, i32>(value).unwrap();
+        }
+        data
+    }
+
+    #[test]
+    fn read_summary_detects_endianness() {
+        for (endian, data) in [(Endian::Little, summary_bytes::<LittleEndian>()), (Endian::Big, summary_bytes::<BigEndian>())] {
+            let summary = PackageFileSummary::read(&mut &data[..]).unwrap();
+            assert_eq!(summary.endian, endian);
+            assert_eq!(summary.file_version, PackageFileVersion::new(522, 0));
+            assert_eq!(summary.archive_version().custom_version(&FGuid::new(1, 2, 3, 4)), Some(7));
+            assert_eq!(summary.folder_name, "/Game/Test");
+            assert_eq!((summary.name_count, summary.export_count, summary.import_offset), (12, 3, 0x280));
+            assert_eq!(summary.persistent_guid, FGuid::new(9, 9, 9, 9));
+            assert_eq!(summary.saved_by_engine_version.to_string(), "4.27.2-18319896+++UE4+Release-4.27");
+            assert_eq!(summary.package_source, 0x12345678);
+            assert_eq!(summary.bulk_data_start_offset, 0x1000);
+            assert_eq!(summary.preload_dependency_offset, -1);
+            assert_eq!(summary.names_referenced_from_export_data_count, 12);
+        }
+
+        let error = PackageFileSummary::read(&mut &[0u8, 1, 2, 3][..]).unwrap_err();
+        assert!(matches!(error, ReadError::InvalidMagic { found: 0x03020100, offset: None }));
+    }
+
+    #[test]
+    fn read_summary_rejects_unsupported_versions() {
+        // An unversioned summary read without versions, and one older than any loadable package.
+        for ue4 in [0, 213] {
+            let mut data = Vec::new();
+            data.write_u32_le(PackageFileSummary::PACKAGE_FILE_TAG).unwrap();
+            for value in [-7, 864, ue4, 0, 0, 0, 0, 0] {
+                data.write_i32_le(value).unwrap();
+            }
+
+            let error = PackageFileSummary::read(&mut Reader::new(&data[..])).unwrap_err();
+            assert!(matches!(error, ReadError::UnsupportedVersion { version, offset: Some(12) } if version == ue4));
+        }
+    }
+
+}
@@ -0,0 +1,718 @@
+use byteorder::LittleEndian;
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::limits;
+use crate::package::{FObjectExport, FObjectImport, FPackageIndex};
+use crate::types::{FGuid, FName, NameTable, RawFName};
+use crate::{ArchiveVersion, CustomVersion, PackageFileVersion, ReadError, ReadExt, ReadFrom, ReaderResult, SliceReader};
+use crate::StringEncoding;
+
+/// How deep the outer chain of a script object may go before it is taken for a loop.
+const MAX_OUTER_DEPTH: u32 = 64;
+
+/// A reference to an object in the Zen loader, `FPackageObjectIndex` in Unreal: an
+/// export of the same package, a script object by the hash of its path, or a public
+/// export of an imported package.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FPackageObjectIndex(u64);
+
+impl FPackageObjectIndex {
+
+    pub const NULL: FPackageObjectIndex = FPackageObjectIndex(u64::MAX);
+
+    const TYPE_SHIFT: u32 = 62;
+    const INDEX_MASK: u64 = (1 << Self::TYPE_SHIFT) - 1;
+    const TYPE_EXPORT: u64 = 0;
+    const TYPE_SCRIPT_IMPORT: u64 = 1;
+    const TYPE_PACKAGE_IMPORT: u64 = 2;
+    const TYPE_NULL: u64 = 3;
+
+    #[inline]
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+
+    /// Returns the value as serialized.
+    #[inline]
+    pub const fn value(self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    pub const fn is_null(self) -> bool {
+        self.0 >> Self::TYPE_SHIFT == Self::TYPE_NULL
+    }
+
+    #[inline]
+    pub const fn is_script_import(self) -> bool {
+        self.0 >> Self::TYPE_SHIFT == Self::TYPE_SCRIPT_IMPORT
+    }
+
+    /// Returns the position in the export map, if this refers to an export.
+    #[inline]
+    pub const fn to_export(self) -> Option<usize> {
+        if self.0 >> Self::TYPE_SHIFT == Self::TYPE_EXPORT { Some((self.0 & Self::INDEX_MASK) as usize) } else { None }
+    }
+
+    /// Returns the positions in the imported package names and the imported public
+    /// export hashes, if this refers to an export of another package.
+    #[inline]
+    pub const fn to_package_import(self) -> Option<(u32, u32)> {
+        if self.0 >> Self::TYPE_SHIFT == Self::TYPE_PACKAGE_IMPORT {
+            Some((((self.0 & Self::INDEX_MASK) >> 32) as u32, self.0 as u32))
+        } else {
+            None
+        }
+    }
+
+}
+
+impl ReadFrom for FPackageObjectIndex {
+    #[inline]
+    fn read_from<B: byteorder::ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        reader.read_num::<B, u64>().map(FPackageObjectIndex)
+    }
+}
+
+/// The fixed header at the start of a Zen package, `FZenPackageSummary` in Unreal.
+///
+/// This is the layout cooked by UE 5.3 and later, with dependency bundles and imported
+/// package names. Offsets are from the start of the summary.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ZenPackageSummary {
+    pub has_versioning_info: bool,
+    /// The size of the whole header, after which the export data starts.
+    pub header_size: u32,
+    /// The package's name in its name map.
+    pub name: RawFName,
+    pub package_flags: u32,
+    /// The header size of the package as cooked to a `.uasset`, which export offsets
+    /// are relative to.
+    pub cooked_header_size: u32,
+    pub imported_public_export_hashes_offset: i32,
+    pub import_map_offset: i32,
+    pub export_map_offset: i32,
+    pub export_bundle_entries_offset: i32,
+    pub dependency_bundle_headers_offset: i32,
+    pub dependency_bundle_entries_offset: i32,
+    pub imported_package_names_offset: i32,
+}
+
+impl ZenPackageSummary {
+
+    pub const SIZE: usize = 52;
+
+    pub fn read(reader: &mut SliceReader<'_>) -> ReaderResult<Self> {
+        Ok(Self {
+            has_versioning_info: reader.read_bool::<LittleEndian>()?,
+            header_size: reader.read_u32_le()?,
+            name: read_mapped_name(reader)?,
+            package_flags: reader.read_u32_le()?,
+            cooked_header_size: reader.read_u32_le()?,
+            imported_public_export_hashes_offset: reader.read_i32_le()?,
+            import_map_offset: reader.read_i32_le()?,
+            export_map_offset: reader.read_i32_le()?,
+            export_bundle_entries_offset: reader.read_i32_le()?,
+            dependency_bundle_headers_offset: reader.read_i32_le()?,
+            dependency_bundle_entries_offset: reader.read_i32_le()?,
+            imported_package_names_offset: reader.read_i32_le()?,
+        })
+    }
+
+}
+
+/// The versions a Zen package was saved with, `FZenPackageVersioningInfo` in Unreal.
+/// Only packages cooked with versioning have it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ZenPackageVersioningInfo {
+    pub zen_version: u32,
+    pub package_version: PackageFileVersion,
+    pub licensee_version: i32,
+    pub custom_versions: Vec<CustomVersion>,
+}
+
+impl ZenPackageVersioningInfo {
+
+    /// Returns the version state the package's exports are read with.
+    pub fn archive_version(&self) -> ArchiveVersion {
+        ArchiveVersion {
+            package: self.package_version,
+            licensee: self.licensee_version,
+            custom_versions: self.custom_versions.clone(),
+        }
+    }
+
+}
+
+impl ReadFrom for ZenPackageVersioningInfo {
+    fn read_from<B: byteorder::ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        Ok(Self {
+            zen_version: reader.read_num::<B, u32>()?,
+            package_version: PackageFileVersion::new(reader.read_num::<B, i32>()?, reader.read_num::<B, i32>()?),
+            licensee_version: reader.read_num::<B, i32>()?,
+            custom_versions: reader.read_array::<B, _>(|r| CustomVersion::read_from::<B, _>(r))?,
+        })
+    }
+}
+
+/// An object saved in a Zen package, `FExportMapEntry` in Unreal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FExportMapEntry {
+    pub cooked_serial_offset: u64,
+    pub cooked_serial_size: u64,
+    pub object_name: FName,
+    pub outer_index: FPackageObjectIndex,
+    pub class_index: FPackageObjectIndex,
+    pub super_index: FPackageObjectIndex,
+    pub template_index: FPackageObjectIndex,
+    /// The hash other packages import the export by, zero if it is not public.
+    pub public_export_hash: u64,
+    pub object_flags: u32,
+    pub filter_flags: u8,
+}
+
+impl FExportMapEntry {
+
+    pub const SIZE: usize = 72;
+
+    pub const FILTER_NOT_FOR_CLIENT: u8 = 0x01;
+    pub const FILTER_NOT_FOR_SERVER: u8 = 0x02;
+
+    fn read(reader: &mut SliceReader<'_>, names: &NameTable) -> ReaderResult<Self> {
+        let cooked_serial_offset = reader.read_u64_le()?;
+        let cooked_serial_size = reader.read_u64_le()?;
+        let object_name = names.resolve(read_mapped_name(reader)?).map_err(|e| e.at(reader.offset().unwrap_or(0)))?;
+        let entry = Self {
+            cooked_serial_offset,
+            cooked_serial_size,
+            object_name,
+            outer_index: FPackageObjectIndex::read_from::<LittleEndian, _>(reader)?,
+            class_index: FPackageObjectIndex::read_from::<LittleEndian, _>(reader)?,
+            super_index: FPackageObjectIndex::read_from::<LittleEndian, _>(reader)?,
+            template_index: FPackageObjectIndex::read_from::<LittleEndian, _>(reader)?,
+            public_export_hash: reader.read_u64_le()?,
+            object_flags: reader.read_u32_le()?,
+            filter_flags: reader.read_num::<LittleEndian, u8>()?,
+        };
+        let _padding = reader.read_bytes(3)?;
+        Ok(entry)
+    }
+
+}
+
+/// A step of loading an export, `FExportBundleEntry` in Unreal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FExportBundleEntry {
+    pub local_export_index: u32,
+    pub command_type: u32,
+}
+
+impl FExportBundleEntry {
+
+    pub const COMMAND_CREATE: u32 = 0;
+    pub const COMMAND_SERIALIZE: u32 = 1;
+
+}
+
+/// The dependencies of one export, `FDependencyBundleHeader` in Unreal: a run of
+/// dependency bundle entries starting at `first_entry_index`, counted by the command
+/// that depends and the command depended on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FDependencyBundleHeader {
+    pub first_entry_index: i32,
+    pub entry_count: [[u32; 2]; 2],
+}
+
+/// The header of a package stored in an IoStore container, read from the start of
+/// its export bundle data chunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZenPackageHeader {
+    pub summary: ZenPackageSummary,
+    pub versioning_info: Option<ZenPackageVersioningInfo>,
+    pub names: NameTable,
+    pub package_name: FName,
+    pub imported_public_export_hashes: Vec<u64>,
+    pub import_map: Vec<FPackageObjectIndex>,
+    pub export_map: Vec<FExportMapEntry>,
+    pub export_bundle_entries: Vec<FExportBundleEntry>,
+    pub dependency_bundle_headers: Vec<FDependencyBundleHeader>,
+    /// The imports and exports dependency bundle headers point into.
+    pub dependency_bundle_entries: Vec<FPackageIndex>,
+    pub imported_package_names: Vec<FName>,
+}
+
+impl ZenPackageHeader {
+
+    /// Reads the header at the start of `data`, the package's export bundle data chunk.
+    pub fn read(data: &[u8]) -> ReaderResult<Self> {
+        let summary = SliceReader::new(data).with_context("Summary", ZenPackageSummary::read)?;
+        let header_size = summary.header_size as usize;
+        let data = data.get(..header_size).ok_or(ReadError::UnexpectedEof { offset: Some(data.len() as u64) })?;
+
+        let mut reader = SliceReader::new(data);
+        reader.set_position(ZenPackageSummary::SIZE)?;
+        let versioning_info = if summary.has_versioning_info {
+            Some(reader.with_context("VersioningInfo", ZenPackageVersioningInfo::read_from::<LittleEndian, _>)?)
+        } else {
+            None
+        };
+        let names = reader.with_context("NameMap", read_name_batch)?;
+        let package_name = names.resolve(summary.name).map_err(|e| e.context("Name"))?;
+
+        let imported_public_export_hashes = read_table(
+            &mut reader,
+            "ImportedPublicExportHashes",
+            (summary.imported_public_export_hashes_offset, summary.import_map_offset),
+            8,
+            |r| r.read_u64_le(),
+        )?;
+        let import_map = read_table(
+            &mut reader,
+            "ImportMap",
+            (summary.import_map_offset, summary.export_map_offset),
+            8,
+            FPackageObjectIndex::read_from::<LittleEndian, _>,
+        )?;
+        let export_map = read_table(
+            &mut reader,
+            "ExportMap",
+            (summary.export_map_offset, summary.export_bundle_entries_offset),
+            FExportMapEntry::SIZE,
+            |r| FExportMapEntry::read(r, &names),
+        )?;
+        let export_bundle_entries = read_table(
+            &mut reader,
+            "ExportBundleEntries",
+            (summary.export_bundle_entries_offset, summary.dependency_bundle_headers_offset),
+            8,
+            |r| Ok(FExportBundleEntry { local_export_index: r.read_u32_le()?, command_type: r.read_u32_le()? }),
+        )?;
+        let dependency_bundle_headers = read_table(
+            &mut reader,
+            "DependencyBundleHeaders",
+            (summary.dependency_bundle_headers_offset, summary.dependency_bundle_entries_offset),
+            20,
+            |r| {
+                let first_entry_index = r.read_i32_le()?;
+                let mut entry_count = [[0; 2]; 2];
+                for count in entry_count.iter_mut().flatten() {
+                    *count = r.read_u32_le()?;
+                }
+                Ok(FDependencyBundleHeader { first_entry_index, entry_count })
+            },
+        )?;
+        let dependency_bundle_entries = read_table(
+            &mut reader,
+            "DependencyBundleEntries",
+            (summary.dependency_bundle_entries_offset, summary.imported_package_names_offset),
+            4,
+            FPackageIndex::read_from::<LittleEndian, _>,
+        )?;
+
+        let imported_package_names = reader.with_context("ImportedPackageNames", |r| {
+            let offset = summary.imported_package_names_offset;
+            r.set_position(usize::try_from(offset).map_err(|_| ReadError::UnexpectedEof { offset: None })?)?;
+            let names = read_name_batch(r)?;
+            names.iter().map(|name| Ok(FName::new(name, r.read_i32_le()?))).collect::<ReaderResult<Vec<_>>>()
+        })?;
+
+        Ok(Self {
+            summary,
+            versioning_info,
+            names,
+            package_name,
+            imported_public_export_hashes,
+            import_map,
+            export_map,
+            export_bundle_entries,
+            dependency_bundle_headers,
+            dependency_bundle_entries,
+            imported_package_names,
+        })
+    }
+
+    /// Returns the offset of an export's data in the export bundle data chunk, which
+    /// starts right after the header.
+    pub fn export_data_offset(&self, export: &FExportMapEntry) -> u64 {
+        u64::from(self.summary.header_size) + export.cooked_serial_offset.saturating_sub(self.summary.cooked_header_size.into())
+    }
+
+    /// Builds the import and export maps a legacy package would have, so that
+    /// [`ObjectResolver`](crate::package::ObjectResolver) and other code written for
+    /// them works on Zen packages.
+    ///
+    /// The first imports match the import map one to one. The packages of imported
+    /// objects, and the outers of script objects, are appended after them.
+    ///
+    /// Zen packages only know objects of other packages by the hash of their path, so
+    /// those are named by the hash in hex. Script objects are named from
+    /// `script_objects` where given, and by the hash likewise otherwise; their classes
+    /// are guessed, as Zen packages do not save them. Exports get a
+    /// [`serial_offset`](FObjectExport::serial_offset) in the chunk, from
+    /// [`ZenPackageHeader::export_data_offset`], and no preload dependencies.
+    pub fn to_object_maps(&self, script_objects: Option<&ScriptObjects>) -> ReaderResult<(Vec<FObjectImport>, Vec<FObjectExport>)> {
+        let mut builder = ImportBuilder {
+            header: self,
+            script_objects,
+            imports: Vec::with_capacity(self.import_map.len()),
+            indices: BTreeMap::new(),
+            packages: BTreeMap::new(),
+        };
+        for (i, &index) in self.import_map.iter().enumerate() {
+            builder.imports.push(object_import(CORE_UOBJECT, "Object", FPackageIndex::NULL, FName::new("None", 0)));
+            if !index.is_null() {
+                builder.indices.entry(index).or_insert(i);
+            }
+        }
+        for (i, &index) in self.import_map.iter().enumerate() {
+            if !index.is_null() {
+                builder.imports[i] = builder.build(index, 0).map_err(|e| e.context("ImportMap").index(i))?;
+            }
+        }
+
+        let mut exports = Vec::with_capacity(self.export_map.len());
+        for (i, export) in self.export_map.iter().enumerate() {
+            let export = builder.export(export).map_err(|e| e.context("ExportMap").index(i))?;
+            exports.push(export);
+        }
+        Ok((builder.imports, exports))
+    }
+
+}
+
+/// The script objects of the engine and game modules, the `ScriptObjects` chunk of the
+/// global container, which script imports of Zen packages refer to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScriptObjects {
+    entries: BTreeMap<FPackageObjectIndex, ScriptObjectEntry>,
+}
+
+/// A script object, `FScriptObjectEntry` in Unreal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptObjectEntry {
+    pub object_name: FName,
+    pub global_index: FPackageObjectIndex,
+    pub outer_index: FPackageObjectIndex,
+    /// The class of a class default object, null for other objects.
+    pub cdo_class_index: FPackageObjectIndex,
+}
+
+impl ScriptObjects {
+
+    /// Reads the chunk: a name batch followed by the entries.
+    pub fn read(data: &[u8]) -> ReaderResult<Self> {
+        let mut reader = SliceReader::new(data);
+        let names = reader.with_context("Names", read_name_batch)?;
+        let entries = reader.with_context("Entries", |r| {
+            r.read_array::<LittleEndian, _>(|r| {
+                let object_name = names.resolve(read_mapped_name(r)?)?;
+                Ok(ScriptObjectEntry {
+                    object_name,
+                    global_index: FPackageObjectIndex::read_from::<LittleEndian, _>(r)?,
+                    outer_index: FPackageObjectIndex::read_from::<LittleEndian, _>(r)?,
+                    cdo_class_index: FPackageObjectIndex::read_from::<LittleEndian, _>(r)?,
+                })
+            })
+        })?;
+        Ok(Self { entries: entries.into_iter().map(|entry| (entry.global_index, entry)).collect() })
+    }
+
+    #[inline]
+    pub fn get(&self, index: FPackageObjectIndex) -> Option<&ScriptObjectEntry> {
+        self.entries.get(&index)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+}
+
+const CORE_UOBJECT: &str = "/Script/CoreUObject";
+
+fn object_import(class_package: &str, class_name: &str, outer_index: FPackageIndex, object_name: FName) -> FObjectImport {
+    FObjectImport {
+        class_package: FName::new(class_package, 0),
+        class_name: FName::new(class_name, 0),
+        outer_index,
+        object_name,
+        package_name: None,
+        import_optional: false,
+    }
+}
+
+/// Converts Zen object indices to legacy package indices, appending the imports the
+/// import map has no place for.
+struct ImportBuilder<'a> {
+    header: &'a ZenPackageHeader,
+    script_objects: Option<&'a ScriptObjects>,
+    imports: Vec<FObjectImport>,
+    indices: BTreeMap<FPackageObjectIndex, usize>,
+    /// The appended package imports, by position in the imported package names.
+    packages: BTreeMap<u32, usize>,
+}
+
+impl ImportBuilder<'_> {
+
+    fn resolve(&mut self, index: FPackageObjectIndex, depth: u32) -> ReaderResult<FPackageIndex> {
+        if index.is_null() {
+            return Ok(FPackageIndex::NULL);
+        }
+        if let Some(export) = index.to_export() {
+            return Ok(FPackageIndex::from_export(export));
+        }
+        if let Some(&import) = self.indices.get(&index) {
+            return Ok(FPackageIndex::from_import(import));
+        }
+
+        let import = self.build(index, depth)?;
+        self.imports.push(import);
+        self.indices.insert(index, self.imports.len() - 1);
+        Ok(FPackageIndex::from_import(self.imports.len() - 1))
+    }
+
+    fn build(&mut self, index: FPackageObjectIndex, depth: u32) -> ReaderResult<FObjectImport> {
+        if depth > MAX_OUTER_DEPTH {
+            return Err(ReadError::NestingLimit { limit: MAX_OUTER_DEPTH, offset: None });
+        }
+
+        let hex_name = |hash: u64| FName::new(format!("{:016X}", hash), 0);
+        if let Some((package, hash)) = index.to_package_import() {
+            let invalid = || ReadError::InvalidPackageIndex { index: package as i32, offset: None };
+            let hash = *self.header.imported_public_export_hashes.get(hash as usize).ok_or_else(invalid)?;
+            let outer = self.package(package).ok_or_else(invalid)?;
+            return Ok(object_import(CORE_UOBJECT, "Object", outer, hex_name(hash)));
+        }
+
+        let Some(entry) = self.script_objects.and_then(|objects| objects.get(index)) else {
+            return Ok(object_import(CORE_UOBJECT, "Object", FPackageIndex::NULL, hex_name(index.value())));
+        };
+        let outer = self.resolve(entry.outer_index, depth + 1)?;
+        let import = if entry.outer_index.is_null() {
+            object_import(CORE_UOBJECT, "Package", outer, entry.object_name.clone())
+        } else if let Some(class) = self.script_objects.and_then(|objects| objects.get(entry.cdo_class_index)) {
+            let class_package = self.script_objects.and_then(|objects| objects.get(class.outer_index));
+            let class_package = class_package.map_or(CORE_UOBJECT, |package| package.object_name.name());
+            object_import(class_package, class.object_name.name(), outer, entry.object_name.clone())
+        } else {
+            object_import(CORE_UOBJECT, "Class", outer, entry.object_name.clone())
+        };
+        Ok(import)
+    }
+
+    /// Returns the appended import of an imported package, adding it the first time.
+    fn package(&mut self, package: u32) -> Option<FPackageIndex> {
+        if let Some(&import) = self.packages.get(&package) {
+            return Some(FPackageIndex::from_import(import));
+        }
+
+        let name = self.header.imported_package_names.get(package as usize)?.clone();
+        self.imports.push(object_import(CORE_UOBJECT, "Package", FPackageIndex::NULL, name));
+        self.packages.insert(package, self.imports.len() - 1);
+        Some(FPackageIndex::from_import(self.imports.len() - 1))
+    }
+
+    fn export(&mut self, export: &FExportMapEntry) -> ReaderResult<FObjectExport> {
+        Ok(FObjectExport {
+            class_index: self.resolve(export.class_index, 0)?,
+            super_index: self.resolve(export.super_index, 0)?,
+            template_index: self.resolve(export.template_index, 0)?,
+            outer_index: self.resolve(export.outer_index, 0)?,
+            object_name: export.object_name.clone(),
+            object_flags: export.object_flags,
+            serial_size: export.cooked_serial_size as i64,
+            serial_offset: self.header.export_data_offset(export) as i64,
+            forced_export: false,
+            not_for_client: export.filter_flags & FExportMapEntry::FILTER_NOT_FOR_CLIENT != 0,
+            not_for_server: export.filter_flags & FExportMapEntry::FILTER_NOT_FOR_SERVER != 0,
+            package_guid: FGuid::default(),
+            is_inherited_instance: false,
+            package_flags: 0,
+            not_always_loaded_for_editor_game: false,
+            is_asset: false,
+            generate_public_hash: export.public_export_hash != 0,
+            first_export_dependency: -1,
+            serialization_before_serialization_dependencies: -1,
+            create_before_serialization_dependencies: -1,
+            serialization_before_create_dependencies: -1,
+            create_before_create_dependencies: -1,
+            script_serialization_start_offset: 0,
+            script_serialization_end_offset: 0,
+        })
+    }
+
+}
+
+/// Reads an `FMappedName`, whose index keeps the kind of name map in its top bits.
+fn read_mapped_name(reader: &mut SliceReader<'_>) -> ReaderResult<RawFName> {
+    const INDEX_MASK: u32 = (1 << 30) - 1;
+
+    let index = reader.read_u32_le()? & INDEX_MASK;
+    Ok(RawFName { index: index as i32, number: reader.read_i32_le()? })
+}
+
+/// Reads a name batch: the count, the hashes and the headers of every name, then the
+/// strings one after another.
+fn read_name_batch(reader: &mut SliceReader<'_>) -> ReaderResult<NameTable> {
+    let count = reader.read_i32_le()?;
+    let count = limits::check_array_len(count, reader.limits(), reader.offset())?;
+    if count == 0 {
+        return Ok(NameTable::new());
+    }
+
+    let _string_bytes = reader.read_u32_le()?;
+    let _hash_version = reader.read_u64_le()?;
+    let _hashes = reader.read_bytes(count as usize * 8)?;
+    let headers = reader.read_bytes(count as usize * 2)?;
+
+    let mut names = NameTable::new();
+    for header in headers.chunks_exact(2) {
+        let offset = reader.offset();
+        let length = usize::from(header[0] & 0x7F) << 8 | usize::from(header[1]);
+        if header[0] & 0x80 != 0 {
+            let bytes = reader.read_bytes(length * 2)?;
+            let units = bytes.chunks_exact(2).map(|unit| u16::from_le_bytes([unit[0], unit[1]]));
+            let name = char::decode_utf16(units)
+                .collect::<Result<String, _>>()
+                .map_err(|_| ReadError::InvalidString { encoding: StringEncoding::Utf16, offset })?;
+            names.push(name);
+        } else {
+            names.push(reader.read_bytes(length)?.iter().map(|&b| char::from(b)).collect::<String>());
+        }
+    }
+    Ok(names)
+}
+
+/// Reads the table between two summary offsets, of as many entries of `entry_size` as
+/// fit between them.
+fn read_table<'a, T>(
+    reader: &mut SliceReader<'a>,
+    name: &'static str,
+    (start, end): (i32, i32),
+    entry_size: usize,
+    read: impl FnMut(&mut SliceReader<'a>) -> ReaderResult<T>,
+) -> ReaderResult<Vec<T>> {
+    reader.with_context(name, |r| {
+        let invalid = || ReadError::UnexpectedEof { offset: u64::try_from(start).ok() };
+        let (Ok(start), Ok(end)) = (usize::try_from(start), usize::try_from(end)) else {
+            return Err(invalid());
+        };
+        if end < start || end > r.get_ref().len() {
+            return Err(invalid());
+        }
+
+        r.set_position(start)?;
+        let count = i32::try_from((end - start) / entry_size).map_err(|_| invalid())?;
+        r.read_array_with_length(read, count)
+    })
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::package::{FPackageIndex, ObjectResolver, ScriptObjects, ZenPackageHeader, ZenPackageSummary};
+    use crate::WriteExt;
+
+    fn write_name_batch(data: &mut Vec<u8>, names: &[&str]) {
+        data.write_i32_le(names.len() as i32).unwrap();
+        data.write_u32_le(names.iter().map(|name| name.len() as u32).sum()).unwrap();
+        data.write_u64_le(0xC164_0000).unwrap();
+        data.extend(names.iter().flat_map(|_| [0; 8]));
+        for name in names {
+            data.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        }
+        for name in names {
+            data.extend_from_slice(name.as_bytes());
+        }
+    }
+
+    fn write_export(data: &mut Vec<u8>, name: u32, outer: u64, class: u64) {
+        data.write_u64_le(0x100).unwrap();
+        data.write_u64_le(0x20).unwrap();
+        data.write_u32_le(name).unwrap();
+        data.write_u32_le(0).unwrap();
+        for index in [outer, class, u64::MAX, u64::MAX, 0] {
+            data.write_u64_le(index).unwrap();
+        }
+        data.write_u32_le(1).unwrap();
+        data.extend_from_slice(&[0; 4]);
+    }
+
+    #[test]
+    fn read_zen_package() {
+        const SCRIPT: u64 = 1 << 62;
+        const PACKAGE: u64 = 2 << 62;
+
+        let mut body = Vec::new();
+        write_name_batch(&mut body, &["/Game/Foo", "Foo", "Component"]);
+        let mut offsets = Vec::new();
+        offsets.push(body.len());
+        body.write_u64_le(0xAABB).unwrap();
+        offsets.push(body.len());
+        body.write_u64_le(SCRIPT | 0x1234).unwrap();
+        body.write_u64_le(PACKAGE).unwrap();
+        offsets.push(body.len());
+        write_export(&mut body, 1, u64::MAX, SCRIPT | 0x1234);
+        write_export(&mut body, 2, 0, PACKAGE);
+        offsets.push(body.len());
+        for value in [0, 0, 0, 1, 1, 0, 1, 1] {
+            body.write_u32_le(value).unwrap();
+        }
+        offsets.push(body.len());
+        for value in [0, 1, 0, 0, 0] {
+            body.write_i32_le(value).unwrap();
+        }
+        offsets.push(body.len());
+        body.write_i32_le(-1).unwrap();
+        offsets.push(body.len());
+        write_name_batch(&mut body, &["/Game/Other"]);
+        body.write_i32_le(0).unwrap();
+
+        let header_size = ZenPackageSummary::SIZE + body.len();
+        let mut data = Vec::new();
+        for value in [0, header_size as u32, 0, 0, 0, 0x80] {
+            data.write_u32_le(value).unwrap();
+        }
+        for offset in offsets {
+            data.write_i32_le((ZenPackageSummary::SIZE + offset) as i32).unwrap();
+        }
+        data.extend_from_slice(&body);
+
+        let header = ZenPackageHeader::read(&data).unwrap();
+        assert_eq!(header.package_name.to_string(), "/Game/Foo");
+        assert_eq!(header.export_map.len(), 2);
+        assert_eq!(header.export_bundle_entries.len(), 4);
+        assert_eq!(header.dependency_bundle_headers[0].entry_count, [[1, 0], [0, 0]]);
+        assert_eq!(header.dependency_bundle_entries, [FPackageIndex::from_import(0)]);
+        assert_eq!(header.imported_package_names[0].to_string(), "/Game/Other");
+        assert_eq!(header.export_data_offset(&header.export_map[0]), header_size as u64 + 0x100 - 0x80);
+
+        let mut script_data = Vec::new();
+        write_name_batch(&mut script_data, &["/Script/Engine", "StaticMesh"]);
+        script_data.write_i32_le(2).unwrap();
+        for (name, global, outer) in [(0, SCRIPT | 1, u64::MAX), (1, SCRIPT | 0x1234, SCRIPT | 1)] {
+            script_data.write_u32_le(name).unwrap();
+            script_data.write_u32_le(0).unwrap();
+            for index in [global, outer, u64::MAX] {
+                script_data.write_u64_le(index).unwrap();
+            }
+        }
+        let script_objects = ScriptObjects::read(&script_data).unwrap();
+
+        let (imports, exports) = header.to_object_maps(Some(&script_objects)).unwrap();
+        let resolver = ObjectResolver::new("/Game/Foo", &imports, &exports);
+        assert_eq!(resolver.full_name(FPackageIndex::from_export(0)).unwrap(), "/Script/Engine.StaticMesh'/Game/Foo.Foo'");
+        assert_eq!(resolver.path_name(FPackageIndex::from_export(1)).unwrap(), "/Game/Foo.Foo:Component");
+        assert_eq!(resolver.path_name(FPackageIndex::from_import(1)).unwrap(), "/Game/Other.000000000000AABB");
+        assert_eq!(imports.len(), 4);
+    }
+}
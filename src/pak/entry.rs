@@ -0,0 +1,166 @@
+use byteorder::LittleEndian;
+
+use alloc::vec::Vec;
+
+use crate::pak::info::{read_offset, PakInfo};
+use crate::{ReadExt, ReaderResult, SliceReader};
+
+/// The byte range of one compressed block of an entry, `FPakCompressedBlock` in Unreal.
+///
+/// Since version 5 the range is relative to the entry's offset; before, it is absolute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompressedBlock {
+    pub start: u64,
+    pub end: u64,
+}
+
+/// The location and layout of a file in a pak, `FPakEntry` in Unreal.
+///
+/// A copy of the entry is also saved right before the file's data, which is where the
+/// data actually starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PakEntry {
+    /// The offset of the entry's header in the pak.
+    pub offset: u64,
+    /// The size of the data as stored, compressed or not.
+    pub size: u64,
+    pub uncompressed_size: u64,
+    /// The compression method as stored; see [`PakInfo::compression_method`].
+    pub compression_method: u32,
+    /// The SHA1 hash of the stored data, zero where the index does not keep it.
+    pub hash: [u8; 20],
+    pub compression_blocks: Vec<CompressedBlock>,
+    pub flags: u8,
+    /// The uncompressed size of every compressed block but the last.
+    pub compression_block_size: u32,
+}
+
+impl PakEntry {
+
+    pub const FLAG_ENCRYPTED: u8 = 0x01;
+    pub const FLAG_DELETED: u8 = 0x02;
+
+    #[inline]
+    pub fn is_encrypted(&self) -> bool {
+        self.flags & Self::FLAG_ENCRYPTED != 0
+    }
+
+    /// Returns true for delete records, which patch paks use to remove a file of a pak
+    /// mounted before them.
+    #[inline]
+    pub fn is_deleted(&self) -> bool {
+        self.flags & Self::FLAG_DELETED != 0
+    }
+
+    /// Reads an entry in its full layout, as the legacy index and entry headers save it.
+    pub(crate) fn read(reader: &mut SliceReader<'_>, info: &PakInfo) -> ReaderResult<Self> {
+        let offset = read_offset(reader)?;
+        let size = read_offset(reader)?;
+        let uncompressed_size = read_offset(reader)?;
+        let compression_method = if info.is_v8a() {
+            reader.read_num::<LittleEndian, u8>()?.into()
+        } else {
+            reader.read_u32_le()?
+        };
+        if info.version < PakInfo::VERSION_NO_TIMESTAMPS {
+            let _timestamp = reader.read_i64_le()?;
+        }
+
+        let mut hash = [0; 20];
+        reader.read_exact_bytes(&mut hash)?;
+
+        let mut compression_blocks = Vec::new();
+        let mut flags = 0;
+        let mut compression_block_size = 0;
+        if info.version >= PakInfo::VERSION_COMPRESSION_ENCRYPTION {
+            if compression_method != 0 {
+                compression_blocks = reader.read_array::<LittleEndian, _>(|r| {
+                    Ok(CompressedBlock { start: r.read_u64_le()?, end: r.read_u64_le()? })
+                })?;
+            }
+            flags = reader.read_num::<LittleEndian, u8>()?;
+            compression_block_size = reader.read_u32_le()?;
+        }
+
+        Ok(Self {
+            offset,
+            size,
+            uncompressed_size,
+            compression_method,
+            hash,
+            compression_blocks,
+            flags,
+            compression_block_size,
+        })
+    }
+
+    /// Decodes an entry from the bit-packed layout of the encoded entries of version 10
+    /// indexes, `FPakFile::DecodePakEntry` in Unreal.
+    pub(crate) fn decode(reader: &mut SliceReader<'_>, info: &PakInfo) -> ReaderResult<Self> {
+        let value = reader.read_u32_le()?;
+        let compression_block_size = if value & 0x3F == 0x3F {
+            reader.read_u32_le()?
+        } else {
+            (value & 0x3F) << 11
+        };
+        let compression_method = (value >> 23) & 0x3F;
+        let encrypted = value & (1 << 22) != 0;
+        let block_count = (value >> 6) & 0xFFFF;
+
+        let mut read_size = |is_32_bit: bool| -> ReaderResult<u64> {
+            if is_32_bit {
+                Ok(reader.read_u32_le()?.into())
+            } else {
+                read_offset(reader)
+            }
+        };
+        let offset = read_size(value & (1 << 31) != 0)?;
+        let uncompressed_size = read_size(value & (1 << 30) != 0)?;
+        let size = if compression_method != 0 { read_size(value & (1 << 29) != 0)? } else { uncompressed_size };
+
+        let mut entry = Self {
+            offset,
+            size,
+            uncompressed_size,
+            compression_method,
+            hash: [0; 20],
+            compression_blocks: Vec::new(),
+            flags: if encrypted { Self::FLAG_ENCRYPTED } else { 0 },
+            compression_block_size,
+        };
+
+        // Blocks are laid out back to back after the entry header, each padded to the
+        // AES block size when encrypted.
+        let base = if info.version >= PakInfo::VERSION_RELATIVE_CHUNK_OFFSETS { 0 } else { offset };
+        let mut start = base + entry.header_size(info, block_count as usize);
+        if block_count == 1 && !encrypted {
+            entry.compression_blocks.push(CompressedBlock { start, end: start.saturating_add(size) });
+        } else {
+            for _ in 0..block_count {
+                let block_size = u64::from(reader.read_u32_le()?);
+                entry.compression_blocks.push(CompressedBlock { start, end: start + block_size });
+                start += if encrypted { block_size.next_multiple_of(16) } else { block_size };
+            }
+        }
+
+        Ok(entry)
+    }
+
+    /// Returns the size of the header saved before the entry's data, for an entry with
+    /// `block_count` compressed blocks.
+    pub(crate) fn header_size(&self, info: &PakInfo, block_count: usize) -> u64 {
+        let mut size = 8 + 8 + 8 + 20;
+        size += if info.is_v8a() { 1 } else { 4 };
+        if info.version < PakInfo::VERSION_NO_TIMESTAMPS {
+            size += 8;
+        }
+        if info.version >= PakInfo::VERSION_COMPRESSION_ENCRYPTION {
+            if self.compression_method != 0 {
+                size += 4 + 16 * block_count as u64;
+            }
+            size += 1 + 4;
+        }
+        size
+    }
+
+}
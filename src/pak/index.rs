@@ -0,0 +1,134 @@
+use byteorder::LittleEndian;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::limits;
+use crate::pak::info::{read_offset, PakInfo};
+use crate::pak::PakEntry;
+use crate::{ReadError, ReadExt, ReaderResult, SliceReader};
+
+/// Where a secondary index of a version 10 pak is saved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct IndexRegion {
+    pub(crate) offset: u64,
+    pub(crate) size: u64,
+    pub(crate) hash: [u8; 20],
+}
+
+/// The primary index of a pak: every entry with its path before version 10, and the
+/// encoded entries the secondary indexes point into since.
+#[derive(Debug, Default)]
+pub(crate) struct PrimaryIndex {
+    pub(crate) mount_point: String,
+    pub(crate) entries: Vec<(String, PakEntry)>,
+    pub(crate) path_hash_seed: u64,
+    pub(crate) path_hash_index: Option<IndexRegion>,
+    pub(crate) full_directory_index: Option<IndexRegion>,
+    encoded_entries: Vec<u8>,
+    non_encoded_entries: Vec<PakEntry>,
+}
+
+impl PrimaryIndex {
+
+    pub(crate) fn read(reader: &mut SliceReader<'_>, info: &PakInfo) -> ReaderResult<Self> {
+        let mount_point = reader.with_context("MountPoint", |r| r.read_fstring::<LittleEndian>())?;
+        let entry_count = reader.read_i32_le()?;
+
+        if info.version < PakInfo::VERSION_PATH_HASH_INDEX {
+            let entries = reader.with_context("Entries", |r| {
+                r.read_array_with_length(|r| Ok((r.read_fstring::<LittleEndian>()?, PakEntry::read(r, info)?)), entry_count)
+            })?;
+            return Ok(Self { mount_point, entries, ..Self::default() });
+        }
+
+        let path_hash_seed = reader.read_u64_le()?;
+        let path_hash_index = reader.with_context("PathHashIndex", read_region)?;
+        let full_directory_index = reader.with_context("FullDirectoryIndex", read_region)?;
+        let encoded_entries = reader.with_context("EncodedPakEntries", |r| {
+            let size = r.read_i32_le()?;
+            let size = limits::check_array_len(size, r.limits(), r.offset())?;
+            limits::read_buffer(r, size)
+        })?;
+        let non_encoded_entries = reader.with_context("NonEncodableEntries", |r| {
+            r.read_array::<LittleEndian, _>(|r| PakEntry::read(r, info))
+        })?;
+
+        Ok(Self {
+            mount_point,
+            entries: Vec::new(),
+            path_hash_seed,
+            path_hash_index,
+            full_directory_index,
+            encoded_entries,
+            non_encoded_entries,
+        })
+    }
+
+    /// Returns the entry a secondary index points to: an offset into the encoded entries
+    /// if not negative, and a one's complement index into the entries that could not be
+    /// encoded otherwise.
+    pub(crate) fn entry_at(&self, location: i32, info: &PakInfo) -> ReaderResult<PakEntry> {
+        let invalid = || ReadError::UnexpectedEof { offset: None };
+        match usize::try_from(location) {
+            Ok(offset) => {
+                let mut reader = SliceReader::new(&self.encoded_entries);
+                reader.set_position(offset).map_err(|_| invalid())?;
+                PakEntry::decode(&mut reader, info)
+            }
+            Err(_) => {
+                let index = usize::try_from(-(location as i64) - 1).map_err(|_| invalid())?;
+                self.non_encoded_entries.get(index).cloned().ok_or_else(invalid)
+            }
+        }
+    }
+
+}
+
+fn read_region(reader: &mut SliceReader<'_>) -> ReaderResult<Option<IndexRegion>> {
+    if !reader.read_bool::<LittleEndian>()? {
+        return Ok(None);
+    }
+
+    let offset = read_offset(reader)?;
+    let size = read_offset(reader)?;
+    let mut hash = [0; 20];
+    reader.read_exact_bytes(&mut hash)?;
+    Ok(Some(IndexRegion { offset, size, hash }))
+}
+
+/// Reads a full directory index, returning the path of every file relative to the
+/// mount point with the location of its entry.
+pub(crate) fn read_directory_index(reader: &mut SliceReader<'_>) -> ReaderResult<Vec<(String, i32)>> {
+    let mut files = Vec::new();
+    let directories = reader.read_i32_le()?;
+    let directories = limits::check_array_len(directories, reader.limits(), reader.offset())?;
+    for index in 0..directories as usize {
+        let directory = reader.read_fstring::<LittleEndian>().map_err(|e| e.index(index))?;
+        let directory = directory.trim_start_matches('/');
+        let entries = reader.read_array::<LittleEndian, _>(|r| Ok((r.read_fstring::<LittleEndian>()?, r.read_i32_le()?)));
+        for (file, location) in entries.map_err(|e| e.index(index))? {
+            files.push((alloc::format!("{}{}", directory, file), location));
+        }
+    }
+    Ok(files)
+}
+
+/// Reads a path hash index, the path hash of every file with the location of its entry.
+pub(crate) fn read_path_hash_index(reader: &mut SliceReader<'_>) -> ReaderResult<Vec<(u64, i32)>> {
+    reader.read_array::<LittleEndian, _>(|r| Ok((r.read_u64_le()?, r.read_i32_le()?)))
+}
+
+/// Hashes a path relative to the mount point as path hash indexes do, with FNV-64 over
+/// its lowercase UTF-16 code units. Before version 11 only the first half of the bytes
+/// was hashed.
+pub(crate) fn path_hash(path: &str, seed: u64, version: i32) -> u64 {
+    const OFFSET_BASIS: u64 = 0xCBF29CE484222325;
+    const PRIME: u64 = 0x100000001B3;
+
+    let bytes: Vec<u8> = path.to_lowercase().encode_utf16().flat_map(u16::to_le_bytes).collect();
+    let length = if version >= PakInfo::VERSION_FNV64_BUG_FIX { bytes.len() } else { bytes.len() / 2 };
+    bytes[..length]
+        .iter()
+        .fold(OFFSET_BASIS.wrapping_add(seed), |hash, &byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME))
+}
@@ -0,0 +1,217 @@
+use std::io::{Read, Seek, SeekFrom};
+
+use byteorder::LittleEndian;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::compression::CompressionMethod;
+use crate::types::FGuid;
+use crate::{ReadError, ReadExt, ReadFrom, ReaderResult, SliceReader};
+
+/// The size of the footer fields every version has: magic, version, index offset, index
+/// size and index hash.
+const BASE_FOOTER_SIZE: usize = 44;
+
+/// The size of a compression method name in the footer.
+const COMPRESSION_METHOD_NAME_SIZE: usize = 32;
+
+/// The footer of a pak file, `FPakInfo` in Unreal, which locates its index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PakInfo {
+    pub version: i32,
+    /// The GUID of the key the pak is encrypted with, null for the default key.
+    pub encryption_key_guid: FGuid,
+    pub encrypted_index: bool,
+    pub index_offset: u64,
+    pub index_size: u64,
+    pub index_hash: [u8; 20],
+    pub index_is_frozen: bool,
+    /// The compression methods entries refer to by index, empty for unused slots.
+    /// Only saved since version 8.
+    pub compression_methods: Vec<String>,
+}
+
+impl PakInfo {
+
+    pub const MAGIC: u32 = 0x5A6F12E1;
+
+    pub const VERSION_INITIAL: i32 = 1;
+    pub const VERSION_NO_TIMESTAMPS: i32 = 2;
+    pub const VERSION_COMPRESSION_ENCRYPTION: i32 = 3;
+    pub const VERSION_INDEX_ENCRYPTION: i32 = 4;
+    pub const VERSION_RELATIVE_CHUNK_OFFSETS: i32 = 5;
+    pub const VERSION_DELETE_RECORDS: i32 = 6;
+    pub const VERSION_ENCRYPTION_KEY_GUID: i32 = 7;
+    pub const VERSION_FNAME_BASED_COMPRESSION_METHOD: i32 = 8;
+    pub const VERSION_FROZEN_INDEX: i32 = 9;
+    pub const VERSION_PATH_HASH_INDEX: i32 = 10;
+    pub const VERSION_FNV64_BUG_FIX: i32 = 11;
+    pub const VERSION_LATEST: i32 = 11;
+
+    /// The footer sizes, each with the versions whose footer has that size.
+    const LAYOUTS: &'static [(usize, &'static [i32])] = &[
+        (222, &[Self::VERSION_FROZEN_INDEX]),
+        (221, &[Self::VERSION_FNAME_BASED_COMPRESSION_METHOD, Self::VERSION_PATH_HASH_INDEX, Self::VERSION_FNV64_BUG_FIX]),
+        (189, &[Self::VERSION_FNAME_BASED_COMPRESSION_METHOD]),
+        (61, &[Self::VERSION_ENCRYPTION_KEY_GUID]),
+        (45, &[Self::VERSION_INDEX_ENCRYPTION, Self::VERSION_RELATIVE_CHUNK_OFFSETS, Self::VERSION_DELETE_RECORDS]),
+        (44, &[Self::VERSION_INITIAL, Self::VERSION_NO_TIMESTAMPS, Self::VERSION_COMPRESSION_ENCRYPTION]),
+    ];
+
+    /// Finds and reads the footer at the end of `reader`.
+    ///
+    /// The footer grew over the versions, so every known size is tried until one has
+    /// the magic where its layout puts it.
+    pub fn read<R: Read + Seek + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        let length = reader.seek(SeekFrom::End(0))?;
+
+        let mut found = None;
+        for &(size, versions) in Self::LAYOUTS {
+            let Some(start) = length.checked_sub(size as u64) else {
+                continue;
+            };
+
+            let mut footer = alloc::vec![0; size];
+            reader.seek(SeekFrom::Start(start))?;
+            reader.read_exact(&mut footer)?;
+
+            let magic_offset = size - BASE_FOOTER_SIZE - Self::trailer_size(size);
+            let magic = u32::from_le_bytes(footer[magic_offset..magic_offset + 4].try_into().unwrap());
+            let version = i32::from_le_bytes(footer[magic_offset + 4..magic_offset + 8].try_into().unwrap());
+            if magic != Self::MAGIC {
+                found.get_or_insert(magic);
+                continue;
+            }
+            if !(Self::VERSION_INITIAL..=Self::VERSION_LATEST).contains(&version) {
+                return Err(ReadError::UnsupportedVersion { version, offset: Some(start + magic_offset as u64 + 4) });
+            }
+            if !versions.contains(&version) {
+                continue;
+            }
+
+            let mut footer_reader = SliceReader::new(&footer);
+            return Self::read_footer(&mut footer_reader, size, version);
+        }
+
+        let offset = length.checked_sub(BASE_FOOTER_SIZE as u64);
+        Err(ReadError::InvalidMagic { found: found.unwrap_or(0), offset })
+    }
+
+    /// Returns the size of the fields after the base fields of a footer of `size` bytes.
+    fn trailer_size(size: usize) -> usize {
+        match size {
+            222 => 1 + 5 * COMPRESSION_METHOD_NAME_SIZE,
+            221 => 5 * COMPRESSION_METHOD_NAME_SIZE,
+            189 => 4 * COMPRESSION_METHOD_NAME_SIZE,
+            _ => 0,
+        }
+    }
+
+    fn read_footer(reader: &mut SliceReader<'_>, size: usize, version: i32) -> ReaderResult<Self> {
+        let encryption_key_guid = if version >= Self::VERSION_ENCRYPTION_KEY_GUID {
+            FGuid::read_from::<LittleEndian, _>(reader)?
+        } else {
+            FGuid::default()
+        };
+        let encrypted_index = version >= Self::VERSION_INDEX_ENCRYPTION && reader.read_num::<LittleEndian, u8>()? != 0;
+
+        let _magic = reader.read_u32_le()?;
+        let _version = reader.read_i32_le()?;
+        let index_offset = read_offset(reader)?;
+        let index_size = read_offset(reader)?;
+        let mut index_hash = [0; 20];
+        reader.read_exact_bytes(&mut index_hash)?;
+        let index_is_frozen = version == Self::VERSION_FROZEN_INDEX && reader.read_num::<LittleEndian, u8>()? != 0;
+
+        let mut compression_methods = Vec::new();
+        if version >= Self::VERSION_FNAME_BASED_COMPRESSION_METHOD {
+            let count = (Self::trailer_size(size) - usize::from(version == Self::VERSION_FROZEN_INDEX)) / COMPRESSION_METHOD_NAME_SIZE;
+            for _ in 0..count {
+                let name = reader.read_bytes(COMPRESSION_METHOD_NAME_SIZE)?;
+                let length = name.iter().position(|&b| b == 0).unwrap_or(name.len());
+                compression_methods.push(String::from_utf8_lossy(&name[..length]).into_owned());
+            }
+        }
+
+        Ok(Self {
+            version,
+            encryption_key_guid,
+            encrypted_index,
+            index_offset,
+            index_size,
+            index_hash,
+            index_is_frozen,
+            compression_methods,
+        })
+    }
+
+    /// Returns true for version 8 paks written before a fifth compression method slot
+    /// was added, whose entries store the method index in a single byte.
+    #[inline]
+    pub(crate) fn is_v8a(&self) -> bool {
+        self.version == Self::VERSION_FNAME_BASED_COMPRESSION_METHOD && self.compression_methods.len() == 4
+    }
+
+    /// Resolves the compression method an entry stores: an index into
+    /// [`compression_methods`](PakInfo::compression_methods) counted from 1 since
+    /// version 8, and flags before.
+    pub fn compression_method(&self, stored: u32) -> ReaderResult<CompressionMethod> {
+        const COMPRESS_ZLIB: u32 = 0x01;
+        const COMPRESS_GZIP: u32 = 0x02;
+        const COMPRESS_CUSTOM: u32 = 0x04;
+
+        if stored == 0 {
+            return Ok(CompressionMethod::None);
+        }
+
+        let unsupported = |method: &str| ReadError::UnsupportedCompression { method: method.into(), offset: None };
+        if self.version < Self::VERSION_FNAME_BASED_COMPRESSION_METHOD {
+            return match stored & (COMPRESS_ZLIB | COMPRESS_GZIP | COMPRESS_CUSTOM) {
+                COMPRESS_ZLIB => Ok(CompressionMethod::Zlib),
+                COMPRESS_GZIP => Ok(CompressionMethod::Gzip),
+                _ => Err(unsupported("Custom")),
+            };
+        }
+
+        let name = self.compression_methods.get(stored as usize - 1).map(String::as_str).unwrap_or_default();
+        CompressionMethod::from_name(name).ok_or_else(|| unsupported(name))
+    }
+
+}
+
+/// Reads an i64 offset or size, which may not be negative.
+pub(crate) fn read_offset(reader: &mut SliceReader<'_>) -> ReaderResult<u64> {
+    let offset = reader.offset();
+    let value = reader.read_i64_le()?;
+    u64::try_from(value).map_err(|_| ReadError::NegativeLength { length: value, offset })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::compression::CompressionMethod;
+    use crate::pak::PakInfo;
+    use crate::{ReadError, WriteExt};
+
+    #[test]
+    fn read_legacy_footer() {
+        let mut data = vec![0; 100];
+        data.write_u32_le(PakInfo::MAGIC).unwrap();
+        data.write_i32_le(PakInfo::VERSION_COMPRESSION_ENCRYPTION).unwrap();
+        data.write_i64_le(10).unwrap();
+        data.write_i64_le(20).unwrap();
+        data.extend_from_slice(&[0xAA; 20]);
+
+        let info = PakInfo::read(&mut Cursor::new(&data)).unwrap();
+        assert_eq!(info.version, PakInfo::VERSION_COMPRESSION_ENCRYPTION);
+        assert_eq!((info.index_offset, info.index_size), (10, 20));
+        assert_eq!(info.index_hash, [0xAA; 20]);
+        assert!(!info.encrypted_index);
+        assert_eq!(info.compression_method(1).unwrap(), CompressionMethod::Zlib);
+
+        data[100] ^= 0xFF;
+        assert!(matches!(PakInfo::read(&mut Cursor::new(&data)), Err(ReadError::InvalidMagic { .. })));
+    }
+}
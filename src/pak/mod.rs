@@ -0,0 +1,773 @@
+//! `.pak` archives: the footer, the indexes, and the data of their entries.
+//!
+//! Decompressing entries needs the cargo feature of their compression method; see
+//! [`compression`](crate::compression). Encrypted indexes and entries need the `aes`
+//! feature and a key given to [`PakReader::with_key`].
+
+mod entry;
+mod index;
+mod info;
+mod stream;
+
+pub use entry::{CompressedBlock, PakEntry};
+pub use info::PakInfo;
+pub use stream::PakEntryReader;
+
+use std::io::{Read, Seek, SeekFrom};
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::compression::CompressionMethod;
+use crate::limits;
+#[cfg(feature = "aes")]
+pub(crate) use crate::crypto::AesKey;
+use crate::pak::index::{IndexRegion, PrimaryIndex};
+use crate::{Limits, ReadError, ReadExt, ReaderResult, SliceReader};
+
+/// The size of the pieces uncompressed entries are read in.
+const UNCOMPRESSED_CHUNK_SIZE: u64 = 64 * 1024;
+
+/// Stands in for the key without the `aes` feature, where none can be given.
+#[cfg(not(feature = "aes"))]
+#[derive(Clone)]
+pub(crate) enum AesKey {}
+
+#[cfg(not(feature = "aes"))]
+impl AesKey {
+
+    pub(crate) fn decrypt(&self, _data: &mut [u8]) {
+        match *self {}
+    }
+
+}
+
+/// A pak archive opened for reading.
+///
+/// Paths are relative to the [mount point](PakReader::mount_point), as in
+/// `Game/Content/Maps/Entry.umap`.
+pub struct PakReader<R> {
+    inner: R,
+    limits: Limits,
+    length: u64,
+    info: PakInfo,
+    mount_point: String,
+    entries: BTreeMap<String, PakEntry>,
+    path_hash_seed: u64,
+    hashed_entries: BTreeMap<u64, PakEntry>,
+    key: Option<AesKey>,
+}
+
+impl<R: Read + Seek> PakReader<R> {
+
+    pub fn new(inner: R) -> ReaderResult<Self> {
+        Self::with_limits(inner, Limits::DEFAULT)
+    }
+
+    /// Reads the footer and the indexes of a pak, checking the sizes of what they
+    /// locate against `limits`.
+    ///
+    /// Since version 10 paths are listed from the full directory index. A pak saved
+    /// without one only has its path hash index, so its entries can be opened but not
+    /// listed.
+    pub fn with_limits(inner: R, limits: Limits) -> ReaderResult<Self> {
+        Self::open(inner, limits, None)
+    }
+
+    /// Reads a pak whose index or entries are encrypted with `key`.
+    ///
+    /// A wrong key is reported as [`ReadError::InvalidKey`], found by checking the
+    /// decrypted index against its hash, or its mount point where no hash was saved.
+    /// Entries are not checked, and decrypt to data that fails to decompress or parse.
+    #[cfg(feature = "aes")]
+    pub fn with_key(inner: R, key: AesKey) -> ReaderResult<Self> {
+        Self::with_key_and_limits(inner, key, Limits::DEFAULT)
+    }
+
+    /// Reads a pak encrypted with `key`, checking sizes against `limits`.
+    #[cfg(feature = "aes")]
+    pub fn with_key_and_limits(inner: R, key: AesKey, limits: Limits) -> ReaderResult<Self> {
+        Self::open(inner, limits, Some(key))
+    }
+
+    fn open(mut inner: R, limits: Limits, key: Option<AesKey>) -> ReaderResult<Self> {
+        let info = PakInfo::read(&mut inner)?;
+        let length = inner.seek(SeekFrom::End(0))?;
+        if info.index_is_frozen {
+            return Err(ReadError::UnsupportedVersion { version: info.version, offset: Some(info.index_offset) });
+        }
+
+        let region = IndexRegion { offset: info.index_offset, size: info.index_size, hash: info.index_hash };
+        let data = read_index(&mut inner, &limits, length, &region, info.encrypted_index, key.as_ref())?;
+        if info.encrypted_index && !has_valid_mount_point(&data) {
+            return Err(ReadError::InvalidKey { offset: Some(info.index_offset) });
+        }
+        let index = SliceReader::new(&data).with_context("Index", |r| PrimaryIndex::read(r, &info))?;
+
+        let mut pak = Self {
+            inner,
+            limits,
+            length,
+            info,
+            mount_point: index.mount_point.clone(),
+            entries: BTreeMap::new(),
+            path_hash_seed: index.path_hash_seed,
+            hashed_entries: BTreeMap::new(),
+            key,
+        };
+
+        if pak.info.version < PakInfo::VERSION_PATH_HASH_INDEX {
+            pak.entries = index.entries.into_iter().collect();
+        } else if let Some(region) = index.full_directory_index {
+            let data = pak.read_secondary_index(&region)?;
+            let files = SliceReader::new(&data).with_context("FullDirectoryIndex", index::read_directory_index)?;
+            for (i, (path, location)) in files.into_iter().enumerate() {
+                let entry = index.entry_at(location, &pak.info).map_err(|e| e.context("FullDirectoryIndex").index(i))?;
+                pak.entries.insert(path, entry);
+            }
+        } else if let Some(region) = index.path_hash_index {
+            let data = pak.read_secondary_index(&region)?;
+            let hashes = SliceReader::new(&data).with_context("PathHashIndex", index::read_path_hash_index)?;
+            for (i, (hash, location)) in hashes.into_iter().enumerate() {
+                let entry = index.entry_at(location, &pak.info).map_err(|e| e.context("PathHashIndex").index(i))?;
+                pak.hashed_entries.insert(hash, entry);
+            }
+        }
+
+        Ok(pak)
+    }
+
+    /// Reads a secondary index, which is encrypted along with the primary index.
+    fn read_secondary_index(&mut self, region: &IndexRegion) -> ReaderResult<Vec<u8>> {
+        let encrypted = self.info.encrypted_index;
+        read_index(&mut self.inner, &self.limits, self.length, region, encrypted, self.key.as_ref())
+    }
+
+    #[inline]
+    pub fn info(&self) -> &PakInfo {
+        &self.info
+    }
+
+    /// Returns the path the pak's files are mounted at, such as `../../../`.
+    #[inline]
+    pub fn mount_point(&self) -> &str {
+        &self.mount_point
+    }
+
+    /// Returns the paths of the pak's files in order, leaving out delete records.
+    pub fn files(&self) -> impl Iterator<Item = &str> + '_ {
+        self.entries.iter().filter(|(_, entry)| !entry.is_deleted()).map(|(path, _)| path.as_str())
+    }
+
+    /// Returns the entry at `path`, looking it up by hash in paks with only a path hash
+    /// index.
+    pub fn entry(&self, path: &str) -> Option<&PakEntry> {
+        match self.entries.get(path) {
+            Some(entry) => Some(entry),
+            None => self.hashed_entries.get(&index::path_hash(path, self.path_hash_seed, self.info.version)),
+        }
+    }
+
+    /// Opens the data of the file at `path` for reading.
+    pub fn open_entry(&mut self, path: &str) -> ReaderResult<PakEntryReader<'_, R>> {
+        let entry = match self.entry(path) {
+            Some(entry) if !entry.is_deleted() => entry.clone(),
+            _ => return Err(ReadError::EntryNotFound { path: path.to_string(), offset: None }),
+        };
+        let key = match (&self.key, entry.is_encrypted()) {
+            (Some(key), true) => Some(key),
+            (None, true) => return Err(ReadError::Encrypted { offset: Some(entry.offset) }),
+            (_, false) => None,
+        };
+
+        // The header before the data repeats the entry with its hash, which encoded
+        // entries leave out.
+        let header_size = entry.header_size(&self.info, entry.compression_blocks.len());
+        let header = read_region(&mut self.inner, &self.limits, self.length, entry.offset, header_size)?;
+        let header = PakEntry::read(&mut SliceReader::new(&header), &self.info).map_err(|e| e.context("EntryHeader"))?;
+        let data_start = entry.offset + header_size;
+        let eof = || ReadError::UnexpectedEof { offset: Some(entry.offset) };
+        if data_start.checked_add(entry.size).is_none_or(|end| end > self.length) {
+            return Err(eof());
+        }
+
+        let method = self.info.compression_method(entry.compression_method).map_err(|e| e.at(entry.offset))?;
+        let (blocks, block_size) = if method == CompressionMethod::None {
+            let blocks = (0..entry.size)
+                .step_by(UNCOMPRESSED_CHUNK_SIZE as usize)
+                .map(|start| CompressedBlock {
+                    start: data_start + start,
+                    end: data_start + (start + UNCOMPRESSED_CHUNK_SIZE).min(entry.size),
+                })
+                .collect();
+            (blocks, UNCOMPRESSED_CHUNK_SIZE)
+        } else if entry.compression_blocks.is_empty() {
+            // Before version 3 a compressed file is a single block.
+            (vec![CompressedBlock { start: data_start, end: data_start + entry.size }], entry.uncompressed_size)
+        } else {
+            let base = if self.info.version >= PakInfo::VERSION_RELATIVE_CHUNK_OFFSETS { entry.offset } else { 0 };
+            let blocks = entry
+                .compression_blocks
+                .iter()
+                .map(|block| match (base.checked_add(block.start), base.checked_add(block.end)) {
+                    (Some(start), Some(end)) => Ok(CompressedBlock { start, end }),
+                    _ => Err(eof()),
+                })
+                .collect::<ReaderResult<_>>()?;
+            let block_size = match entry.compression_block_size {
+                0 => entry.uncompressed_size,
+                size => size.into(),
+            };
+            (blocks, block_size)
+        };
+
+        limits::check_buffer_size(block_size, &self.limits, Some(entry.offset))?;
+
+        Ok(PakEntryReader::new(
+            &mut self.inner,
+            self.limits,
+            self.length,
+            method,
+            blocks,
+            block_size,
+            entry.uncompressed_size,
+            header.hash,
+            key,
+        ))
+    }
+
+    /// Reads the whole data of the file at `path`.
+    pub fn read_entry(&mut self, path: &str) -> ReaderResult<Vec<u8>> {
+        let limits = self.limits;
+        let mut reader = self.open_entry(path)?;
+        let size = reader.uncompressed_size();
+        limits::check_buffer_size(size, &limits, None)?;
+
+        let mut data = Vec::with_capacity(size as usize);
+        reader.read_to_end(&mut data)?;
+        Ok(data)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+}
+
+/// Reads `size` bytes at `offset` of a stream `length` bytes long, checking the size
+/// against `limits` before allocating.
+pub(crate) fn read_region<R: Read + Seek + ?Sized>(
+    inner: &mut R,
+    limits: &Limits,
+    length: u64,
+    offset: u64,
+    size: u64,
+) -> ReaderResult<Vec<u8>> {
+    limits::check_buffer_size(size, limits, Some(offset))?;
+    if offset.checked_add(size).is_none_or(|end| end > length) {
+        return Err(ReadError::UnexpectedEof { offset: Some(offset) });
+    }
+
+    let mut buffer = vec![0; size as usize];
+    inner.seek(SeekFrom::Start(offset))?;
+    inner.read_exact(&mut buffer).map_err(|e| ReadError::from(e).at(offset))?;
+    Ok(buffer)
+}
+
+/// Reads an index and checks its hash, decrypting it first if `encrypted`. The hash is
+/// of the decrypted index, so a mismatch there means the key is wrong.
+fn read_index<R: Read + Seek + ?Sized>(
+    inner: &mut R,
+    limits: &Limits,
+    length: u64,
+    region: &IndexRegion,
+    encrypted: bool,
+    key: Option<&AesKey>,
+) -> ReaderResult<Vec<u8>> {
+    let mut data = read_region(inner, limits, length, region.offset, region.size)?;
+    if !encrypted {
+        stream::verify_hash(&sha1_smol::Sha1::from(&data), &region.hash, region.offset)?;
+        return Ok(data);
+    }
+
+    decrypt(key, &mut data, region.offset)?;
+    stream::verify_hash(&sha1_smol::Sha1::from(&data), &region.hash, region.offset).map_err(|e| match e {
+        ReadError::HashMismatch { offset } => ReadError::InvalidKey { offset },
+        e => e,
+    })?;
+    Ok(data)
+}
+
+/// Decrypts data in place, failing when no key was given.
+pub(crate) fn decrypt(key: Option<&AesKey>, data: &mut [u8], offset: u64) -> ReaderResult<()> {
+    match key {
+        Some(key) => {
+            key.decrypt(data);
+            Ok(())
+        }
+        None => Err(ReadError::Encrypted { offset: Some(offset) }),
+    }
+}
+
+/// Returns true if a decrypted primary index starts with a null terminated mount point
+/// FString that fits in it, which data decrypted with a wrong key almost never does.
+pub(crate) fn has_valid_mount_point(index: &[u8]) -> bool {
+    let Some(length) = index.get(..4) else {
+        return false;
+    };
+    let length = i32::from_le_bytes(length.try_into().unwrap());
+    let (size, terminator) = if length < 0 { (i64::from(length) * -2, 2) } else { (i64::from(length), 1) };
+    if length == 0 {
+        return true;
+    }
+    match usize::try_from(size + 4).ok().and_then(|end| index.get(end - terminator..end)) {
+        Some(terminator) => terminator.iter().all(|&b| b == 0),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use byteorder::LittleEndian;
+
+    use crate::pak::{index, CompressedBlock, PakEntry, PakInfo, PakReader};
+    use crate::{ReadError, SliceReader, WriteExt};
+
+    fn sha1(data: &[u8]) -> [u8; 20] {
+        sha1_smol::Sha1::from(data).digest().bytes()
+    }
+
+    fn legacy_info(version: i32, compression_methods: &[&str]) -> PakInfo {
+        PakInfo {
+            version,
+            encryption_key_guid: Default::default(),
+            encrypted_index: false,
+            index_offset: 0,
+            index_size: 0,
+            index_hash: [0; 20],
+            index_is_frozen: false,
+            compression_methods: compression_methods.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    /// Writes an entry in the full layout of legacy indexes and entry headers.
+    fn write_entry(out: &mut Vec<u8>, info: &PakInfo, entry: &PakEntry) {
+        out.write_i64_le(entry.offset as i64).unwrap();
+        out.write_i64_le(entry.size as i64).unwrap();
+        out.write_i64_le(entry.uncompressed_size as i64).unwrap();
+        if info.is_v8a() {
+            out.write_num::<LittleEndian, u8>(entry.compression_method as u8).unwrap();
+        } else {
+            out.write_u32_le(entry.compression_method).unwrap();
+        }
+        if info.version < PakInfo::VERSION_NO_TIMESTAMPS {
+            out.write_i64_le(0).unwrap();
+        }
+        out.extend_from_slice(&entry.hash);
+        if info.version >= PakInfo::VERSION_COMPRESSION_ENCRYPTION {
+            if entry.compression_method != 0 {
+                out.write_i32_le(entry.compression_blocks.len() as i32).unwrap();
+                for block in &entry.compression_blocks {
+                    out.write_u64_le(block.start).unwrap();
+                    out.write_u64_le(block.end).unwrap();
+                }
+            }
+            out.write_num::<LittleEndian, u8>(entry.flags).unwrap();
+            out.write_u32_le(entry.compression_block_size).unwrap();
+        }
+    }
+
+    /// Appends an unencrypted `index` and the footer of `info`'s version to `pak`.
+    fn finish_pak(mut pak: Vec<u8>, info: &PakInfo, index: &[u8]) -> Vec<u8> {
+        let index_offset = pak.len();
+        pak.extend_from_slice(index);
+        if info.version >= PakInfo::VERSION_ENCRYPTION_KEY_GUID {
+            pak.extend_from_slice(&[0; 16]);
+        }
+        if info.version >= PakInfo::VERSION_INDEX_ENCRYPTION {
+            pak.write_num::<LittleEndian, u8>(0).unwrap();
+        }
+        pak.write_u32_le(PakInfo::MAGIC).unwrap();
+        pak.write_i32_le(info.version).unwrap();
+        pak.write_i64_le(index_offset as i64).unwrap();
+        pak.write_i64_le(index.len() as i64).unwrap();
+        pak.extend_from_slice(&sha1(index));
+        for name in &info.compression_methods {
+            let mut slot = [0; 32];
+            slot[..name.len()].copy_from_slice(name.as_bytes());
+            pak.extend_from_slice(&slot);
+        }
+        pak
+    }
+
+    /// Builds a pak with a pre-version 10 index of `entries`, whose headers and data
+    /// are already in `data`.
+    fn write_legacy_pak(info: &PakInfo, data: Vec<u8>, entries: &[(&str, PakEntry)]) -> Vec<u8> {
+        let mut index = Vec::new();
+        index.write_fstring::<LittleEndian>("../../../Game/").unwrap();
+        index.write_i32_le(entries.len() as i32).unwrap();
+        for (path, entry) in entries {
+            index.write_fstring::<LittleEndian>(path).unwrap();
+            write_entry(&mut index, info, entry);
+        }
+        finish_pak(data, info, &index)
+    }
+
+    /// Writes the entry header saved before an entry's data in a version 11 pak.
+    fn write_header(pak: &mut Vec<u8>, stored: &[u8], size: u64, uncompressed_size: u64, method: u32, blocks: &[(u64, u64)]) {
+        pak.write_i64_le(0).unwrap();
+        pak.write_i64_le(size as i64).unwrap();
+        pak.write_i64_le(uncompressed_size as i64).unwrap();
+        pak.write_u32_le(method).unwrap();
+        pak.extend_from_slice(&sha1(stored));
+        if method != 0 {
+            pak.write_i32_le(blocks.len() as i32).unwrap();
+            for &(start, end) in blocks {
+                pak.write_u64_le(start).unwrap();
+                pak.write_u64_le(end).unwrap();
+            }
+        }
+        pak.write_num::<LittleEndian, u8>(0).unwrap();
+        pak.write_u32_le(if method != 0 { 0x10000 } else { 0 }).unwrap();
+    }
+
+    /// Pads an index to whole AES blocks and encrypts it with [`KEY`] if `encrypt`,
+    /// returning the hash of the plain index.
+    fn seal(data: &mut Vec<u8>, encrypt: bool) -> [u8; 20] {
+        if encrypt {
+            data.resize(data.len().next_multiple_of(16), 0);
+        }
+        let hash = sha1(data);
+        #[cfg(feature = "aes")]
+        if encrypt {
+            encrypt_blocks(data);
+        }
+        hash
+    }
+
+    #[cfg(feature = "aes")]
+    const KEY: [u8; 32] = [0x42; 32];
+
+    #[cfg(feature = "aes")]
+    fn encrypt_blocks(data: &mut [u8]) {
+        use aes::cipher::generic_array::GenericArray;
+        use aes::cipher::{BlockEncrypt, KeyInit};
+
+        let cipher = aes::Aes256::new(&KEY.into());
+        for block in data.chunks_exact_mut(16) {
+            cipher.encrypt_block(GenericArray::from_mut_slice(block));
+        }
+    }
+
+    /// Builds a version 11 pak with a full directory index listing `Content/`, with its
+    /// indexes and an extra `secret.txt` entry encrypted if `encrypt`.
+    fn write_pak(encrypt: bool) -> Vec<u8> {
+        let mut pak = Vec::new();
+        let mut encoded = Vec::new();
+        let mut files = Vec::new();
+
+        write_header(&mut pak, b"hello pak", 9, 9, 0, &[]);
+        pak.extend_from_slice(b"hello pak");
+        encoded.write_u32_le(0xE000_0000).unwrap();
+        encoded.write_u32_le(0).unwrap();
+        encoded.write_u32_le(9).unwrap();
+        files.push(("a.txt", 0));
+
+        #[cfg(feature = "zlib")]
+        {
+            let data = b"compressed compressed compressed";
+            let stored = miniz_oxide::deflate::compress_to_vec_zlib(data, 6);
+            let offset = pak.len() as u32;
+            write_header(&mut pak, &stored, stored.len() as u64, data.len() as u64, 1, &[(73, 73 + stored.len() as u64)]);
+            pak.extend_from_slice(&stored);
+
+            files.push(("b.bin", encoded.len() as i32));
+            encoded.write_u32_le(0xE000_0000 | 1 << 23 | 1 << 6 | 32).unwrap();
+            encoded.write_u32_le(offset).unwrap();
+            encoded.write_u32_le(data.len() as u32).unwrap();
+            encoded.write_u32_le(stored.len() as u32).unwrap();
+        }
+
+        #[cfg(feature = "aes")]
+        if encrypt {
+            let mut stored = b"secret entry data".to_vec();
+            stored.resize(32, 0);
+            encrypt_blocks(&mut stored);
+            let offset = pak.len() as u32;
+            write_header(&mut pak, &stored, 17, 17, 0, &[]);
+            pak.extend_from_slice(&stored);
+
+            files.push(("secret.txt", encoded.len() as i32));
+            encoded.write_u32_le(0xE000_0000 | 1 << 22).unwrap();
+            encoded.write_u32_le(offset).unwrap();
+            encoded.write_u32_le(17).unwrap();
+        }
+
+        let mut directory_index = Vec::new();
+        directory_index.write_i32_le(1).unwrap();
+        directory_index.write_fstring::<LittleEndian>("/Content/").unwrap();
+        directory_index.write_i32_le(files.len() as i32).unwrap();
+        for (name, location) in &files {
+            directory_index.write_fstring::<LittleEndian>(name).unwrap();
+            directory_index.write_i32_le(*location).unwrap();
+        }
+        let directory_hash = seal(&mut directory_index, encrypt);
+        let directory_offset = pak.len();
+        pak.extend_from_slice(&directory_index);
+
+        let mut index = Vec::new();
+        index.write_fstring::<LittleEndian>("../../../Game/").unwrap();
+        index.write_i32_le(files.len() as i32).unwrap();
+        index.write_u64_le(0).unwrap();
+        index.write_u32_le(0).unwrap();
+        index.write_u32_le(1).unwrap();
+        index.write_i64_le(directory_offset as i64).unwrap();
+        index.write_i64_le(directory_index.len() as i64).unwrap();
+        index.extend_from_slice(&directory_hash);
+        index.write_i32_le(encoded.len() as i32).unwrap();
+        index.extend_from_slice(&encoded);
+        index.write_i32_le(0).unwrap();
+        let index_hash = seal(&mut index, encrypt);
+        let index_offset = pak.len();
+        pak.extend_from_slice(&index);
+
+        pak.extend_from_slice(&[0; 16]);
+        pak.write_num::<LittleEndian, u8>(encrypt.into()).unwrap();
+        pak.write_u32_le(PakInfo::MAGIC).unwrap();
+        pak.write_i32_le(PakInfo::VERSION_FNV64_BUG_FIX).unwrap();
+        pak.write_i64_le(index_offset as i64).unwrap();
+        pak.write_i64_le(index.len() as i64).unwrap();
+        pak.extend_from_slice(&index_hash);
+        for name in ["Zlib", "", "", "", ""] {
+            let mut slot = [0; 32];
+            slot[..name.len()].copy_from_slice(name.as_bytes());
+            pak.extend_from_slice(&slot);
+        }
+        pak
+    }
+
+    #[test]
+    fn read_v11_pak() {
+        let mut pak = PakReader::new(Cursor::new(write_pak(false))).unwrap();
+        assert_eq!(pak.info().version, PakInfo::VERSION_FNV64_BUG_FIX);
+        assert_eq!(pak.mount_point(), "../../../Game/");
+        assert_eq!(pak.read_entry("Content/a.txt").unwrap(), b"hello pak");
+
+        #[cfg(feature = "zlib")]
+        {
+            use std::io::Read;
+
+            assert_eq!(pak.files().collect::<Vec<_>>(), ["Content/a.txt", "Content/b.bin"]);
+            let mut data = Vec::new();
+            pak.open_entry("Content/b.bin").unwrap().read_to_end(&mut data).unwrap();
+            assert_eq!(data, b"compressed compressed compressed");
+        }
+
+        assert!(matches!(pak.read_entry("Content/missing.txt"), Err(ReadError::EntryNotFound { .. })));
+
+        // The first entry's data follows its 53 byte header.
+        let mut data = pak.into_inner().into_inner();
+        data[53] ^= 0xFF;
+        let mut pak = PakReader::new(Cursor::new(data)).unwrap();
+        assert!(matches!(pak.read_entry("Content/a.txt"), Err(ReadError::HashMismatch { offset: Some(53) })));
+    }
+
+    #[cfg(feature = "aes")]
+    #[test]
+    fn read_encrypted_pak() {
+        use crate::crypto::AesKey;
+        use crate::Limits;
+
+        let data = write_pak(true);
+        let mut pak = PakReader::with_key(Cursor::new(data.clone()), AesKey::new(&KEY)).unwrap();
+        assert_eq!(pak.mount_point(), "../../../Game/");
+        assert_eq!(pak.read_entry("Content/a.txt").unwrap(), b"hello pak");
+        assert_eq!(pak.read_entry("Content/secret.txt").unwrap(), b"secret entry data");
+
+        assert!(matches!(PakReader::new(Cursor::new(data.clone())), Err(ReadError::Encrypted { .. })));
+        let wrong_key = PakReader::with_key(Cursor::new(data.clone()), AesKey::new(&[0x24; 32]));
+        assert!(matches!(wrong_key, Err(ReadError::InvalidKey { .. })));
+
+        let limits = Limits { max_buffer_bytes: 16, ..Limits::DEFAULT };
+        let limited = PakReader::with_key_and_limits(Cursor::new(data), AesKey::new(&KEY), limits);
+        assert!(matches!(limited, Err(ReadError::AllocationLimit { limit: 16, .. })));
+    }
+
+    #[test]
+    fn reject_hostile_entry_layouts() {
+        // An entry claiming far more data than the pak holds.
+        let info = legacy_info(PakInfo::VERSION_COMPRESSION_ENCRYPTION, &[]);
+        let entry = PakEntry {
+            offset: 0,
+            size: 1 << 62,
+            uncompressed_size: 1 << 62,
+            compression_method: 0,
+            hash: [0; 20],
+            compression_blocks: Vec::new(),
+            flags: 0,
+            compression_block_size: 0,
+        };
+        let mut data = Vec::new();
+        write_entry(&mut data, &info, &entry);
+        let mut pak = PakReader::new(Cursor::new(write_legacy_pak(&info, data, &[("huge.bin", entry)]))).unwrap();
+        assert!(matches!(pak.read_entry("huge.bin"), Err(ReadError::UnexpectedEof { offset: Some(0) })));
+
+        // A block whose offset overflows once made absolute.
+        let info = legacy_info(PakInfo::VERSION_RELATIVE_CHUNK_OFFSETS, &[]);
+        let entry = PakEntry {
+            offset: 16,
+            size: 4,
+            uncompressed_size: 4,
+            compression_method: 1,
+            hash: [0; 20],
+            compression_blocks: vec![CompressedBlock { start: u64::MAX - 8, end: u64::MAX }],
+            flags: 0,
+            compression_block_size: 4,
+        };
+        let mut data = vec![0; 16];
+        write_entry(&mut data, &info, &entry);
+        data.extend_from_slice(&[0; 4]);
+        let mut pak = PakReader::new(Cursor::new(write_legacy_pak(&info, data, &[("wrapped.bin", entry)]))).unwrap();
+        assert!(matches!(pak.read_entry("wrapped.bin"), Err(ReadError::UnexpectedEof { offset: Some(16) })));
+    }
+
+    #[test]
+    fn read_legacy_paks() {
+        // Absolute block offsets, relative ones, and single byte compression methods.
+        for (version, methods) in [(3, &[][..]), (5, &[][..]), (8, &["Zlib", "", "", ""][..])] {
+            let info = legacy_info(version, methods);
+            let mut data = Vec::new();
+            let mut entries = Vec::new();
+
+            let entry = PakEntry {
+                offset: 0,
+                size: 9,
+                uncompressed_size: 9,
+                compression_method: 0,
+                hash: sha1(b"hello pak"),
+                compression_blocks: Vec::new(),
+                flags: 0,
+                compression_block_size: 0,
+            };
+            write_entry(&mut data, &info, &entry);
+            data.extend_from_slice(b"hello pak");
+            entries.push(("a.txt", entry));
+
+            #[cfg(feature = "zlib")]
+            {
+                let blocks: Vec<_> = b"first block, second block".chunks(16).map(|c| miniz_oxide::deflate::compress_to_vec_zlib(c, 6)).collect();
+                let stored = blocks.concat();
+                let mut entry = PakEntry {
+                    offset: data.len() as u64,
+                    size: stored.len() as u64,
+                    uncompressed_size: 25,
+                    compression_method: 1,
+                    hash: sha1(&stored),
+                    compression_blocks: Vec::new(),
+                    flags: 0,
+                    compression_block_size: 16,
+                };
+                let base = if version >= PakInfo::VERSION_RELATIVE_CHUNK_OFFSETS { 0 } else { entry.offset };
+                let mut start = base + entry.header_size(&info, blocks.len());
+                for block in &blocks {
+                    entry.compression_blocks.push(CompressedBlock { start, end: start + block.len() as u64 });
+                    start += block.len() as u64;
+                }
+                write_entry(&mut data, &info, &entry);
+                data.extend_from_slice(&stored);
+                entries.push(("b.bin", entry));
+            }
+
+            let mut pak = PakReader::new(Cursor::new(write_legacy_pak(&info, data, &entries))).unwrap();
+            assert_eq!(pak.info().version, version);
+            assert_eq!(pak.info().is_v8a(), version == PakInfo::VERSION_FNAME_BASED_COMPRESSION_METHOD);
+            assert_eq!(pak.mount_point(), "../../../Game/");
+            assert_eq!(pak.files().count(), entries.len());
+            assert_eq!(pak.read_entry("a.txt").unwrap(), b"hello pak");
+            #[cfg(feature = "zlib")]
+            assert_eq!(pak.read_entry("b.bin").unwrap(), b"first block, second block");
+        }
+    }
+
+    #[test]
+    fn read_path_hash_pak() {
+        const SEED: u64 = 0x1234;
+
+        // Version 10 hashes only the first half of the path's bytes.
+        assert_eq!(index::path_hash("Content/A.txt", SEED, PakInfo::VERSION_PATH_HASH_INDEX), 0x3090974C6CA2D4B8);
+        assert_eq!(index::path_hash("Content/A.txt", SEED, PakInfo::VERSION_FNV64_BUG_FIX), 0xBBD7CC1264F964F8);
+
+        let info = legacy_info(PakInfo::VERSION_PATH_HASH_INDEX, &["", "", "", "", ""]);
+        let entry = PakEntry {
+            offset: 0,
+            size: 9,
+            uncompressed_size: 9,
+            compression_method: 0,
+            hash: sha1(b"hello pak"),
+            compression_blocks: Vec::new(),
+            flags: 0,
+            compression_block_size: 0,
+        };
+        let mut pak = Vec::new();
+        write_entry(&mut pak, &info, &entry);
+        pak.extend_from_slice(b"hello pak");
+
+        let mut hash_index = Vec::new();
+        hash_index.write_i32_le(1).unwrap();
+        hash_index.write_u64_le(0x3090974C6CA2D4B8).unwrap();
+        hash_index.write_i32_le(0).unwrap();
+        let hash_index_offset = pak.len();
+        pak.extend_from_slice(&hash_index);
+
+        let mut index = Vec::new();
+        index.write_fstring::<LittleEndian>("../../../Game/").unwrap();
+        index.write_i32_le(1).unwrap();
+        index.write_u64_le(SEED).unwrap();
+        index.write_u32_le(1).unwrap();
+        index.write_i64_le(hash_index_offset as i64).unwrap();
+        index.write_i64_le(hash_index.len() as i64).unwrap();
+        index.extend_from_slice(&sha1(&hash_index));
+        index.write_u32_le(0).unwrap();
+        index.write_i32_le(12).unwrap();
+        for value in [0xE000_0000, 0, 9] {
+            index.write_u32_le(value).unwrap();
+        }
+        index.write_i32_le(0).unwrap();
+
+        let mut pak = PakReader::new(Cursor::new(finish_pak(pak, &info, &index))).unwrap();
+        assert_eq!(pak.files().count(), 0);
+        assert_eq!(pak.read_entry("Content/A.txt").unwrap(), b"hello pak");
+        assert_eq!(pak.read_entry("content/a.txt").unwrap(), b"hello pak");
+        // Only "Content" and part of the slash reach the hash, so this collides.
+        assert_eq!(pak.read_entry("Content/B.txt").unwrap(), b"hello pak");
+        assert!(matches!(pak.read_entry("Other/A.txt"), Err(ReadError::EntryNotFound { .. })));
+    }
+
+    #[test]
+    fn decode_block_layouts() {
+        let info = legacy_info(PakInfo::VERSION_FNV64_BUG_FIX, &["Zlib", "", "", "", ""]);
+
+        // Two zlib blocks of 2 KiB, at offset 100, unencrypted and then encrypted.
+        for (flag, second_start) in [(0, 1089), (1 << 22, 1097)] {
+            let mut encoded = Vec::new();
+            for value in [0xE000_0000 | flag | 1 << 23 | 2 << 6 | 1, 100, 3000, 1500, 1000, 500] {
+                encoded.write_u32_le(value).unwrap();
+            }
+
+            let entry = PakEntry::decode(&mut SliceReader::new(&encoded), &info).unwrap();
+            assert_eq!((entry.offset, entry.size, entry.uncompressed_size), (100, 1500, 3000));
+            assert_eq!((entry.compression_method, entry.compression_block_size), (1, 2048));
+            assert_eq!(entry.is_encrypted(), flag != 0);
+            // The header takes 89 bytes with two blocks; encrypted blocks are padded to 16 bytes.
+            assert_eq!(entry.compression_blocks, [
+                CompressedBlock { start: 89, end: 1089 },
+                CompressedBlock { start: second_start, end: second_start + 500 },
+            ]);
+        }
+    }
+
+}
@@ -0,0 +1,128 @@
+use std::io::{self, Read, Seek};
+
+use alloc::vec::{self, Vec};
+use core::cmp;
+
+use crate::compression::{self, CompressionMethod};
+use crate::pak::{read_region, AesKey, CompressedBlock};
+use crate::{Limits, ReadError, ReaderResult};
+
+/// A reader over the data of one pak entry, returned by
+/// [`PakReader::open_entry`](crate::pak::PakReader::open_entry).
+///
+/// Blocks are read and decompressed one at a time as the data is read. The SHA1 hash
+/// of the stored data is checked when the last block is read, failing that read with
+/// [`ReadError::HashMismatch`]. Encrypted blocks are hashed as stored, before they are
+/// decrypted.
+pub struct PakEntryReader<'a, R> {
+    inner: &'a mut R,
+    limits: Limits,
+    length: u64,
+    method: CompressionMethod,
+    blocks: vec::IntoIter<CompressedBlock>,
+    block_size: u64,
+    uncompressed_size: u64,
+    remaining: u64,
+    buffer: Vec<u8>,
+    position: usize,
+    hasher: sha1_smol::Sha1,
+    hash: [u8; 20],
+    key: Option<&'a AesKey>,
+}
+
+impl<'a, R: Read + Seek> PakEntryReader<'a, R> {
+
+    /// `blocks` are absolute ranges of the stored data, each decompressing to
+    /// `block_size` bytes but the last. With a `key`, every block is padded to whole AES
+    /// blocks and decrypted.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        inner: &'a mut R,
+        limits: Limits,
+        length: u64,
+        method: CompressionMethod,
+        blocks: Vec<CompressedBlock>,
+        block_size: u64,
+        uncompressed_size: u64,
+        hash: [u8; 20],
+        key: Option<&'a AesKey>,
+    ) -> Self {
+        Self {
+            inner,
+            limits,
+            length,
+            method,
+            blocks: blocks.into_iter(),
+            block_size,
+            uncompressed_size,
+            remaining: uncompressed_size,
+            buffer: Vec::new(),
+            position: 0,
+            hasher: sha1_smol::Sha1::new(),
+            hash,
+            key,
+        }
+    }
+
+    /// Returns the size of the entry's data once decompressed.
+    #[inline]
+    pub fn uncompressed_size(&self) -> u64 {
+        self.uncompressed_size
+    }
+
+    /// Reads and decompresses the next block into the buffer, returning false at the end
+    /// of the data.
+    fn next_block(&mut self) -> ReaderResult<bool> {
+        let Some(block) = self.blocks.next() else {
+            if self.remaining != 0 {
+                return Err(ReadError::UnexpectedEof { offset: None });
+            }
+            return Ok(false);
+        };
+
+        let size = block.end.saturating_sub(block.start);
+        let padded_size = if self.key.is_some() { size.next_multiple_of(16) } else { size };
+        let mut stored = read_region(self.inner, &self.limits, self.length, block.start, padded_size)?;
+        self.hasher.update(&stored);
+        if self.blocks.len() == 0 {
+            verify_hash(&self.hasher, &self.hash, block.start)?;
+        }
+        if let Some(key) = self.key {
+            key.decrypt(&mut stored);
+            stored.truncate(size as usize);
+        }
+
+        let size = cmp::min(self.block_size, self.remaining);
+        self.buffer = compression::decompress(self.method, &stored, size as usize).map_err(|e| e.at(block.start))?;
+        self.remaining -= size;
+        self.position = 0;
+        Ok(true)
+    }
+
+}
+
+impl<R: Read + Seek> Read for PakEntryReader<'_, R> {
+
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        while self.position == self.buffer.len() {
+            if !self.next_block()? {
+                return Ok(0);
+            }
+        }
+
+        let available = &self.buffer[self.position..];
+        let length = cmp::min(buffer.len(), available.len());
+        buffer[..length].copy_from_slice(&available[..length]);
+        self.position += length;
+        Ok(length)
+    }
+
+}
+
+/// Checks a hash saved in a pak, where a zero hash means none was saved.
+pub(crate) fn verify_hash(hasher: &sha1_smol::Sha1, hash: &[u8; 20], offset: u64) -> ReaderResult<()> {
+    if *hash != [0; 20] && hasher.digest().bytes() != *hash {
+        return Err(ReadError::HashMismatch { offset: Some(offset) });
+    }
+    Ok(())
+}
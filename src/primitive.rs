@@ -0,0 +1,100 @@
+use byteorder::ByteOrder;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A fixed-size number that can be read and written in either byte order.
+///
+/// The trait is sealed: readers decode primitives through a 16 byte buffer, so only
+/// the integer and float types of up to 128 bits implement it.
+///
+/// ```compile_fail,E0277
+/// #[derive(Clone, Copy)]
+/// struct Wide([u8; 32]);
+///
+/// impl thoo_readext::Primitive for Wide {
+///     const SIZE: usize = 32;
+///     fn from_bytes<B: thoo_readext::ByteOrder>(bytes: &[u8]) -> Self { unimplemented!() }
+///     fn to_bytes<B: thoo_readext::ByteOrder>(self, bytes: &mut [u8]) {}
+/// }
+/// ```
+pub trait Primitive: Copy + sealed::Sealed {
+
+    /// The size of the value in bytes.
+    const SIZE: usize;
+
+    /// Decodes the value from the first [`Primitive::SIZE`] bytes of `bytes`.
+    fn from_bytes<B: ByteOrder>(bytes: &[u8]) -> Self;
+
+    /// Encodes the value into the first [`Primitive::SIZE`] bytes of `bytes`.
+    fn to_bytes<B: ByteOrder>(self, bytes: &mut [u8]);
+
+}
+
+macro_rules! impl_primitive {
+    ($($ty:ty => $read:ident, $write:ident;)*) => {
+        $(
+            impl sealed::Sealed for $ty {}
+
+            impl Primitive for $ty {
+                const SIZE: usize = core::mem::size_of::<$ty>();
+
+                #[inline(always)]
+                fn from_bytes<B: ByteOrder>(bytes: &[u8]) -> Self {
+                    B::$read(bytes)
+                }
+
+                #[inline(always)]
+                fn to_bytes<B: ByteOrder>(self, bytes: &mut [u8]) {
+                    B::$write(bytes, self)
+                }
+            }
+        )*
+    };
+}
+
+impl sealed::Sealed for u8 {}
+
+impl Primitive for u8 {
+    const SIZE: usize = 1;
+
+    #[inline(always)]
+    fn from_bytes<B: ByteOrder>(bytes: &[u8]) -> Self {
+        bytes[0]
+    }
+
+    #[inline(always)]
+    fn to_bytes<B: ByteOrder>(self, bytes: &mut [u8]) {
+        bytes[0] = self;
+    }
+}
+
+impl sealed::Sealed for i8 {}
+
+impl Primitive for i8 {
+    const SIZE: usize = 1;
+
+    #[inline(always)]
+    fn from_bytes<B: ByteOrder>(bytes: &[u8]) -> Self {
+        bytes[0] as i8
+    }
+
+    #[inline(always)]
+    fn to_bytes<B: ByteOrder>(self, bytes: &mut [u8]) {
+        bytes[0] = self as u8;
+    }
+}
+
+impl_primitive! {
+    i16 => read_i16, write_i16;
+    u16 => read_u16, write_u16;
+    i32 => read_i32, write_i32;
+    u32 => read_u32, write_u32;
+    i64 => read_i64, write_i64;
+    u64 => read_u64, write_u64;
+    i128 => read_i128, write_i128;
+    u128 => read_u128, write_u128;
+    f32 => read_f32, write_f32;
+    f64 => read_f64, write_f64;
+}
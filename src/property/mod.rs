@@ -0,0 +1,11 @@
+//! Properties, the format UObjects are saved in: tagged in versioned packages, and
+//! unversioned, needing [`Mappings`](crate::usmap::Mappings), in cooked ones.
+
+mod nested;
+mod tag;
+mod unversioned;
+mod value;
+
+pub use tag::{FPropertyTag, PropertyType};
+pub use unversioned::read_unversioned_properties;
+pub use value::{read_properties, Property, PropertyValue, StructValue};
@@ -0,0 +1,103 @@
+use crate::types::NameTable;
+use crate::{ArchiveVersion, Endian, Limits, ReadError, ReadExt, ReaderResult, SliceReader};
+
+/// Reads a value buffered out of a parent reader, reporting the parent's offsets,
+/// versions and names so values decode as if read in place.
+///
+/// It copies the parent's state instead of wrapping the parent, so nesting readers
+/// does not nest types. Allocations are checked against the parent's budget and
+/// recorded here, for the caller to pass on to the parent with [`NestedReader::reserved`].
+pub(crate) struct NestedReader<'a, 'p> {
+    data: SliceReader<'a>,
+    base: Option<u64>,
+    limits: Limits,
+    allocated: u64,
+    reserved: u64,
+    endian: Endian,
+    version: &'p ArchiveVersion,
+    names: &'p NameTable,
+}
+
+impl<'a, 'p> NestedReader<'a, 'p> {
+
+    /// `base` is the parent's offset of the first byte of `data`.
+    pub(crate) fn new<R: ReadExt + ?Sized>(data: &'a [u8], base: Option<u64>, parent: &'p R) -> Self {
+        Self {
+            data: SliceReader::new(data),
+            base,
+            limits: *parent.limits(),
+            allocated: parent.allocated(),
+            reserved: 0,
+            endian: parent.endian(),
+            version: parent.version(),
+            names: parent.names(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.data.remaining_slice().is_empty()
+    }
+
+    /// Returns the number of bytes allocated while reading, which the parent has not
+    /// recorded yet.
+    #[inline]
+    pub(crate) fn reserved(&self) -> u64 {
+        self.reserved
+    }
+
+}
+
+impl ReadExt for NestedReader<'_, '_> {
+
+    fn read_exact_bytes(&mut self, buffer: &mut [u8]) -> ReaderResult<()> {
+        let offset = self.offset();
+        self.data.read_exact_bytes(buffer).map_err(|e| e.located(offset))
+    }
+
+    #[inline]
+    fn offset(&self) -> Option<u64> {
+        self.base.map(|base| base + self.data.position() as u64)
+    }
+
+    #[inline]
+    fn remaining(&self) -> Option<u64> {
+        self.data.remaining()
+    }
+
+    #[inline]
+    fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    fn allocate(&mut self, bytes: u64) -> ReaderResult<()> {
+        let total = self.allocated().saturating_add(bytes);
+        if total > self.limits.max_total_bytes {
+            return Err(ReadError::AllocationLimit { requested: total, limit: self.limits.max_total_bytes, offset: self.offset() });
+        }
+
+        self.reserved += bytes;
+        Ok(())
+    }
+
+    #[inline]
+    fn allocated(&self) -> u64 {
+        self.allocated.saturating_add(self.reserved)
+    }
+
+    #[inline]
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    #[inline]
+    fn version(&self) -> &ArchiveVersion {
+        self.version
+    }
+
+    #[inline]
+    fn names(&self) -> &NameTable {
+        self.names
+    }
+
+}
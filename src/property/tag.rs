@@ -0,0 +1,261 @@
+use byteorder::ByteOrder;
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::limits;
+use crate::types::{FGuid, FName};
+use crate::{ObjectVersion, ObjectVersionUE5, ReadExt, ReadFrom, ReaderResult};
+
+/// The type of a property with the types it is parameterized by, `FPropertyTypeName`
+/// in Unreal: `StructProperty(Vector)`, `MapProperty(NameProperty,IntProperty)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PropertyType {
+    pub name: FName,
+    pub parameters: Vec<PropertyType>,
+}
+
+impl PropertyType {
+
+    pub fn new(name: FName) -> Self {
+        Self { name, parameters: Vec::new() }
+    }
+
+    /// Returns the `index`th type parameter, such as the struct name of a `StructProperty`
+    /// or the key type of a `MapProperty`.
+    #[inline]
+    pub fn parameter(&self, index: usize) -> Option<&PropertyType> {
+        self.parameters.get(index)
+    }
+
+}
+
+impl ReadFrom for PropertyType {
+    /// Reads a type name as UE 5.4 saves it: every node as a name followed by its
+    /// parameter count, outermost first.
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        let mut nodes = Vec::new();
+        let mut pending = 1u64;
+        while pending > 0 {
+            let name = reader.read_fname::<B>()?;
+            let length = reader.read_num::<B, i32>()?;
+            let count = limits::check_array_len(length, reader.limits(), reader.offset())?;
+            nodes.push((name, count as usize));
+            pending = (pending - 1).saturating_add(count);
+        }
+
+        // Build the tree from the innermost nodes out, so nesting depth costs no stack.
+        let mut built: Vec<PropertyType> = Vec::with_capacity(nodes.len());
+        for (name, count) in nodes.into_iter().rev() {
+            let parameters = built.split_off(built.len() - count).into_iter().rev().collect();
+            built.push(PropertyType { name, parameters });
+        }
+
+        Ok(built.pop().expect("a type name has at least one node"))
+    }
+}
+
+impl fmt::Display for PropertyType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.name, f)?;
+        if let Some((first, rest)) = self.parameters.split_first() {
+            write!(f, "({}", first)?;
+            for parameter in rest {
+                write!(f, ",{}", parameter)?;
+            }
+            f.write_str(")")?;
+        }
+        Ok(())
+    }
+}
+
+/// The header written before every tagged property, `FPropertyTag` in Unreal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FPropertyTag {
+    pub name: FName,
+    pub property_type: PropertyType,
+    /// The size in bytes of the value following the tag.
+    pub size: i32,
+    pub array_index: i32,
+    /// The value of a `BoolProperty`, which is stored in the tag itself.
+    pub bool_value: bool,
+    /// The GUID of the struct of a `StructProperty`, only saved before UE 5.4.
+    pub struct_guid: Option<FGuid>,
+    pub property_guid: Option<FGuid>,
+    pub overridable_operation: Option<u8>,
+    /// True if the value was saved by the struct's own serializer instead of as tagged
+    /// properties. Only known since UE 5.4.
+    pub binary_or_native_serialize: bool,
+}
+
+impl FPropertyTag {
+
+    const HAS_ARRAY_INDEX: u8 = 0x01;
+    const HAS_PROPERTY_GUID: u8 = 0x02;
+    const HAS_PROPERTY_EXTENSIONS: u8 = 0x04;
+    const HAS_BINARY_OR_NATIVE_SERIALIZE: u8 = 0x08;
+    const BOOL_TRUE: u8 = 0x10;
+
+    const EXTENSION_OVERRIDABLE_INFORMATION: u8 = 0x02;
+
+    /// Reads a tag with the reader's versions, returning `None` for the `None` tag that
+    /// ends a property list.
+    pub fn read<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Option<Self>> {
+        let name = reader.with_context("Name", |r| r.read_fname::<B>())?;
+        if name.is_none() {
+            return Ok(None);
+        }
+
+        let tag = if reader.ue_version().ue5 >= ObjectVersionUE5::PROPERTY_TAG_COMPLETE_TYPE_NAME {
+            Self::read_complete::<B, R>(reader, name)?
+        } else {
+            Self::read_legacy::<B, R>(reader, name)?
+        };
+        Ok(Some(tag))
+    }
+
+    fn read_complete<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R, name: FName) -> ReaderResult<Self> {
+        let property_type = reader.with_context("Type", |r| PropertyType::read_from::<B, _>(r))?;
+        let size = reader.with_context("Size", |r| r.read_num::<B, i32>())?;
+        let flags = reader.with_context("Flags", |r| r.read_num::<B, u8>())?;
+
+        let array_index = if flags & Self::HAS_ARRAY_INDEX != 0 {
+            reader.with_context("ArrayIndex", |r| r.read_num::<B, i32>())?
+        } else {
+            0
+        };
+        let property_guid = if flags & Self::HAS_PROPERTY_GUID != 0 {
+            Some(reader.with_context("PropertyGuid", |r| FGuid::read_from::<B, _>(r))?)
+        } else {
+            None
+        };
+        let overridable_operation = if flags & Self::HAS_PROPERTY_EXTENSIONS != 0 {
+            Self::read_extensions::<B, R>(reader)?
+        } else {
+            None
+        };
+
+        Ok(Self {
+            name,
+            property_type,
+            size,
+            array_index,
+            bool_value: flags & Self::BOOL_TRUE != 0,
+            struct_guid: None,
+            property_guid,
+            overridable_operation,
+            binary_or_native_serialize: flags & Self::HAS_BINARY_OR_NATIVE_SERIALIZE != 0,
+        })
+    }
+
+    fn read_legacy<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R, name: FName) -> ReaderResult<Self> {
+        let version = reader.ue_version();
+
+        let type_name = reader.with_context("Type", |r| r.read_fname::<B>())?;
+        let size = reader.with_context("Size", |r| r.read_num::<B, i32>())?;
+        let array_index = reader.with_context("ArrayIndex", |r| r.read_num::<B, i32>())?;
+
+        let mut bool_value = false;
+        let mut struct_guid = None;
+        let mut parameters = Vec::new();
+        let mut read_parameter = |reader: &mut R, context| -> ReaderResult<()> {
+            let name = reader.with_context(context, |r| r.read_fname::<B>())?;
+            parameters.push(PropertyType::new(name));
+            Ok(())
+        };
+        match type_name.name() {
+            "StructProperty" => {
+                read_parameter(reader, "StructName")?;
+                if version.ue4 >= ObjectVersion::STRUCT_GUID_IN_PROPERTY_TAG {
+                    struct_guid = Some(reader.with_context("StructGuid", |r| FGuid::read_from::<B, _>(r))?);
+                }
+            }
+            "BoolProperty" => bool_value = reader.with_context("BoolVal", |r| r.read_num::<B, u8>())? != 0,
+            "ByteProperty" | "EnumProperty" => read_parameter(reader, "EnumName")?,
+            "ArrayProperty" if version.ue4 >= ObjectVersion::ARRAY_PROPERTY_INNER_TAGS => {
+                read_parameter(reader, "InnerType")?
+            }
+            "SetProperty" if version.ue4 >= ObjectVersion::PROPERTY_TAG_SET_MAP_SUPPORT => {
+                read_parameter(reader, "InnerType")?
+            }
+            "MapProperty" if version.ue4 >= ObjectVersion::PROPERTY_TAG_SET_MAP_SUPPORT => {
+                read_parameter(reader, "InnerType")?;
+                read_parameter(reader, "ValueType")?;
+            }
+            _ => {}
+        }
+
+        // A byte that is not an enum names `None` as its enum.
+        if type_name.name() == "ByteProperty" && parameters[0].name.is_none() {
+            parameters.clear();
+        }
+
+        let mut property_guid = None;
+        if version.ue4 >= ObjectVersion::PROPERTY_GUID_IN_PROPERTY_TAG
+            && reader.with_context("HasPropertyGuid", |r| r.read_num::<B, u8>())? != 0
+        {
+            property_guid = Some(reader.with_context("PropertyGuid", |r| FGuid::read_from::<B, _>(r))?);
+        }
+
+        let overridable_operation = if version.ue5 >= ObjectVersionUE5::PROPERTY_TAG_EXTENSION_AND_OVERRIDABLE_SERIALIZATION {
+            Self::read_extensions::<B, R>(reader)?
+        } else {
+            None
+        };
+
+        Ok(Self {
+            name,
+            property_type: PropertyType { name: type_name, parameters },
+            size,
+            array_index,
+            bool_value,
+            struct_guid,
+            property_guid,
+            overridable_operation,
+            binary_or_native_serialize: false,
+        })
+    }
+
+    fn read_extensions<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Option<u8>> {
+        let extensions = reader.with_context("PropertyExtensions", |r| r.read_num::<B, u8>())?;
+        if extensions & Self::EXTENSION_OVERRIDABLE_INFORMATION == 0 {
+            return Ok(None);
+        }
+
+        let operation = reader.with_context("OverridableOperation", |r| r.read_num::<B, u8>())?;
+        reader.with_context("bExperimentalOverridableLogic", |r| r.read_bool::<B>())?;
+        Ok(Some(operation))
+    }
+
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::property::FPropertyTag;
+    use crate::types::NameTable;
+    use crate::{ArchiveVersion, LittleEndian, PackageFileVersion, Reader, WriteExt};
+
+    #[test]
+    fn read_complete_type_name_tag() {
+        let mut data = Vec::new();
+        for value in [1, 0, 2, 0, 2, 3, 0, 0, 4, 0, 0, 4] {
+            data.write_i32_le(value).unwrap();
+        }
+        data.write_num::<LittleEndian, u8>(0x11).unwrap();
+        data.write_i32_le(3).unwrap();
+        data.write_i32_le(0).unwrap();
+        data.write_i32_le(0).unwrap();
+
+        let names = ["None", "Scores", "MapProperty", "NameProperty", "IntProperty"];
+        let mut reader = Reader::new(&data[..]);
+        reader.set_version(ArchiveVersion::new(PackageFileVersion::new(522, 1012), 0));
+        reader.set_names(NameTable::from(names.map(String::from).to_vec()));
+
+        let tag = FPropertyTag::read::<LittleEndian, _>(&mut reader).unwrap().unwrap();
+        assert_eq!(tag.name.to_string(), "Scores");
+        assert_eq!(tag.property_type.to_string(), "MapProperty(NameProperty,IntProperty)");
+        assert_eq!((tag.size, tag.array_index, tag.bool_value), (4, 3, true));
+        assert_eq!(FPropertyTag::read::<LittleEndian, _>(&mut reader).unwrap(), None);
+    }
+
+}
@@ -0,0 +1,331 @@
+use byteorder::ByteOrder;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::package::FPackageIndex;
+use crate::property::value::{read_value, Format};
+use crate::property::{FPropertyTag, Property, PropertyType, PropertyValue, StructValue};
+use crate::types::{FName, FQuat, FRotator, FSoftObjectPath, FText, FVector};
+use crate::usmap::{Mappings, PropertySchema, StructSchema};
+use crate::{ReadError, ReadExt, ReaderResult};
+
+/// How many super structs a struct may have before its chain is taken for a loop.
+const MAX_SUPER_DEPTH: usize = 64;
+
+/// Reads the unversioned properties of a `struct_name` object or struct, as cooked
+/// packages save them, using the schemas in `mappings`.
+///
+/// The properties come out as [`read_properties`](crate::property::read_properties)
+/// returns them, with tags rebuilt from the schemas. Values the header marks as zero
+/// are included with their zero value. As unversioned values carry no size, a value
+/// of an unsupported type fails the whole struct instead of becoming raw bytes.
+pub fn read_unversioned_properties<B: ByteOrder, R: ReadExt + ?Sized>(
+    reader: &mut R,
+    mappings: &Mappings,
+    struct_name: &str,
+) -> ReaderResult<Vec<Property>> {
+    read_struct_at::<B, R>(reader, mappings, struct_name, 0)
+}
+
+pub(crate) fn read_struct_at<B: ByteOrder, R: ReadExt + ?Sized>(
+    reader: &mut R,
+    mappings: &Mappings,
+    struct_name: &str,
+    depth: u32,
+) -> ReaderResult<Vec<Property>> {
+    let offset = reader.offset();
+    let chain = super_chain(mappings, struct_name).map_err(|e| e.located(offset))?;
+    let header = reader.with_context("Header", |r| Header::read::<B, R>(r))?;
+
+    let mut properties = Vec::new();
+    let mut slot = 0usize;
+    let mut zero_index = 0usize;
+    for fragment in &header.fragments {
+        slot += fragment.skip;
+        for _ in 0..fragment.values {
+            let is_zero = fragment.has_zeroes && header.is_zero(zero_index);
+            zero_index += usize::from(fragment.has_zeroes);
+
+            let property = read_slot::<B, R>(reader, mappings, &chain, slot, is_zero, depth);
+            properties.push(property.map_err(|e| e.index(slot))?);
+            slot += 1;
+        }
+    }
+
+    Ok(properties)
+}
+
+fn read_slot<B: ByteOrder, R: ReadExt + ?Sized>(
+    reader: &mut R,
+    mappings: &Mappings,
+    chain: &[&StructSchema],
+    slot: usize,
+    is_zero: bool,
+    depth: u32,
+) -> ReaderResult<Property> {
+    let offset = reader.offset();
+    let Some((schema, array_index)) = find_slot(chain, slot) else {
+        let name = alloc::format!("property {} of {}", slot, chain[0].name);
+        return Err(ReadError::MissingSchema { name, offset });
+    };
+
+    let ty = &schema.property_type;
+    let value = if is_zero {
+        zero_value(ty, mappings).ok_or_else(|| ReadError::UnsupportedType { name: ty.to_string(), offset })?
+    } else {
+        read_value::<B, R>(reader, ty, Format::Unversioned(mappings), depth)?
+    };
+
+    let tag = FPropertyTag {
+        name: schema.name.clone(),
+        property_type: ty.clone(),
+        size: 0,
+        array_index,
+        bool_value: value == PropertyValue::Bool(true),
+        struct_guid: None,
+        property_guid: None,
+        overridable_operation: None,
+        binary_or_native_serialize: false,
+    };
+    Ok(Property { tag, value })
+}
+
+/// Returns `struct_name` and its super structs, most derived first.
+fn super_chain<'m>(mappings: &'m Mappings, struct_name: &str) -> ReaderResult<Vec<&'m StructSchema>> {
+    let mut chain = Vec::new();
+    let mut next = Some(struct_name);
+    while let Some(name) = next {
+        let schema = mappings.get_struct(name).ok_or_else(|| ReadError::MissingSchema { name: name.to_string(), offset: None })?;
+        if chain.len() == MAX_SUPER_DEPTH {
+            return Err(ReadError::MissingSchema { name: alloc::format!("{} (its super structs loop)", struct_name), offset: None });
+        }
+
+        chain.push(schema);
+        next = schema.super_struct.as_ref().map(FName::name);
+    }
+    Ok(chain)
+}
+
+/// Finds the property holding `slot` and the element of it the slot is. Slots number
+/// the properties of the root super struct first.
+fn find_slot<'m>(chain: &[&'m StructSchema], slot: usize) -> Option<(&'m PropertySchema, i32)> {
+    let mut base = 0usize;
+    for schema in chain.iter().rev() {
+        let count = usize::from(schema.property_count);
+        if slot < base + count {
+            let local = slot - base;
+            return schema.properties.iter().find_map(|property| {
+                let first = usize::from(property.schema_index);
+                let element = local.checked_sub(first).filter(|&e| e < usize::from(property.array_dim.max(1)))?;
+                Some((property, element as i32))
+            });
+        }
+        base += count;
+    }
+    None
+}
+
+/// The value of a property whose bits are all zero, which unversioned properties omit.
+fn zero_value(ty: &PropertyType, mappings: &Mappings) -> Option<PropertyValue> {
+    let value = match ty.name.name() {
+        "BoolProperty" => PropertyValue::Bool(false),
+        "ByteProperty" if ty.parameters.is_empty() => PropertyValue::Byte(0),
+        "ByteProperty" | "EnumProperty" => PropertyValue::Enum(enum_value_name(mappings, ty.parameter(0)?.name.name(), 0)),
+        "Int8Property" => PropertyValue::Int8(0),
+        "Int16Property" => PropertyValue::Int16(0),
+        "IntProperty" => PropertyValue::Int(0),
+        "Int64Property" => PropertyValue::Int64(0),
+        "UInt16Property" => PropertyValue::UInt16(0),
+        "UInt32Property" => PropertyValue::UInt32(0),
+        "UInt64Property" => PropertyValue::UInt64(0),
+        "FloatProperty" => PropertyValue::Float(0.0),
+        "DoubleProperty" => PropertyValue::Double(0.0),
+        "StrProperty" => PropertyValue::Str(String::new()),
+        "NameProperty" => PropertyValue::Name(FName::new("None", 0)),
+        "TextProperty" => PropertyValue::Text(FText::default()),
+        "ObjectProperty" | "ClassProperty" | "WeakObjectProperty" | "InterfaceProperty" => {
+            PropertyValue::Object(FPackageIndex::NULL)
+        }
+        "SoftObjectProperty" | "SoftClassProperty" => PropertyValue::SoftObject(FSoftObjectPath::default()),
+        "StructProperty" => {
+            let struct_name = ty.parameter(0)?.name.clone();
+            let value = match struct_name.name() {
+                "Vector" => StructValue::Vector(FVector::ZERO),
+                "Rotator" => StructValue::Rotator(FRotator::ZERO),
+                "Quat" => StructValue::Quat(FQuat::new(0.0, 0.0, 0.0, 0.0)),
+                "Guid" => StructValue::Guid(Default::default()),
+                "Color" => StructValue::Color(Default::default()),
+                "LinearColor" => StructValue::LinearColor(Default::default()),
+                "SoftObjectPath" | "SoftClassPath" => StructValue::SoftObjectPath(FSoftObjectPath::default()),
+                _ => StructValue::Properties(Vec::new()),
+            };
+            PropertyValue::Struct { struct_name, value }
+        }
+        "ArrayProperty" => PropertyValue::Array(Vec::new()),
+        "SetProperty" => PropertyValue::Set(Vec::new()),
+        "MapProperty" => PropertyValue::Map(Vec::new()),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Reads an unversioned enum, saved as its underlying integer, and names the value
+/// from the mappings.
+pub(crate) fn read_enum_value<B: ByteOrder, R: ReadExt + ?Sized>(
+    reader: &mut R,
+    ty: &PropertyType,
+    mappings: &Mappings,
+    depth: u32,
+) -> ReaderResult<PropertyValue> {
+    let offset = reader.offset();
+    let (Some(enum_type), Some(underlying)) = (ty.parameter(0), ty.parameter(1)) else {
+        return Err(ReadError::UnsupportedType { name: ty.to_string(), offset });
+    };
+
+    let value = match read_value::<B, R>(reader, underlying, Format::Unversioned(mappings), depth + 1)? {
+        PropertyValue::Byte(value) => value.into(),
+        PropertyValue::Int8(value) => value.into(),
+        PropertyValue::Int16(value) => value.into(),
+        PropertyValue::UInt16(value) => value.into(),
+        PropertyValue::Int(value) => value.into(),
+        PropertyValue::UInt32(value) => value.into(),
+        PropertyValue::Int64(value) => value,
+        PropertyValue::UInt64(value) => value as i64,
+        _ => return Err(ReadError::UnsupportedType { name: ty.to_string(), offset }),
+    };
+    Ok(PropertyValue::Enum(enum_value_name(mappings, enum_type.name.name(), value)))
+}
+
+/// Names an enum value `Enum::Entry` as tagged properties do, or `Enum::5` for a value
+/// the mappings do not list.
+fn enum_value_name(mappings: &Mappings, enum_name: &str, value: i64) -> FName {
+    let entry = mappings.get_enum(enum_name).and_then(|schema| schema.value_name(value));
+    match entry {
+        Some(entry) if entry.name().contains("::") => entry.clone(),
+        Some(entry) => FName::new(alloc::format!("{}::{}", enum_name, entry), 0),
+        None => FName::new(alloc::format!("{}::{}", enum_name, value), 0),
+    }
+}
+
+/// A run of property slots in an unversioned header.
+struct Fragment {
+    /// The number of slots skipped before the run.
+    skip: usize,
+    /// The number of slots in the run.
+    values: usize,
+    /// True if the zero mask covers the slots of the run.
+    has_zeroes: bool,
+}
+
+/// `FUnversionedHeader`: the fragments naming the saved slots, and a bit per slot of the
+/// fragments with zeroes, set if the value was omitted for being zero.
+struct Header {
+    fragments: Vec<Fragment>,
+    zero_mask: Vec<u32>,
+}
+
+impl Header {
+
+    const SKIP_NUM_MASK: u16 = 0x7F;
+    const HAS_ZERO_MASK: u16 = 0x80;
+    const IS_LAST_MASK: u16 = 0x100;
+    const VALUE_NUM_SHIFT: u32 = 9;
+
+    fn read<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        let mut fragments = Vec::new();
+        let mut zero_count = 0usize;
+        loop {
+            let packed = reader.read_num::<B, u16>()?;
+            let fragment = Fragment {
+                skip: usize::from(packed & Self::SKIP_NUM_MASK),
+                values: usize::from(packed >> Self::VALUE_NUM_SHIFT),
+                has_zeroes: packed & Self::HAS_ZERO_MASK != 0,
+            };
+            if fragment.has_zeroes {
+                zero_count += fragment.values;
+            }
+            fragments.push(fragment);
+
+            if packed & Self::IS_LAST_MASK != 0 {
+                break;
+            }
+        }
+
+        let zero_mask = match zero_count {
+            0 => Vec::new(),
+            1..=8 => alloc::vec![reader.read_num::<B, u8>()?.into()],
+            9..=16 => alloc::vec![reader.read_num::<B, u16>()?.into()],
+            _ => (0..zero_count.div_ceil(32)).map(|_| reader.read_num::<B, u32>()).collect::<ReaderResult<_>>()?,
+        };
+        Ok(Self { fragments, zero_mask })
+    }
+
+    #[inline]
+    fn is_zero(&self, index: usize) -> bool {
+        self.zero_mask[index / 32] & (1 << (index % 32)) != 0
+    }
+
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::property::{read_unversioned_properties, PropertyType, PropertyValue};
+    use crate::types::FName;
+    use crate::usmap::{EnumSchema, Mappings, PropertySchema, StructSchema};
+    use crate::{LittleEndian, Reader, WriteExt};
+
+    fn property(name: &str, schema_index: u16, array_dim: u8, ty: &str, parameters: &[&str]) -> PropertySchema {
+        let mut property_type = PropertyType::new(FName::new(ty, 0));
+        property_type.parameters = parameters.iter().map(|p| PropertyType::new(FName::new(*p, 0))).collect();
+        PropertySchema { name: FName::new(name, 0), schema_index, array_dim, property_type }
+    }
+
+    #[test]
+    fn read_unversioned_struct() {
+        let mut mappings = Mappings::default();
+        let mode = EnumSchema { name: FName::new("EMode", 0), values: vec![(0, FName::new("A", 0)), (1, FName::new("B", 0))] };
+        mappings.enums.insert("EMode".into(), mode);
+        let base = StructSchema {
+            name: FName::new("Base", 0),
+            super_struct: None,
+            property_count: 1,
+            properties: vec![property("Health", 0, 1, "FloatProperty", &[])],
+        };
+        let child = StructSchema {
+            name: FName::new("Child", 0),
+            super_struct: Some(FName::new("Base", 0)),
+            property_count: 3,
+            properties: vec![
+                property("Tags", 0, 2, "IntProperty", &[]),
+                property("Mode", 2, 1, "EnumProperty", &["EMode", "ByteProperty"]),
+            ],
+        };
+        mappings.structs.insert("Base".into(), base);
+        mappings.structs.insert("Child".into(), child);
+
+        // One last fragment of four slots with zeroes, the second of them zero.
+        let mut data = Vec::new();
+        data.write_u16_le(0x80 | 0x100 | (4 << 9)).unwrap();
+        data.write_num::<LittleEndian, u8>(0b0010).unwrap();
+        data.write_f32_le(50.0).unwrap();
+        data.write_i32_le(7).unwrap();
+        data.write_num::<LittleEndian, u8>(1).unwrap();
+
+        let mut reader = Reader::new(&data[..]);
+        let properties = read_unversioned_properties::<LittleEndian, _>(&mut reader, &mappings, "Child").unwrap();
+        assert!(reader.get_ref().is_empty());
+
+        let summary: Vec<_> = properties.iter().map(|p| (p.tag.name.to_string(), p.tag.array_index, p.value.clone())).collect();
+        assert_eq!(summary, [
+            ("Health".to_string(), 0, PropertyValue::Float(50.0)),
+            ("Tags".to_string(), 0, PropertyValue::Int(0)),
+            ("Tags".to_string(), 1, PropertyValue::Int(7)),
+            ("Mode".to_string(), 0, PropertyValue::Enum(FName::new("EMode::B", 0))),
+        ]);
+
+        let mut reader = Reader::new(&data[..]);
+        assert!(read_unversioned_properties::<LittleEndian, _>(&mut reader, &mappings, "Missing").is_err());
+    }
+
+}
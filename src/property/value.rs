@@ -0,0 +1,329 @@
+use byteorder::ByteOrder;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use crate::limits;
+use crate::package::FPackageIndex;
+use crate::property::nested::NestedReader;
+use crate::property::unversioned;
+use crate::property::{FPropertyTag, PropertyType};
+use crate::types::{FColor, FGuid, FLinearColor, FName, FQuat, FRotator, FSoftObjectPath, FText, FVector};
+use crate::usmap::Mappings;
+use crate::{ObjectVersionUE5, ReadError, ReadExt, ReadFrom, ReaderResult};
+
+/// How deep arrays, maps and structs may nest before reading gives up.
+const MAX_DEPTH: u32 = 128;
+
+/// How the properties of structs are laid out.
+#[derive(Clone, Copy)]
+pub(crate) enum Format<'m> {
+    /// As a tag list ended by `None`.
+    Tagged,
+    /// As an unversioned header followed by the values the mappings describe.
+    Unversioned(&'m Mappings),
+}
+
+/// A tagged property and its value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub tag: FPropertyTag,
+    pub value: PropertyValue,
+}
+
+/// The value of a property, decoded according to its [`PropertyType`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum PropertyValue {
+    Bool(bool),
+    Int8(i8),
+    Int16(i16),
+    Int(i32),
+    Int64(i64),
+    Byte(u8),
+    UInt16(u16),
+    UInt32(u32),
+    UInt64(u64),
+    Float(f32),
+    Double(f64),
+    Str(String),
+    Name(FName),
+    Text(FText),
+    /// The name of an enum value, from an `EnumProperty` or a `ByteProperty` with an enum.
+    Enum(FName),
+    Object(FPackageIndex),
+    SoftObject(FSoftObjectPath),
+    Struct { struct_name: FName, value: StructValue },
+    Array(Vec<PropertyValue>),
+    Set(Vec<PropertyValue>),
+    Map(Vec<(PropertyValue, PropertyValue)>),
+    /// The bytes of a tagged value whose type is not supported or that failed to decode.
+    Raw(Vec<u8>),
+}
+
+/// The value of a struct: decoded for structs with a native layout, and a property list
+/// for every other struct.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum StructValue {
+    Vector(FVector),
+    Rotator(FRotator),
+    Quat(FQuat),
+    Guid(FGuid),
+    Color(FColor),
+    LinearColor(FLinearColor),
+    SoftObjectPath(FSoftObjectPath),
+    Properties(Vec<Property>),
+}
+
+/// Reads tagged properties up to the `None` tag that ends them, as saved for every
+/// UObject and struct in versioned packages.
+///
+/// Each value is buffered using the size in its tag, and a value that fails to decode
+/// becomes [`PropertyValue::Raw`] instead of failing the list. Only a broken tag fails.
+pub fn read_properties<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Vec<Property>> {
+    read_properties_at::<B, R>(reader, 0)
+}
+
+fn read_properties_at<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R, depth: u32) -> ReaderResult<Vec<Property>> {
+    let mut properties = Vec::new();
+    loop {
+        let index = properties.len();
+        let Some(tag) = FPropertyTag::read::<B, R>(reader).map_err(|e| e.index(index))? else {
+            return Ok(properties);
+        };
+
+        let value = read_tagged_value::<B, R>(reader, &tag, depth).map_err(|e| e.index(index))?;
+        properties.push(Property { tag, value });
+    }
+}
+
+fn read_tagged_value<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R, tag: &FPropertyTag, depth: u32) -> ReaderResult<PropertyValue> {
+    if tag.property_type.name.name() == "BoolProperty" {
+        return Ok(PropertyValue::Bool(tag.bool_value));
+    }
+
+    let offset = reader.offset();
+    let data = reader.with_context("Value", |r| {
+        let size = limits::check_array_len(tag.size, r.limits(), offset)?;
+        limits::read_buffer(r, size)
+    })?;
+
+    let mut nested = NestedReader::new(&data, offset, &*reader);
+    let value = match tag.property_type.name.name() {
+        // The tag gives the text's size, so a text of an unknown history type keeps its bytes.
+        "TextProperty" => FText::read_sized::<B, _>(&mut nested, data.len() as u64).map(PropertyValue::Text),
+        _ => read_value::<B, _>(&mut nested, &tag.property_type, Format::Tagged, depth),
+    };
+    let (reserved, is_empty) = (nested.reserved(), nested.is_empty());
+    reader.allocate(reserved)?;
+    match value {
+        Ok(value) if is_empty => Ok(value),
+        // Running out of the session's budget is not a sign of an unknown layout.
+        Err(e) if matches!(e.root(), ReadError::AllocationLimit { .. }) => Err(e),
+        _ => Ok(PropertyValue::Raw(data)),
+    }
+}
+
+pub(crate) fn read_value<B: ByteOrder, R: ReadExt + ?Sized>(
+    reader: &mut R,
+    ty: &PropertyType,
+    format: Format<'_>,
+    depth: u32,
+) -> ReaderResult<PropertyValue> {
+    let offset = reader.offset();
+    if depth > MAX_DEPTH {
+        return Err(ReadError::NestingLimit { limit: MAX_DEPTH, offset });
+    }
+
+    let unsupported = || ReadError::UnsupportedType { name: ty.to_string(), offset };
+    let parameter = |index| ty.parameter(index).ok_or_else(unsupported);
+
+    let value = match ty.name.name() {
+        "BoolProperty" => PropertyValue::Bool(reader.read_num::<B, u8>()? != 0),
+        "ByteProperty" if ty.parameters.is_empty() => PropertyValue::Byte(reader.read_num::<B, u8>()?),
+        "ByteProperty" | "EnumProperty" => match format {
+            Format::Tagged => PropertyValue::Enum(reader.read_fname::<B>()?),
+            Format::Unversioned(mappings) => unversioned::read_enum_value::<B, R>(reader, ty, mappings, depth)?,
+        },
+        "Int8Property" => PropertyValue::Int8(reader.read_num::<B, i8>()?),
+        "Int16Property" => PropertyValue::Int16(reader.read_num::<B, i16>()?),
+        "IntProperty" => PropertyValue::Int(reader.read_num::<B, i32>()?),
+        "Int64Property" => PropertyValue::Int64(reader.read_num::<B, i64>()?),
+        "UInt16Property" => PropertyValue::UInt16(reader.read_num::<B, u16>()?),
+        "UInt32Property" => PropertyValue::UInt32(reader.read_num::<B, u32>()?),
+        "UInt64Property" => PropertyValue::UInt64(reader.read_num::<B, u64>()?),
+        "FloatProperty" => PropertyValue::Float(reader.read_num::<B, f32>()?),
+        "DoubleProperty" => PropertyValue::Double(reader.read_num::<B, f64>()?),
+        "StrProperty" => PropertyValue::Str(reader.read_fstring::<B>()?),
+        "NameProperty" => PropertyValue::Name(reader.read_fname::<B>()?),
+        "TextProperty" => PropertyValue::Text(FText::read_from::<B, R>(reader)?),
+        "ObjectProperty" | "ClassProperty" | "WeakObjectProperty" | "InterfaceProperty" => {
+            PropertyValue::Object(FPackageIndex::read_from::<B, R>(reader)?)
+        }
+        "SoftObjectProperty" | "SoftClassProperty" => {
+            PropertyValue::SoftObject(FSoftObjectPath::read_from::<B, R>(reader)?)
+        }
+        "StructProperty" => {
+            let struct_name = parameter(0)?.name.clone();
+            let value = read_struct::<B, R>(reader, &struct_name, format, depth + 1)?;
+            PropertyValue::Struct { struct_name, value }
+        }
+        "ArrayProperty" => {
+            let count = reader.read_num::<B, i32>()?;
+
+            // Before UE 5.4 the tag of an array of structs does not name the struct,
+            // so the array repeats a full tag for its elements.
+            let inner_tag;
+            let mut inner = parameter(0)?;
+            if matches!(format, Format::Tagged)
+                && inner.name.name() == "StructProperty"
+                && reader.ue_version().ue5 < ObjectVersionUE5::PROPERTY_TAG_COMPLETE_TYPE_NAME
+            {
+                inner_tag = FPropertyTag::read::<B, R>(reader)?.ok_or_else(unsupported)?;
+                inner = &inner_tag.property_type;
+            }
+
+            PropertyValue::Array(reader.read_array_with_length(|r| read_value::<B, R>(r, inner, format, depth + 1), count)?)
+        }
+        "SetProperty" => {
+            let inner = parameter(0)?;
+            reader.read_array::<B, _>(|r| read_value::<B, R>(r, inner, format, depth + 1))?;
+            PropertyValue::Set(reader.read_array::<B, _>(|r| read_value::<B, R>(r, inner, format, depth + 1))?)
+        }
+        "MapProperty" => {
+            let (key, value) = (parameter(0)?, parameter(1)?);
+            reader.read_array::<B, _>(|r| read_value::<B, R>(r, key, format, depth + 1))?;
+            PropertyValue::Map(reader.read_array::<B, _>(|r| {
+                Ok((read_value::<B, R>(r, key, format, depth + 1)?, read_value::<B, R>(r, value, format, depth + 1)?))
+            })?)
+        }
+        _ => return Err(unsupported()),
+    };
+
+    Ok(value)
+}
+
+fn read_struct<B: ByteOrder, R: ReadExt + ?Sized>(
+    reader: &mut R,
+    struct_name: &FName,
+    format: Format<'_>,
+    depth: u32,
+) -> ReaderResult<StructValue> {
+    let value = match struct_name.name() {
+        "Vector" => StructValue::Vector(FVector::read_from::<B, R>(reader)?),
+        "Rotator" => StructValue::Rotator(FRotator::read_from::<B, R>(reader)?),
+        "Quat" => StructValue::Quat(FQuat::read_from::<B, R>(reader)?),
+        "Guid" => StructValue::Guid(FGuid::read_from::<B, R>(reader)?),
+        "Color" => StructValue::Color(FColor::read_from::<B, R>(reader)?),
+        "LinearColor" => StructValue::LinearColor(FLinearColor::read_from::<B, R>(reader)?),
+        "SoftObjectPath" | "SoftClassPath" => StructValue::SoftObjectPath(FSoftObjectPath::read_from::<B, R>(reader)?),
+        _ => StructValue::Properties(match format {
+            Format::Tagged => read_properties_at::<B, R>(reader, depth)?,
+            Format::Unversioned(mappings) => unversioned::read_struct_at::<B, R>(reader, mappings, struct_name.name(), depth)?,
+        }),
+    };
+
+    Ok(value)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::property::{read_properties, PropertyValue, StructValue};
+    use crate::types::{FVector, NameTable};
+    use crate::{ArchiveVersion, Limits, LittleEndian, PackageFileVersion, ReadError, Reader, WriteExt};
+
+    const NAMES: [&str; 14] = [
+        "None", "Count", "IntProperty", "Label", "StrProperty", "Location", "StructProperty",
+        "Vector", "Values", "ArrayProperty", "Mystery", "MysteryStruct", "bEnabled", "BoolProperty",
+    ];
+
+    /// Writes a UE4 tag without a property GUID, `extra` being the type-specific fields.
+    fn write_tag(data: &mut Vec<u8>, name: i32, ty: i32, size: i32, extra: &[i32]) {
+        for value in [name, 0, ty, 0, size, 0] {
+            data.write_i32_le(value).unwrap();
+        }
+        for &value in extra {
+            data.write_i32_le(value).unwrap();
+        }
+    }
+
+    #[test]
+    fn read_tagged_properties() {
+        let mut data = Vec::new();
+        write_tag(&mut data, 1, 2, 4, &[]);
+        data.write_num::<LittleEndian, u8>(0).unwrap();
+        data.write_i32_le(5).unwrap();
+
+        write_tag(&mut data, 3, 4, 7, &[]);
+        data.write_num::<LittleEndian, u8>(0).unwrap();
+        data.write_fstring::<LittleEndian>("Hi").unwrap();
+
+        write_tag(&mut data, 5, 6, 12, &[7, 0, 0, 0, 0, 0]);
+        data.write_num::<LittleEndian, u8>(0).unwrap();
+        for value in [1.0, 2.0, 3.0] {
+            data.write_f32_le(value).unwrap();
+        }
+
+        write_tag(&mut data, 8, 9, 12, &[2, 0]);
+        data.write_num::<LittleEndian, u8>(0).unwrap();
+        for value in [2, 7, -8] {
+            data.write_i32_le(value).unwrap();
+        }
+
+        write_tag(&mut data, 10, 6, 5, &[11, 0, 0, 0, 0, 0]);
+        data.write_num::<LittleEndian, u8>(0).unwrap();
+        data.extend_from_slice(&[1, 2, 3, 4, 5]);
+
+        write_tag(&mut data, 12, 13, 0, &[]);
+        data.extend_from_slice(&[1, 0]);
+        data.write_i32_le(0).unwrap();
+        data.write_i32_le(0).unwrap();
+
+        let mut reader = Reader::new(&data[..]);
+        reader.set_version(ArchiveVersion::new(PackageFileVersion::new(522, 0), 0));
+        reader.set_names(NameTable::from(NAMES.map(String::from).to_vec()));
+        let properties = read_properties::<LittleEndian, _>(&mut reader).unwrap();
+        assert!(reader.get_ref().is_empty());
+
+        let values: Vec<_> = properties.iter().map(|p| (p.tag.name.to_string(), p.value.clone())).collect();
+        assert_eq!(values[0], ("Count".into(), PropertyValue::Int(5)));
+        assert_eq!(values[1], ("Label".into(), PropertyValue::Str("Hi".into())));
+        assert!(matches!(
+            &values[2].1,
+            PropertyValue::Struct { value: StructValue::Vector(v), .. } if *v == FVector::new(1.0, 2.0, 3.0)
+        ));
+        assert_eq!(values[3].1, PropertyValue::Array(vec![PropertyValue::Int(7), PropertyValue::Int(-8)]));
+        assert_eq!(values[4].1, PropertyValue::Raw(vec![1, 2, 3, 4, 5]));
+        assert_eq!(values[5], ("bEnabled".into(), PropertyValue::Bool(true)));
+        assert_eq!(properties[2].tag.property_type.to_string(), "StructProperty(Vector)");
+    }
+
+    #[test]
+    fn nested_values_share_the_allocation_budget() {
+        let mut data = Vec::new();
+        write_tag(&mut data, 8, 9, 12, &[2, 0]);
+        data.write_num::<LittleEndian, u8>(0).unwrap();
+        for value in [2, 7, -8] {
+            data.write_i32_le(value).unwrap();
+        }
+        data.write_i32_le(0).unwrap();
+        data.write_i32_le(0).unwrap();
+
+        let read = |limits| {
+            let mut reader = Reader::with_limits(&data[..], limits);
+            reader.set_version(ArchiveVersion::new(PackageFileVersion::new(522, 0), 0));
+            reader.set_names(NameTable::from(NAMES.map(String::from).to_vec()));
+            read_properties::<LittleEndian, _>(&mut reader).map(|_| reader.allocated())
+        };
+
+        // The value's 12 bytes and its array of two elements are both recorded by the parent.
+        let element = core::mem::size_of::<PropertyValue>() as u64;
+        assert_eq!(read(Limits::DEFAULT).unwrap(), 12 + 2 * element);
+
+        // The array is read from a buffered copy of the value, 4 bytes into it.
+        let error = read(Limits { max_total_bytes: 20, ..Limits::DEFAULT }).unwrap_err();
+        assert!(matches!(error.root(), ReadError::AllocationLimit { limit: 20, offset: Some(37), .. }));
+    }
+
+}
@@ -0,0 +1,293 @@
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(feature = "tokio")]
+use crate::AsyncUnrealReadExt;
+use crate::types::NameTable;
+use crate::{ArchiveVersion, Endian, Limits, ReadError, ReadExt, ReaderResult};
+
+/// A reader that tracks its position and enforces [`Limits`] across a whole session.
+///
+/// Plain byte sources only check the per-call limits in [`Limits::DEFAULT`];
+/// wrap them in a `Reader` to configure the limits, to bound the total number of bytes
+/// allocated, and to get byte offsets on every [`ReadError`].
+#[derive(Debug)]
+pub struct Reader<R> {
+    inner: R,
+    position: u64,
+    end: Option<u64>,
+    limits: Limits,
+    allocated: u64,
+    endian: Endian,
+    version: ArchiveVersion,
+    names: NameTable,
+}
+
+impl<R> Reader<R> {
+
+    pub fn new(inner: R) -> Self {
+        Self::with_limits(inner, Limits::DEFAULT)
+    }
+
+    pub fn with_limits(inner: R, limits: Limits) -> Self {
+        Self {
+            inner,
+            position: 0,
+            end: None,
+            limits,
+            allocated: 0,
+            endian: Endian::Little,
+            version: ArchiveVersion::UNVERSIONED,
+            names: NameTable::new(),
+        }
+    }
+
+    /// Returns the number of bytes read so far, or the stream position for
+    /// readers created with [`Reader::seekable`].
+    #[inline]
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
+    #[inline]
+    pub fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    pub fn set_limits(&mut self, limits: Limits) {
+        self.limits = limits;
+    }
+
+    /// Returns the number of bytes reserved by array and string buffers so far.
+    #[inline]
+    pub fn allocated(&self) -> u64 {
+        self.allocated
+    }
+
+    /// Sets the byte order reported by [`ReadExt::endian`], typically once a header
+    /// magic has revealed it.
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+    }
+
+    /// Sets the version state reported by [`ReadExt::version`] to nested readers.
+    pub fn set_version(&mut self, version: ArchiveVersion) {
+        self.version = version;
+    }
+
+    pub fn version_mut(&mut self) -> &mut ArchiveVersion {
+        &mut self.version
+    }
+
+    /// Sets the name table FNames are resolved against, typically once a package's
+    /// name map has been read.
+    pub fn set_names(&mut self, names: NameTable) {
+        self.names = names;
+    }
+
+    pub fn names_mut(&mut self) -> &mut NameTable {
+        &mut self.names
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn reserve(&mut self, bytes: u64) -> ReaderResult<()> {
+        let total = self.allocated.saturating_add(bytes);
+        if total > self.limits.max_total_bytes {
+            return Err(ReadError::AllocationLimit {
+                requested: total,
+                limit: self.limits.max_total_bytes,
+                offset: Some(self.position),
+            });
+        }
+
+        self.allocated = total;
+        Ok(())
+    }
+
+}
+
+#[cfg(feature = "std")]
+impl<R: io::Seek> Reader<R> {
+
+    /// Creates a reader over a seekable stream. Its length is used to reject length
+    /// prefixes that run past the end, and to bound array preallocation.
+    pub fn seekable(mut inner: R) -> io::Result<Self> {
+        let position = inner.stream_position()?;
+        let end = inner.seek(io::SeekFrom::End(0))?;
+        inner.seek(io::SeekFrom::Start(position))?;
+
+        let mut reader = Self::new(inner);
+        reader.position = position;
+        reader.end = Some(end);
+        Ok(reader)
+    }
+
+}
+
+impl<R: ReadExt> ReadExt for Reader<R> {
+
+    fn read_exact_bytes(&mut self, buffer: &mut [u8]) -> ReaderResult<()> {
+        self.inner
+            .read_exact_bytes(buffer)
+            .map_err(|e| e.at(self.position))?;
+        self.position += buffer.len() as u64;
+        Ok(())
+    }
+
+    #[inline]
+    fn offset(&self) -> Option<u64> {
+        Some(self.position)
+    }
+
+    #[inline]
+    fn remaining(&self) -> Option<u64> {
+        self.end.map(|end| end.saturating_sub(self.position))
+    }
+
+    #[inline]
+    fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    #[inline]
+    fn allocate(&mut self, bytes: u64) -> ReaderResult<()> {
+        self.reserve(bytes)
+    }
+
+    #[inline]
+    fn allocated(&self) -> u64 {
+        self.allocated
+    }
+
+    #[inline]
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    #[inline]
+    fn version(&self) -> &ArchiveVersion {
+        &self.version
+    }
+
+    #[inline]
+    fn names(&self) -> &NameTable {
+        &self.names
+    }
+
+}
+
+#[cfg(feature = "tokio")]
+impl<R: AsyncUnrealReadExt> AsyncUnrealReadExt for Reader<R> {
+
+    async fn read_exact_bytes(&mut self, buffer: &mut [u8]) -> ReaderResult<()> {
+        self.inner
+            .read_exact_bytes(buffer)
+            .await
+            .map_err(|e| e.at(self.position))?;
+        self.position += buffer.len() as u64;
+        Ok(())
+    }
+
+    #[inline]
+    fn offset(&self) -> Option<u64> {
+        Some(self.position)
+    }
+
+    #[inline]
+    fn remaining(&self) -> Option<u64> {
+        self.end.map(|end| end.saturating_sub(self.position))
+    }
+
+    #[inline]
+    fn limits(&self) -> &Limits {
+        &self.limits
+    }
+
+    #[inline]
+    fn allocate(&mut self, bytes: u64) -> ReaderResult<()> {
+        self.reserve(bytes)
+    }
+
+    #[inline]
+    fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    #[inline]
+    fn version(&self) -> &ArchiveVersion {
+        &self.version
+    }
+
+    #[inline]
+    fn names(&self) -> &NameTable {
+        &self.names
+    }
+
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::types::FGuid;
+    use crate::{ArchiveVersion, Limits, LittleEndian, PackageFileVersion, ReadError, ReadExt, Reader};
+
+    #[test]
+    fn reader_rejects_hostile_lengths() {
+        // An array claiming i32::MAX elements in a 4 byte stream.
+        let mut reader = Reader::seekable(Cursor::new(vec![0xFF, 0xFF, 0xFF, 0x7F])).unwrap();
+        let error = reader.read_array::<LittleEndian, _>(|r| r.read_u64_le()).unwrap_err();
+        assert!(matches!(error, ReadError::AllocationLimit { .. }));
+
+        // A string running past the end of the stream.
+        let mut reader = Reader::seekable(Cursor::new(vec![0x00, 0x00, 0x01, 0x00, 0x41])).unwrap();
+        let error = reader.read_fstring::<LittleEndian>().unwrap_err();
+        assert!(matches!(error, ReadError::UnexpectedEof { offset: Some(4) }));
+    }
+
+    #[test]
+    fn reader_limits_total_allocation() {
+        let limits = Limits { max_total_bytes: 8, ..Limits::DEFAULT };
+        let data = vec![4, 0, 0, 0, 0x41, 0x42, 0x43, 0x00, 6, 0, 0, 0, 0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x00];
+        let mut reader = Reader::with_limits(Cursor::new(data), limits);
+
+        assert_eq!(reader.read_fstring::<LittleEndian>().unwrap(), "ABC");
+        let error = reader.read_fstring::<LittleEndian>().unwrap_err();
+        assert!(matches!(error, ReadError::AllocationLimit { requested: 10, limit: 8, offset: Some(12) }));
+    }
+
+    #[test]
+    fn reader_versions_reach_element_readers() {
+        const KEY: FGuid = FGuid::new(1, 2, 3, 4);
+
+        fn read_items(custom_version: i32, data: Vec<u8>) -> Vec<u32> {
+            let mut version = ArchiveVersion::new(PackageFileVersion::new(522, 1004), 0);
+            version.set_custom_version(KEY, custom_version);
+
+            let mut reader = Reader::new(Cursor::new(data));
+            reader.set_version(version);
+            reader.read_array::<LittleEndian, _>(|ar| {
+                if ar.ue_version() >= PackageFileVersion::new(522, 0) && ar.custom_version(&KEY) >= Some(7) {
+                    ar.read_u32_le()
+                } else {
+                    ar.read_u16_le().map(u32::from)
+                }
+            }).unwrap()
+        }
+
+        assert_eq!(read_items(7, vec![2, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0]), [1, 2]);
+        assert_eq!(read_items(6, vec![2, 0, 0, 0, 1, 0, 2, 0]), [1, 2]);
+    }
+
+}
@@ -0,0 +1,185 @@
+use byteorder::ByteOrder;
+
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::{Primitive, ReadError, ReadExt, ReaderResult};
+#[cfg(feature = "std")]
+use crate::{WriteExt, WriterResult};
+
+/// A type that can be deserialized from a reader in the byte order `B`.
+///
+/// Implemented for primitives, `bool` (as Unreal's 4 byte bool), `String` (as an FString)
+/// and `Vec<T>` (as a TArray), and derivable for structs with `#[derive(ReadFrom)]`
+/// when the `derive` feature is enabled.
+pub trait ReadFrom: Sized {
+
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self>;
+
+}
+
+/// A type that can be serialized to a writer in the byte order `B`, producing
+/// exactly the bytes [`ReadFrom`] consumes. Requires the `std` feature.
+#[cfg(feature = "std")]
+pub trait WriteTo {
+
+    fn write_to<B: ByteOrder, W: WriteExt + ?Sized>(&self, writer: &mut W) -> WriterResult<()>;
+
+}
+
+macro_rules! impl_primitive_serialize {
+    ($($ty:ty),*) => {
+        $(
+            impl ReadFrom for $ty {
+                #[inline(always)]
+                fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+                    reader.read_num::<B, $ty>()
+                }
+            }
+
+            #[cfg(feature = "std")]
+            impl WriteTo for $ty {
+                #[inline(always)]
+                fn write_to<B: ByteOrder, W: WriteExt + ?Sized>(&self, writer: &mut W) -> WriterResult<()> {
+                    writer.write_num::<B, $ty>(*self)
+                }
+            }
+        )*
+    };
+}
+
+impl_primitive_serialize!(u8, i8, i16, u16, i32, u32, i64, u64, i128, u128, f32, f64);
+
+impl ReadFrom for bool {
+    #[inline]
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        reader.read_bool::<B>()
+    }
+}
+
+#[cfg(feature = "std")]
+impl WriteTo for bool {
+    #[inline]
+    fn write_to<B: ByteOrder, W: WriteExt + ?Sized>(&self, writer: &mut W) -> WriterResult<()> {
+        writer.write_bool::<B>(*self)
+    }
+}
+
+impl ReadFrom for String {
+    #[inline]
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        reader.read_fstring::<B>()
+    }
+}
+
+#[cfg(feature = "std")]
+impl WriteTo for String {
+    #[inline]
+    fn write_to<B: ByteOrder, W: WriteExt + ?Sized>(&self, writer: &mut W) -> WriterResult<()> {
+        writer.write_fstring::<B>(self)
+    }
+}
+
+impl<T: ReadFrom> ReadFrom for Vec<T> {
+    #[inline]
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        reader.read_array::<B, T>(|r| T::read_from::<B, _>(r))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: WriteTo> WriteTo for Vec<T> {
+    #[inline]
+    fn write_to<B: ByteOrder, W: WriteExt + ?Sized>(&self, writer: &mut W) -> WriterResult<()> {
+        writer.write_array::<B, T>(self, |w, item| item.write_to::<B, _>(w))
+    }
+}
+
+/// Support code for `#[derive(ReadFrom, WriteTo)]`. Not public API.
+#[doc(hidden)]
+pub mod __private {
+    use super::*;
+
+    #[cfg(feature = "std")]
+    use crate::StringEncoding;
+
+    /// Reads a TArray whose length prefix has the primitive type `L`.
+    pub fn read_vec<B, L, T, R>(reader: &mut R) -> ReaderResult<Vec<T>>
+    where
+        B: ByteOrder,
+        L: Primitive + Into<i128>,
+        T: ReadFrom,
+        R: ReadExt + ?Sized,
+    {
+        let offset = reader.offset();
+        let length: i128 = reader.read_num::<B, L>()?.into();
+        let length = i32::try_from(length).map_err(|_| ReadError::LengthOverflow {
+            length: length.clamp(i64::MIN.into(), i64::MAX.into()) as i64,
+            offset,
+        })?;
+
+        reader.read_array_with_length(|r| T::read_from::<B, _>(r), length)
+    }
+
+    /// Writes a TArray whose length prefix has the primitive type `L`.
+    #[cfg(feature = "std")]
+    pub fn write_vec<B, L, T, W>(writer: &mut W, items: &[T]) -> WriterResult<()>
+    where
+        B: ByteOrder,
+        L: Primitive + TryFrom<usize>,
+        T: WriteTo,
+        W: WriteExt + ?Sized,
+    {
+        let length = L::try_from(items.len()).map_err(|_| {
+            io::Error::new(io::ErrorKind::InvalidInput, format!("length {} does not fit in its prefix", items.len()))
+        })?;
+
+        writer.write_num::<B, L>(length)?;
+        writer.write_array_with_length(items, |w, item| item.write_to::<B, _>(w))
+    }
+
+    pub fn read_string<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R, lossy: bool) -> ReaderResult<String> {
+        if lossy {
+            reader.read_fstring_lossy::<B>()
+        } else {
+            reader.read_fstring::<B>()
+        }
+    }
+
+    #[cfg(feature = "std")]
+    pub fn write_string<B: ByteOrder, W: WriteExt + ?Sized>(
+        writer: &mut W,
+        value: &str,
+        encoding: StringEncoding,
+    ) -> WriterResult<()> {
+        writer.write_fstring_with::<B>(value, encoding)
+    }
+
+    pub fn skip<R: ReadExt + ?Sized>(reader: &mut R, count: usize) -> ReaderResult<()> {
+        let mut buffer = [0u8; 64];
+        let mut left = count;
+        while left > 0 {
+            let chunk = left.min(buffer.len());
+            reader.read_exact_bytes(&mut buffer[..chunk])?;
+            left -= chunk;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    pub fn pad<W: WriteExt + ?Sized>(writer: &mut W, count: usize) -> WriterResult<()> {
+        let buffer = [0u8; 64];
+        let mut left = count;
+        while left > 0 {
+            let chunk = left.min(buffer.len());
+            writer.write_all_bytes(&buffer[..chunk])?;
+            left -= chunk;
+        }
+
+        Ok(())
+    }
+
+}
@@ -0,0 +1,170 @@
+use byteorder::ByteOrder;
+
+use alloc::borrow::Cow;
+
+use crate::fstring;
+use crate::{ReadError, ReadExt, ReaderResult, StringEncoding};
+
+/// A reader over an in-memory byte slice, such as a memory-mapped file, that can hand
+/// out data borrowed from the slice instead of copying it.
+///
+/// It implements [`ReadExt`] directly, reporting its offset and the bytes left to
+/// every read.
+#[derive(Debug, Clone)]
+pub struct SliceReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> SliceReader<'a> {
+
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves to `position`, which may be at most the length of the slice.
+    pub fn set_position(&mut self, position: usize) -> ReaderResult<()> {
+        if position > self.data.len() {
+            return Err(ReadError::UnexpectedEof { offset: Some(position as u64) });
+        }
+
+        self.position = position;
+        Ok(())
+    }
+
+    /// Returns the whole underlying slice.
+    #[inline]
+    pub fn get_ref(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the part of the slice that has not been read yet.
+    #[inline]
+    pub fn remaining_slice(&self) -> &'a [u8] {
+        &self.data[self.position..]
+    }
+
+    /// Reads `length` bytes without copying them.
+    pub fn read_bytes(&mut self, length: usize) -> ReaderResult<&'a [u8]> {
+        let bytes = self.remaining_slice()
+            .get(..length)
+            .ok_or(ReadError::UnexpectedEof { offset: Some(self.position as u64) })?;
+        self.position += length;
+        Ok(bytes)
+    }
+
+    /// Reads an ANSI FString in the byte order `B`, borrowing it from the slice.
+    /// UTF-16 strings can't be borrowed and fail with [`ReadError::InvalidString`],
+    /// leaving the reader where it was; use [`SliceReader::read_fstring_cow`] to accept both.
+    pub fn read_fstring_borrowed<B: ByteOrder>(&mut self) -> ReaderResult<&'a str> {
+        let (start, encoding, payload) = self.read_fstring_payload::<B>()?;
+        if encoding == StringEncoding::Utf16 {
+            self.position = start as usize;
+            return Err(ReadError::InvalidString { encoding, offset: Some(start) });
+        }
+
+        match payload {
+            Some(bytes) => fstring::decode_ansi_str(bytes).map_err(|e| e.at(start)),
+            None => Ok(""),
+        }
+    }
+
+    /// Reads an FString in the byte order `B`, borrowing ANSI strings from the slice
+    /// and decoding UTF-16 strings into an owned `String`.
+    pub fn read_fstring_cow<B: ByteOrder>(&mut self) -> ReaderResult<Cow<'a, str>> {
+        self.read_fstring_cow_inner::<B>(false)
+    }
+
+    /// Like [`SliceReader::read_fstring_cow`], but replaces invalid sequences with U+FFFD.
+    pub fn read_fstring_lossy_cow<B: ByteOrder>(&mut self) -> ReaderResult<Cow<'a, str>> {
+        self.read_fstring_cow_inner::<B>(true)
+    }
+
+    fn read_fstring_cow_inner<B: ByteOrder>(&mut self, lossy: bool) -> ReaderResult<Cow<'a, str>> {
+        let (start, encoding, payload) = self.read_fstring_payload::<B>()?;
+        let bytes = match payload {
+            Some(bytes) => bytes,
+            None => return Ok(Cow::Borrowed("")),
+        };
+
+        let decoded = match encoding {
+            StringEncoding::Ansi if lossy => fstring::decode_ansi_lossy(bytes),
+            StringEncoding::Ansi => fstring::decode_ansi_str(bytes).map(Cow::Borrowed),
+            StringEncoding::Utf16 => fstring::decode_utf16::<B>(bytes, lossy).map(Cow::Owned),
+        };
+
+        decoded.map_err(|e| e.at(start))
+    }
+
+    /// Reads an FString length prefix and borrows its payload, `None` for empty strings.
+    fn read_fstring_payload<B: ByteOrder>(&mut self) -> ReaderResult<(u64, StringEncoding, Option<&'a [u8]>)> {
+        let start = self.position as u64;
+        let (encoding, size) = fstring::read_header::<B, _>(self)?;
+        if size == 0 {
+            return Ok((start, encoding, None));
+        }
+
+        let length = usize::try_from(size)
+            .map_err(|_| ReadError::UnexpectedEof { offset: Some(self.position as u64) })?;
+        Ok((start, encoding, Some(self.read_bytes(length)?)))
+    }
+
+}
+
+impl ReadExt for SliceReader<'_> {
+
+    #[inline]
+    fn read_exact_bytes(&mut self, buffer: &mut [u8]) -> ReaderResult<()> {
+        let bytes = self.read_bytes(buffer.len())?;
+        buffer.copy_from_slice(bytes);
+        Ok(())
+    }
+
+    #[inline]
+    fn offset(&self) -> Option<u64> {
+        Some(self.position as u64)
+    }
+
+    #[inline]
+    fn remaining(&self) -> Option<u64> {
+        Some((self.data.len() - self.position) as u64)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::borrow::Cow;
+    use core::ptr;
+
+    use crate::{LittleEndian, ReadError, ReadExt, SliceReader};
+
+    #[test]
+    fn slice_reader_borrows() {
+        let data = [6, 0, 0, 0, 0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x00, 0xAA, 0xBB, 2, 0, 0, 0];
+        let mut reader = SliceReader::new(&data);
+
+        let value = reader.read_fstring_borrowed::<LittleEndian>().unwrap();
+        assert_eq!(value, "Hello");
+        assert!(ptr::eq(value.as_ptr(), data[4..].as_ptr()));
+
+        assert_eq!(reader.read_bytes(2).unwrap(), &[0xAA, 0xBB]);
+        assert_eq!(reader.read_u32_le().unwrap(), 2);
+        assert!(matches!(reader.read_num::<LittleEndian, u8>(), Err(ReadError::UnexpectedEof { offset: Some(16) })));
+    }
+
+    #[test]
+    fn slice_reader_utf16_cow() {
+        let data = [0xFD, 0xFF, 0xFF, 0xFF, 0x48, 0x00, 0x69, 0x00, 0x00, 0x00];
+
+        let mut reader = SliceReader::new(&data);
+        assert!(matches!(reader.read_fstring_borrowed::<LittleEndian>(), Err(ReadError::InvalidString { .. })));
+        assert!(matches!(reader.read_fstring_cow::<LittleEndian>().unwrap(), Cow::Owned(value) if value == "Hi"));
+    }
+
+}
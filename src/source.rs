@@ -0,0 +1,101 @@
+#[cfg(feature = "std")]
+use std::io;
+
+#[cfg(not(feature = "std"))]
+use crate::ReadError;
+use crate::ReaderResult;
+
+/// A minimal source of bytes, which every [`ReadExt`](crate::ReadExt) method is built on.
+///
+/// With the `std` feature every [`io::Read`](std::io::Read) type is a byte source. Without
+/// it, `&[u8]` is, [`SliceReader`](crate::SliceReader) serves as the cursor over a slice,
+/// and other streams can implement this trait directly.
+pub trait ByteSource {
+
+    /// Fills `buffer` completely, failing with
+    /// [`ReadError::UnexpectedEof`](crate::ReadError::UnexpectedEof) if the source runs out.
+    fn read_into(&mut self, buffer: &mut [u8]) -> ReaderResult<()>;
+
+}
+
+#[cfg(feature = "std")]
+impl<Impl> ByteSource for Impl
+where
+    Impl: io::Read
+{
+
+    #[inline(always)]
+    fn read_into(&mut self, buffer: &mut [u8]) -> ReaderResult<()> {
+        Ok(self.read_exact(buffer)?)
+    }
+
+}
+
+#[cfg(not(feature = "std"))]
+impl ByteSource for &[u8] {
+
+    #[inline]
+    fn read_into(&mut self, buffer: &mut [u8]) -> ReaderResult<()> {
+        if buffer.len() > self.len() {
+            *self = &self[self.len()..];
+            return Err(ReadError::UnexpectedEof { offset: None });
+        }
+
+        let (bytes, rest) = self.split_at(buffer.len());
+        buffer.copy_from_slice(bytes);
+        *self = rest;
+        Ok(())
+    }
+
+}
+
+#[cfg(not(feature = "std"))]
+impl<R: ByteSource + ?Sized> ByteSource for &mut R {
+
+    #[inline(always)]
+    fn read_into(&mut self, buffer: &mut [u8]) -> ReaderResult<()> {
+        (**self).read_into(buffer)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ByteSource, LittleEndian, ReadError, ReadExt, ReaderResult};
+
+    /// A source that hands out a repeating byte, as a hardware register might.
+    struct Repeat(u8, usize);
+
+    impl ByteSource for Repeat {
+        fn read_into(&mut self, buffer: &mut [u8]) -> ReaderResult<()> {
+            if buffer.len() > self.1 {
+                return Err(ReadError::UnexpectedEof { offset: None });
+            }
+
+            buffer.fill(self.0);
+            self.1 -= buffer.len();
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn custom_byte_source() {
+        let mut source = Repeat(1, 6);
+        assert_eq!(source.read_u32_le().unwrap(), 0x01010101);
+        assert_eq!(source.read_array_with_length(|r| r.read_num::<LittleEndian, u8>(), 2).unwrap(), [1, 1]);
+        assert!(matches!(source.read_fstring::<LittleEndian>(), Err(ReadError::UnexpectedEof { .. })));
+    }
+
+    #[test]
+    fn slice_byte_source() {
+        let mut data: &[u8] = &[2, 0, 0, 0, 7, 0, 9, 0, 0xAA];
+        assert_eq!(data.read_u32_le().unwrap(), 2);
+
+        // Reading through `&mut &[u8]` advances the slice it borrows.
+        let mut source: &mut &[u8] = &mut data;
+        assert_eq!(ReadExt::read_array_with_length(&mut source, |r| r.read_u16_le(), 2).unwrap(), [7, 9]);
+        assert!(matches!(ReadExt::read_u16_le(&mut source), Err(ReadError::UnexpectedEof { .. })));
+        assert!(data.is_empty());
+    }
+
+}
@@ -0,0 +1,141 @@
+use byteorder::ByteOrder;
+
+use core::fmt;
+
+use crate::{ReadExt, ReadFrom, ReaderResult};
+#[cfg(feature = "std")]
+use crate::{WriteExt, WriteTo, WriterResult};
+
+/// An 8-bit sRGB color, `FColor` in Unreal.
+///
+/// It is serialized as a single u32 holding `A`, `R`, `G` and `B` from the most to the
+/// least significant byte, so its byte layout follows the byte order.
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FColor {
+    pub b: u8,
+    pub g: u8,
+    pub r: u8,
+    pub a: u8,
+}
+
+impl FColor {
+
+    pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { b, g, r, a }
+    }
+
+    /// Returns the color packed as Unreal's `DWColor`.
+    #[inline]
+    pub const fn to_packed(self) -> u32 {
+        u32::from_be_bytes([self.a, self.r, self.g, self.b])
+    }
+
+    #[inline]
+    pub const fn from_packed(value: u32) -> Self {
+        let [a, r, g, b] = value.to_be_bytes();
+        Self { b, g, r, a }
+    }
+
+}
+
+impl ReadFrom for FColor {
+    #[inline]
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        reader.read_num::<B, u32>().map(FColor::from_packed)
+    }
+}
+
+#[cfg(feature = "std")]
+impl WriteTo for FColor {
+    #[inline]
+    fn write_to<B: ByteOrder, W: WriteExt + ?Sized>(&self, writer: &mut W) -> WriterResult<()> {
+        writer.write_num::<B, u32>(self.to_packed())
+    }
+}
+
+impl fmt::Display for FColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(B={},G={},R={},A={})", self.b, self.g, self.r, self.a)
+    }
+}
+
+impl fmt::Debug for FColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+/// A linear color with float components, `FLinearColor` in Unreal. Unlike the math
+/// types it stays single precision with Large World Coordinates.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct FLinearColor {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+    pub a: f32,
+}
+
+impl FLinearColor {
+
+    pub const fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self { r, g, b, a }
+    }
+
+}
+
+impl ReadFrom for FLinearColor {
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        Ok(Self {
+            r: reader.read_num::<B, f32>()?,
+            g: reader.read_num::<B, f32>()?,
+            b: reader.read_num::<B, f32>()?,
+            a: reader.read_num::<B, f32>()?,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl WriteTo for FLinearColor {
+    fn write_to<B: ByteOrder, W: WriteExt + ?Sized>(&self, writer: &mut W) -> WriterResult<()> {
+        writer.write_num::<B, f32>(self.r)?;
+        writer.write_num::<B, f32>(self.g)?;
+        writer.write_num::<B, f32>(self.b)?;
+        writer.write_num::<B, f32>(self.a)
+    }
+}
+
+impl fmt::Display for FLinearColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(R={:.6},G={:.6},B={:.6},A={:.6})", self.r, self.g, self.b, self.a)
+    }
+}
+
+impl fmt::Debug for FLinearColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::types::{FColor, FLinearColor};
+    use crate::{BigEndian, LittleEndian, ReadFrom, WriteTo};
+
+    #[test]
+    fn colors_round_trip() {
+        let color = FColor::read_from::<LittleEndian, _>(&mut &[0x10, 0x20, 0x30, 0xFF][..]).unwrap();
+        assert_eq!(color, FColor::new(0x30, 0x20, 0x10, 0xFF));
+        assert_eq!(color.to_string(), "(B=16,G=32,R=48,A=255)");
+
+        let mut output = Vec::new();
+        color.write_to::<BigEndian, _>(&mut output).unwrap();
+        assert_eq!(output, [0xFF, 0x30, 0x20, 0x10]);
+
+        let linear = FLinearColor::new(1.0, 0.5, 0.0, 1.0);
+        let mut output = Vec::new();
+        linear.write_to::<LittleEndian, _>(&mut output).unwrap();
+        assert_eq!(FLinearColor::read_from::<LittleEndian, _>(&mut &output[..]).unwrap(), linear);
+        assert_eq!(format!("{:?}", linear), "(R=1.000000,G=0.500000,B=0.000000,A=1.000000)");
+    }
+
+}
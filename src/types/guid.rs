@@ -0,0 +1,62 @@
+use byteorder::ByteOrder;
+
+use core::fmt;
+
+use crate::{ReadExt, ReadFrom, ReaderResult};
+#[cfg(feature = "std")]
+use crate::{WriteExt, WriteTo, WriterResult};
+
+/// A 128-bit globally unique identifier, serialized as four u32s.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FGuid {
+    pub a: u32,
+    pub b: u32,
+    pub c: u32,
+    pub d: u32,
+}
+
+impl FGuid {
+
+    pub const fn new(a: u32, b: u32, c: u32, d: u32) -> Self {
+        Self { a, b, c, d }
+    }
+
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        (self.a | self.b | self.c | self.d) != 0
+    }
+
+}
+
+impl fmt::Display for FGuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:08X}{:08X}{:08X}{:08X}", self.a, self.b, self.c, self.d)
+    }
+}
+
+impl fmt::Debug for FGuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl ReadFrom for FGuid {
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        Ok(Self {
+            a: reader.read_num::<B, u32>()?,
+            b: reader.read_num::<B, u32>()?,
+            c: reader.read_num::<B, u32>()?,
+            d: reader.read_num::<B, u32>()?,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl WriteTo for FGuid {
+    fn write_to<B: ByteOrder, W: WriteExt + ?Sized>(&self, writer: &mut W) -> WriterResult<()> {
+        writer.write_num::<B, u32>(self.a)?;
+        writer.write_num::<B, u32>(self.b)?;
+        writer.write_num::<B, u32>(self.c)?;
+        writer.write_num::<B, u32>(self.d)
+    }
+}
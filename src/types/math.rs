@@ -0,0 +1,228 @@
+use byteorder::ByteOrder;
+
+use core::fmt;
+
+use crate::{ReadExt, ReadFrom, ReaderResult};
+#[cfg(feature = "std")]
+use crate::{WriteExt, WriteTo, WriterResult};
+
+/// Reads a component that is a double in packages saved with Large World Coordinates
+/// and a float before.
+#[inline]
+fn read_real<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<f64> {
+    if reader.version().large_world_coordinates() {
+        reader.read_num::<B, f64>()
+    } else {
+        reader.read_num::<B, f32>().map(f64::from)
+    }
+}
+
+#[cfg(feature = "std")]
+#[inline]
+fn write_real<B: ByteOrder, W: WriteExt + ?Sized>(writer: &mut W, value: f64) -> WriterResult<()> {
+    if writer.version().large_world_coordinates() {
+        writer.write_num::<B, f64>(value)
+    } else {
+        writer.write_num::<B, f32>(value as f32)
+    }
+}
+
+/// Implements reading, writing and Unreal's text export for a struct of real components.
+macro_rules! impl_real_struct {
+    ($ty:ident { $first:ident => $first_export:literal $(, $field:ident => $export:literal)* }) => {
+        impl ReadFrom for $ty {
+            fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+                Ok(Self {
+                    $first: read_real::<B, R>(reader)?,
+                    $($field: read_real::<B, R>(reader)?,)*
+                })
+            }
+        }
+
+        #[cfg(feature = "std")]
+        impl WriteTo for $ty {
+            fn write_to<B: ByteOrder, W: WriteExt + ?Sized>(&self, writer: &mut W) -> WriterResult<()> {
+                write_real::<B, W>(writer, self.$first)?;
+                $(write_real::<B, W>(writer, self.$field)?;)*
+                Ok(())
+            }
+        }
+
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(
+                    f,
+                    concat!("(", $first_export, "={:.6}", $(",", $export, "={:.6}",)* ")"),
+                    self.$first, $(self.$field),*
+                )
+            }
+        }
+
+        impl fmt::Debug for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(self, f)
+            }
+        }
+    };
+}
+
+/// A 3D vector, `FVector` in Unreal.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct FVector {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl FVector {
+
+    pub const ZERO: FVector = FVector::new(0.0, 0.0, 0.0);
+    pub const ONE: FVector = FVector::new(1.0, 1.0, 1.0);
+
+    pub const fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+
+}
+
+impl_real_struct!(FVector { x => "X", y => "Y", z => "Z" });
+
+/// A rotation in degrees, `FRotator` in Unreal.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct FRotator {
+    pub pitch: f64,
+    pub yaw: f64,
+    pub roll: f64,
+}
+
+impl FRotator {
+
+    pub const ZERO: FRotator = FRotator::new(0.0, 0.0, 0.0);
+
+    pub const fn new(pitch: f64, yaw: f64, roll: f64) -> Self {
+        Self { pitch, yaw, roll }
+    }
+
+}
+
+impl_real_struct!(FRotator { pitch => "Pitch", yaw => "Yaw", roll => "Roll" });
+
+/// A rotation quaternion, `FQuat` in Unreal.
+#[derive(Clone, Copy, PartialEq)]
+pub struct FQuat {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+impl FQuat {
+
+    pub const IDENTITY: FQuat = FQuat::new(0.0, 0.0, 0.0, 1.0);
+
+    pub const fn new(x: f64, y: f64, z: f64, w: f64) -> Self {
+        Self { x, y, z, w }
+    }
+
+}
+
+impl Default for FQuat {
+    fn default() -> Self {
+        FQuat::IDENTITY
+    }
+}
+
+impl_real_struct!(FQuat { x => "X", y => "Y", z => "Z", w => "W" });
+
+/// A rotation, translation and scale, `FTransform` in Unreal.
+#[derive(Clone, Copy, PartialEq)]
+pub struct FTransform {
+    pub rotation: FQuat,
+    pub translation: FVector,
+    pub scale3d: FVector,
+}
+
+impl FTransform {
+
+    pub const IDENTITY: FTransform = FTransform {
+        rotation: FQuat::IDENTITY,
+        translation: FVector::ZERO,
+        scale3d: FVector::ONE,
+    };
+
+}
+
+impl Default for FTransform {
+    fn default() -> Self {
+        FTransform::IDENTITY
+    }
+}
+
+impl ReadFrom for FTransform {
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        Ok(Self {
+            rotation: FQuat::read_from::<B, R>(reader)?,
+            translation: FVector::read_from::<B, R>(reader)?,
+            scale3d: FVector::read_from::<B, R>(reader)?,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl WriteTo for FTransform {
+    fn write_to<B: ByteOrder, W: WriteExt + ?Sized>(&self, writer: &mut W) -> WriterResult<()> {
+        self.rotation.write_to::<B, W>(writer)?;
+        self.translation.write_to::<B, W>(writer)?;
+        self.scale3d.write_to::<B, W>(writer)
+    }
+}
+
+impl fmt::Display for FTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "(Rotation={},Translation={},Scale3D={})", self.rotation, self.translation, self.scale3d)
+    }
+}
+
+impl fmt::Debug for FTransform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::types::{FQuat, FTransform, FVector};
+    use crate::{ArchiveVersion, LittleEndian, PackageFileVersion, ReadFrom, Reader, WriteTo, Writer};
+
+    #[test]
+    fn math_types_follow_large_world_coordinates() {
+        let transform = FTransform {
+            rotation: FQuat::new(0.0, 0.0, 0.5, 0.75),
+            translation: FVector::new(1.0, -2.5, 3.0),
+            scale3d: FVector::ONE,
+        };
+
+        for (ue5, size) in [(0, 40), (1004, 80)] {
+            let version = ArchiveVersion::new(PackageFileVersion::new(522, ue5), 0);
+
+            let mut writer = Writer::new(Vec::new());
+            writer.set_version(version.clone());
+            transform.write_to::<LittleEndian, _>(&mut writer).unwrap();
+            let data = writer.into_inner();
+            assert_eq!(data.len(), size);
+
+            let mut reader = Reader::new(&data[..]);
+            reader.set_version(version);
+            assert_eq!(FTransform::read_from::<LittleEndian, _>(&mut reader).unwrap(), transform);
+        }
+
+        assert_eq!(
+            transform.to_string(),
+            "(Rotation=(X=0.000000,Y=0.000000,Z=0.500000,W=0.750000),\
+             Translation=(X=1.000000,Y=-2.500000,Z=3.000000),\
+             Scale3D=(X=1.000000,Y=1.000000,Z=1.000000))",
+        );
+        assert_eq!(format!("{:?}", FVector::ZERO), "(X=0.000000,Y=0.000000,Z=0.000000)");
+    }
+
+}
@@ -0,0 +1,17 @@
+//! Core Unreal Engine value types.
+
+mod color;
+mod guid;
+mod math;
+mod name;
+mod soft_object;
+mod text;
+
+pub use color::{FColor, FLinearColor};
+pub use guid::FGuid;
+pub use math::{FQuat, FRotator, FTransform, FVector};
+pub use name::{FName, NameTable, NameTableIter, RawFName};
+pub use soft_object::FSoftObjectPath;
+pub use text::{FText, FormatArgumentValue, NumberFormattingOptions, TextHistory};
+
+pub(crate) use name::EMPTY_NAMES;
@@ -0,0 +1,212 @@
+use byteorder::ByteOrder;
+
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt;
+use core::slice;
+
+use crate::{ReadError, ReadExt, ReadFrom, ReaderResult};
+#[cfg(feature = "std")]
+use crate::{WriteExt, WriteTo, WriterResult};
+
+/// A name resolved against a [`NameTable`]: a string shared with the table and an
+/// instance number.
+///
+/// As in Unreal, the number is stored plus one, so `Name` with number 4 displays as `Name_3`
+/// and number 0 means no suffix.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FName {
+    name: Arc<str>,
+    number: i32,
+}
+
+impl FName {
+
+    pub fn new(name: impl Into<Arc<str>>, number: i32) -> Self {
+        Self { name: name.into(), number }
+    }
+
+    /// Returns the name without its number suffix.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[inline]
+    pub fn number(&self) -> i32 {
+        self.number
+    }
+
+    /// Returns true for `None`, Unreal's empty name.
+    #[inline]
+    pub fn is_none(&self) -> bool {
+        &*self.name == "None" && self.number == 0
+    }
+
+}
+
+impl fmt::Display for FName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.number > 0 {
+            write!(f, "{}_{}", self.name, self.number - 1)
+        } else {
+            f.write_str(&self.name)
+        }
+    }
+}
+
+impl ReadFrom for FName {
+    #[inline]
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        reader.read_fname::<B>()
+    }
+}
+
+/// An FName as serialized: an index into the name table and an instance number,
+/// for tools that work with indices instead of resolved names.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RawFName {
+    pub index: i32,
+    pub number: i32,
+}
+
+impl ReadFrom for RawFName {
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        Ok(Self {
+            index: reader.read_num::<B, i32>()?,
+            number: reader.read_num::<B, i32>()?,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl WriteTo for RawFName {
+    fn write_to<B: ByteOrder, W: WriteExt + ?Sized>(&self, writer: &mut W) -> WriterResult<()> {
+        writer.write_num::<B, i32>(self.index)?;
+        writer.write_num::<B, i32>(self.number)
+    }
+}
+
+/// The names of a package, which serialized FNames index into.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NameTable {
+    names: Vec<Arc<str>>,
+}
+
+impl NameTable {
+
+    pub const fn new() -> Self {
+        Self { names: Vec::new() }
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn get(&self, index: i32) -> Option<&str> {
+        let index = usize::try_from(index).ok()?;
+        self.names.get(index).map(|name| &**name)
+    }
+
+    pub fn push(&mut self, name: impl Into<Arc<str>>) {
+        self.names.push(name.into());
+    }
+
+    pub fn iter(&self) -> NameTableIter<'_> {
+        NameTableIter { inner: self.names.iter() }
+    }
+
+    /// Resolves `raw` against the table, failing with [`ReadError::InvalidNameIndex`]
+    /// if its index is out of range.
+    pub fn resolve(&self, raw: RawFName) -> ReaderResult<FName> {
+        usize::try_from(raw.index)
+            .ok()
+            .and_then(|index| self.names.get(index))
+            .map(|name| FName { name: Arc::clone(name), number: raw.number })
+            .ok_or(ReadError::InvalidNameIndex { index: raw.index, len: self.names.len(), offset: None })
+    }
+
+}
+
+impl FromIterator<String> for NameTable {
+    fn from_iter<I: IntoIterator<Item = String>>(iter: I) -> Self {
+        Self { names: iter.into_iter().map(Arc::from).collect() }
+    }
+}
+
+impl From<Vec<String>> for NameTable {
+    fn from(names: Vec<String>) -> Self {
+        names.into_iter().collect()
+    }
+}
+
+/// Reads the table as a TArray of FStrings.
+impl ReadFrom for NameTable {
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        let names = reader.read_array::<B, _>(|r| r.read_fstring::<B>())?;
+        Ok(names.into())
+    }
+}
+
+/// An iterator over the names in a [`NameTable`].
+#[derive(Debug, Clone)]
+pub struct NameTableIter<'a> {
+    inner: slice::Iter<'a, Arc<str>>,
+}
+
+impl<'a> Iterator for NameTableIter<'a> {
+    type Item = &'a str;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|name| &**name)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+pub(crate) static EMPTY_NAMES: NameTable = NameTable::new();
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    use crate::types::{NameTable, RawFName};
+    use crate::{LittleEndian, ReadError, ReadExt, ReadFrom, Reader};
+
+    #[test]
+    fn read_fname_resolves_names() {
+        let names = NameTable::read_from::<LittleEndian, _>(&mut &[
+            2, 0, 0, 0,
+            5, 0, 0, 0, b'N', b'o', b'n', b'e', 0,
+            5, 0, 0, 0, b'N', b'a', b'm', b'e', 0,
+        ][..]).unwrap();
+        assert_eq!(names.iter().collect::<Vec<_>>(), ["None", "Name"]);
+
+        let data = [1u8, 0, 0, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 7, 0, 0, 0, 0, 0, 0, 0];
+        let mut reader = Reader::new(&data[..]);
+        reader.set_names(names);
+
+        assert_eq!(reader.read_fname::<LittleEndian>().unwrap().to_string(), "Name_3");
+        assert!(reader.read_fname::<LittleEndian>().unwrap().is_none());
+
+        let error = reader.read_fname::<LittleEndian>().unwrap_err();
+        assert!(matches!(error, ReadError::InvalidNameIndex { index: 7, len: 2, offset: Some(16) }));
+        assert_eq!(error.to_string(), "name index 7 is out of range for a name table of 2 entries at offset 0x10");
+
+        let mut data = &[7u8, 0, 0, 0, 1, 0, 0, 0][..];
+        assert_eq!(data.read_fname_raw::<LittleEndian>().unwrap(), RawFName { index: 7, number: 1 });
+    }
+
+}
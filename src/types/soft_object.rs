@@ -0,0 +1,48 @@
+use byteorder::ByteOrder;
+
+use alloc::string::{String, ToString};
+use core::fmt;
+
+use crate::{ObjectVersionUE5, ReadExt, ReadFrom, ReaderResult};
+
+/// A path to an object that is loaded on demand, `FSoftObjectPath` in Unreal.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct FSoftObjectPath {
+    /// The path of the top-level asset, such as `/Game/Foo.Foo`, or `None`.
+    pub asset_path: String,
+    /// The path of a subobject within the asset, usually empty.
+    pub sub_path: String,
+}
+
+impl ReadFrom for FSoftObjectPath {
+    /// Reads a path saved as an asset path FName before UE 5.1 and as a package and
+    /// asset name pair since.
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        let asset_path = if reader.ue_version().ue5 >= ObjectVersionUE5::FSOFTOBJECTPATH_REMOVE_ASSET_PATH_FNAMES {
+            let package_name = reader.read_fname::<B>()?;
+            let asset_name = reader.read_fname::<B>()?;
+            if asset_name.is_none() {
+                package_name.to_string()
+            } else {
+                alloc::format!("{}.{}", package_name, asset_name)
+            }
+        } else {
+            reader.read_fname::<B>()?.to_string()
+        };
+
+        Ok(Self {
+            asset_path,
+            sub_path: reader.read_fstring::<B>()?,
+        })
+    }
+}
+
+impl fmt::Display for FSoftObjectPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.asset_path)?;
+        if !self.sub_path.is_empty() {
+            write!(f, ":{}", self.sub_path)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,431 @@
+use byteorder::ByteOrder;
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::limits;
+use crate::types::FName;
+use crate::{EditorObjectVersion, ObjectVersion, ReadError, ReadExt, ReadFrom, ReaderResult};
+
+/// How deep texts may nest inside the format arguments of other texts.
+const MAX_TEXT_DEPTH: u32 = 32;
+
+/// The bytes of a text before its history payload: the flags and the history type.
+const TEXT_HEADER_SIZE: u64 = 5;
+
+/// A localizable text, `FText` in Unreal: its flags and the history that produces its
+/// display string.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FText {
+    pub flags: u32,
+    pub history: TextHistory,
+}
+
+/// How a text is built, `FTextHistory` in Unreal, with one variant per history type.
+///
+/// Date and time values are `FDateTime` ticks: 100 nanosecond intervals since
+/// January 1, 0001.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum TextHistory {
+    /// A text that is not localized, with its culture invariant string if it has one.
+    None { culture_invariant_string: Option<String> },
+    /// A localized string, looked up by namespace and key.
+    Base { namespace: String, key: String, source_string: String },
+    NamedFormat { source_format: Box<FText>, arguments: Vec<(String, FormatArgumentValue)> },
+    OrderedFormat { source_format: Box<FText>, arguments: Vec<FormatArgumentValue> },
+    ArgumentFormat { source_format: Box<FText>, arguments: Vec<(String, FormatArgumentValue)> },
+    AsNumber { source_value: FormatArgumentValue, format_options: Option<NumberFormattingOptions>, target_culture: String },
+    AsPercent { source_value: FormatArgumentValue, format_options: Option<NumberFormattingOptions>, target_culture: String },
+    AsCurrency {
+        currency_code: String,
+        source_value: FormatArgumentValue,
+        format_options: Option<NumberFormattingOptions>,
+        target_culture: String,
+    },
+    AsDate { date_time: i64, date_style: i8, time_zone: String, target_culture: String },
+    AsTime { date_time: i64, time_style: i8, time_zone: String, target_culture: String },
+    AsDateTime { date_time: i64, date_style: i8, time_style: i8, time_zone: String, target_culture: String },
+    /// A text converted to upper or lower case.
+    Transform { source_text: Box<FText>, transform_type: u8 },
+    StringTableEntry { table_id: FName, key: String },
+    /// A text made by a generator, with the generator's own serialized data.
+    TextGenerator { generator_type: FName, data: Vec<u8> },
+    /// A history type this crate does not know, with the bytes of its payload.
+    Unknown { history_type: i8, data: Vec<u8> },
+}
+
+impl Default for TextHistory {
+    fn default() -> Self {
+        TextHistory::None { culture_invariant_string: None }
+    }
+}
+
+/// A format argument of a text, `FFormatArgumentValue` in Unreal.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatArgumentValue {
+    Int(i64),
+    UInt(u64),
+    Float(f32),
+    Double(f64),
+    Text(Box<FText>),
+    Gender(u8),
+}
+
+/// How a number is formatted into a text, `FNumberFormattingOptions` in Unreal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NumberFormattingOptions {
+    pub always_sign: bool,
+    pub use_grouping: bool,
+    pub rounding_mode: i8,
+    pub minimum_integral_digits: i32,
+    pub maximum_integral_digits: i32,
+    pub minimum_fractional_digits: i32,
+    pub maximum_fractional_digits: i32,
+}
+
+impl FText {
+
+    /// Reads a text known to take `size` bytes, keeping the payload of a history type
+    /// this crate does not know as [`TextHistory::Unknown`] instead of failing.
+    pub fn read_sized<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R, size: u64) -> ReaderResult<Self> {
+        Self::read_at::<B, R>(reader, Some(size), 0)
+    }
+
+    /// Returns the string the text was made from, for histories that have one.
+    pub fn source_string(&self) -> Option<&str> {
+        match &self.history {
+            TextHistory::None { culture_invariant_string } => culture_invariant_string.as_deref(),
+            TextHistory::Base { source_string, .. } => Some(source_string),
+            TextHistory::NamedFormat { source_format, .. }
+            | TextHistory::OrderedFormat { source_format, .. }
+            | TextHistory::ArgumentFormat { source_format, .. } => source_format.source_string(),
+            TextHistory::Transform { source_text, .. } => source_text.source_string(),
+            _ => None,
+        }
+    }
+
+    fn read_at<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R, size: Option<u64>, depth: u32) -> ReaderResult<Self> {
+        let offset = reader.offset();
+        if depth > MAX_TEXT_DEPTH {
+            return Err(ReadError::NestingLimit { limit: MAX_TEXT_DEPTH, offset });
+        }
+
+        // Before histories a text is its source string, namespace and key, then the flags.
+        if reader.ue_version().ue4 < ObjectVersion::FTEXT_HISTORY {
+            let source_string = reader.read_fstring::<B>()?;
+            let (namespace, key) = if reader.ue_version().ue4 >= ObjectVersion::ADDED_NAMESPACE_AND_KEY_DATA_TO_FTEXT {
+                (reader.read_fstring::<B>()?, reader.read_fstring::<B>()?)
+            } else {
+                (String::new(), String::new())
+            };
+            let flags = reader.read_num::<B, u32>()?;
+            return Ok(Self { flags, history: TextHistory::Base { namespace, key, source_string } });
+        }
+
+        let flags = reader.read_num::<B, u32>()?;
+        let history_type = reader.read_num::<B, i8>()?;
+        let history = reader.with_context("History", |r| read_history::<B, R>(r, history_type, size, depth))?;
+        Ok(Self { flags, history })
+    }
+
+}
+
+impl ReadFrom for FText {
+    /// Reads a text of unknown size, failing on history types this crate does not know.
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        Self::read_at::<B, R>(reader, None, 0)
+    }
+}
+
+fn read_history<B: ByteOrder, R: ReadExt + ?Sized>(
+    reader: &mut R,
+    history_type: i8,
+    size: Option<u64>,
+    depth: u32,
+) -> ReaderResult<TextHistory> {
+    let offset = reader.offset();
+    let history = match history_type {
+        -1 => {
+            let has_string = reader.custom_version(&EditorObjectVersion::GUID)
+                >= Some(EditorObjectVersion::CULTURE_INVARIANT_TEXT_SERIALIZATION_KEY_STABILITY)
+                && reader.read_bool::<B>()?;
+            let culture_invariant_string = if has_string { Some(reader.read_fstring::<B>()?) } else { None };
+            TextHistory::None { culture_invariant_string }
+        }
+        0 => TextHistory::Base {
+            namespace: reader.read_fstring::<B>()?,
+            key: reader.read_fstring::<B>()?,
+            source_string: reader.read_fstring::<B>()?,
+        },
+        1 => TextHistory::NamedFormat {
+            source_format: Box::new(FText::read_at::<B, R>(reader, None, depth + 1)?),
+            arguments: reader.read_array::<B, _>(|r| {
+                Ok((r.read_fstring::<B>()?, read_argument_value::<B, R>(r, depth)?))
+            })?,
+        },
+        2 => TextHistory::OrderedFormat {
+            source_format: Box::new(FText::read_at::<B, R>(reader, None, depth + 1)?),
+            arguments: reader.read_array::<B, _>(|r| read_argument_value::<B, R>(r, depth))?,
+        },
+        3 => TextHistory::ArgumentFormat {
+            source_format: Box::new(FText::read_at::<B, R>(reader, None, depth + 1)?),
+            arguments: reader.read_array::<B, _>(|r| read_argument_data::<B, R>(r, depth))?,
+        },
+        4 | 5 => {
+            let source_value = read_argument_value::<B, R>(reader, depth)?;
+            let format_options = read_format_options::<B, R>(reader)?;
+            let target_culture = reader.read_fstring::<B>()?;
+            if history_type == 4 {
+                TextHistory::AsNumber { source_value, format_options, target_culture }
+            } else {
+                TextHistory::AsPercent { source_value, format_options, target_culture }
+            }
+        }
+        6 => {
+            let currency_code = if reader.ue_version().ue4 >= ObjectVersion::ADDED_CURRENCY_CODE_TO_FTEXT {
+                reader.read_fstring::<B>()?
+            } else {
+                String::new()
+            };
+            TextHistory::AsCurrency {
+                currency_code,
+                source_value: read_argument_value::<B, R>(reader, depth)?,
+                format_options: read_format_options::<B, R>(reader)?,
+                target_culture: reader.read_fstring::<B>()?,
+            }
+        }
+        7 => {
+            let date_time = reader.read_num::<B, i64>()?;
+            let date_style = reader.read_num::<B, i8>()?;
+            let time_zone = if reader.ue_version().ue4 >= ObjectVersion::FTEXT_HISTORY_DATE_TIMEZONE {
+                reader.read_fstring::<B>()?
+            } else {
+                String::new()
+            };
+            TextHistory::AsDate { date_time, date_style, time_zone, target_culture: reader.read_fstring::<B>()? }
+        }
+        8 => TextHistory::AsTime {
+            date_time: reader.read_num::<B, i64>()?,
+            time_style: reader.read_num::<B, i8>()?,
+            time_zone: reader.read_fstring::<B>()?,
+            target_culture: reader.read_fstring::<B>()?,
+        },
+        9 => TextHistory::AsDateTime {
+            date_time: reader.read_num::<B, i64>()?,
+            date_style: reader.read_num::<B, i8>()?,
+            time_style: reader.read_num::<B, i8>()?,
+            time_zone: reader.read_fstring::<B>()?,
+            target_culture: reader.read_fstring::<B>()?,
+        },
+        10 => TextHistory::Transform {
+            source_text: Box::new(FText::read_at::<B, R>(reader, None, depth + 1)?),
+            transform_type: reader.read_num::<B, u8>()?,
+        },
+        11 => TextHistory::StringTableEntry {
+            table_id: reader.read_fname::<B>()?,
+            key: reader.read_fstring::<B>()?,
+        },
+        12 => {
+            let generator_type = reader.read_fname::<B>()?;
+            let data = if generator_type.is_none() { Vec::new() } else { reader.read_array::<B, _>(|r| r.read_num::<B, u8>())? };
+            TextHistory::TextGenerator { generator_type, data }
+        }
+        _ => {
+            let unsupported = || ReadError::UnsupportedType { name: alloc::format!("text history {}", history_type), offset };
+            let size = size.and_then(|size| size.checked_sub(TEXT_HEADER_SIZE)).ok_or_else(unsupported)?;
+            TextHistory::Unknown { history_type, data: limits::read_buffer(reader, size)? }
+        }
+    };
+
+    Ok(history)
+}
+
+fn read_argument_value<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R, depth: u32) -> ReaderResult<FormatArgumentValue> {
+    let offset = reader.offset();
+    let argument_type = reader.read_num::<B, i8>()?;
+    read_argument_payload::<B, R>(reader, argument_type, offset, depth)
+}
+
+/// Reads a named argument of an argument format, which is always a text in packages
+/// saved before the argument types of `FFormatArgumentValue` were supported.
+fn read_argument_data<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R, depth: u32) -> ReaderResult<(String, FormatArgumentValue)> {
+    let name = reader.read_fstring::<B>()?;
+    if reader.custom_version(&EditorObjectVersion::GUID) < Some(EditorObjectVersion::TEXT_FORMAT_ARGUMENT_DATA_IS_VARIANT) {
+        return Ok((name, FormatArgumentValue::Text(Box::new(FText::read_at::<B, R>(reader, None, depth + 1)?))));
+    }
+
+    let offset = reader.offset();
+    let argument_type = reader.read_num::<B, u8>()? as i8;
+    Ok((name, read_argument_payload::<B, R>(reader, argument_type, offset, depth)?))
+}
+
+fn read_argument_payload<B: ByteOrder, R: ReadExt + ?Sized>(
+    reader: &mut R,
+    argument_type: i8,
+    offset: Option<u64>,
+    depth: u32,
+) -> ReaderResult<FormatArgumentValue> {
+    let value = match argument_type {
+        0 => FormatArgumentValue::Int(reader.read_num::<B, i64>()?),
+        1 => FormatArgumentValue::UInt(reader.read_num::<B, u64>()?),
+        2 => FormatArgumentValue::Float(reader.read_num::<B, f32>()?),
+        3 => FormatArgumentValue::Double(reader.read_num::<B, f64>()?),
+        4 => FormatArgumentValue::Text(Box::new(FText::read_at::<B, R>(reader, None, depth + 1)?)),
+        5 => FormatArgumentValue::Gender(reader.read_num::<B, u8>()?),
+        _ => {
+            let name = alloc::format!("format argument type {}", argument_type);
+            return Err(ReadError::UnsupportedType { name, offset });
+        }
+    };
+    Ok(value)
+}
+
+fn read_format_options<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Option<NumberFormattingOptions>> {
+    if !reader.read_bool::<B>()? {
+        return Ok(None);
+    }
+
+    let always_sign = reader.custom_version(&EditorObjectVersion::GUID)
+        >= Some(EditorObjectVersion::ADDED_ALWAYS_SIGN_NUMBER_FORMATTING_OPTION)
+        && reader.read_bool::<B>()?;
+    Ok(Some(NumberFormattingOptions {
+        always_sign,
+        use_grouping: reader.read_bool::<B>()?,
+        rounding_mode: reader.read_num::<B, i8>()?,
+        minimum_integral_digits: reader.read_num::<B, i32>()?,
+        maximum_integral_digits: reader.read_num::<B, i32>()?,
+        minimum_fractional_digits: reader.read_num::<B, i32>()?,
+        maximum_fractional_digits: reader.read_num::<B, i32>()?,
+    }))
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::types::{FText, FormatArgumentValue, NameTable, NumberFormattingOptions, TextHistory};
+    use crate::{ArchiveVersion, EditorObjectVersion, LittleEndian, ObjectVersion, PackageFileVersion, ReadFrom, Reader, WriteExt};
+
+    fn version() -> ArchiveVersion {
+        let mut version = ArchiveVersion::new(PackageFileVersion::new(522, 1012), 0);
+        version.set_custom_version(EditorObjectVersion::GUID, 40);
+        version
+    }
+
+    #[test]
+    fn read_text_histories() {
+        // OrderedFormat of Base "{0} apples" with the argument 3.
+        let mut data = Vec::new();
+        data.write_u32_le(0).unwrap();
+        data.write_num::<LittleEndian, i8>(2).unwrap();
+        data.write_u32_le(0).unwrap();
+        data.write_num::<LittleEndian, i8>(0).unwrap();
+        for string in ["Game", "Apples", "{0} apples"] {
+            data.write_fstring::<LittleEndian>(string).unwrap();
+        }
+        data.write_i32_le(1).unwrap();
+        data.write_num::<LittleEndian, i8>(0).unwrap();
+        data.write_i64_le(3).unwrap();
+
+        let mut reader = Reader::new(&data[..]);
+        reader.set_version(version());
+        let text = FText::read_from::<LittleEndian, _>(&mut reader).unwrap();
+        assert!(reader.get_ref().is_empty());
+        assert_eq!(text.source_string(), Some("{0} apples"));
+        let TextHistory::OrderedFormat { arguments, .. } = &text.history else { panic!("{:?}", text) };
+        assert_eq!(arguments, &[FormatArgumentValue::Int(3)]);
+
+        // A history type from the future is kept whole when the size is known.
+        let data = [2, 0, 0, 0, 42, 1, 2, 3];
+        let mut reader = Reader::new(&data[..]);
+        reader.set_version(version());
+        let text = FText::read_sized::<LittleEndian, _>(&mut reader, data.len() as u64).unwrap();
+        assert_eq!(text.history, TextHistory::Unknown { history_type: 42, data: vec![1, 2, 3] });
+
+        let mut reader = Reader::new(&data[..]);
+        reader.set_version(version());
+        assert!(FText::read_from::<LittleEndian, _>(&mut reader).is_err());
+    }
+
+    #[test]
+    fn read_value_histories() {
+        let mut data = Vec::new();
+        // None with a culture invariant string.
+        data.write_u32_le(0).unwrap();
+        data.write_num::<LittleEndian, i8>(-1).unwrap();
+        data.write_u32_le(1).unwrap();
+        data.write_fstring::<LittleEndian>("Raw").unwrap();
+        // AsNumber of the double 2.5, with options, for "en".
+        data.write_u32_le(0).unwrap();
+        data.write_num::<LittleEndian, i8>(4).unwrap();
+        data.write_num::<LittleEndian, i8>(3).unwrap();
+        data.write_f64_le(2.5).unwrap();
+        for value in [1, 1, 0] {
+            data.write_u32_le(value).unwrap();
+        }
+        data.write_num::<LittleEndian, i8>(0).unwrap();
+        for value in [1, 10, 0, 2] {
+            data.write_i32_le(value).unwrap();
+        }
+        data.write_fstring::<LittleEndian>("en").unwrap();
+        // Transform to upper case of a Base text.
+        data.write_u32_le(0).unwrap();
+        data.write_num::<LittleEndian, i8>(10).unwrap();
+        data.write_u32_le(0).unwrap();
+        data.write_num::<LittleEndian, i8>(0).unwrap();
+        for string in ["Game", "Title", "title"] {
+            data.write_fstring::<LittleEndian>(string).unwrap();
+        }
+        data.write_num::<LittleEndian, u8>(1).unwrap();
+        // A string table entry, whose table id is an FName.
+        data.write_u32_le(0).unwrap();
+        data.write_num::<LittleEndian, i8>(11).unwrap();
+        data.write_i32_le(1).unwrap();
+        data.write_i32_le(0).unwrap();
+        data.write_fstring::<LittleEndian>("Greeting").unwrap();
+
+        let mut reader = Reader::new(&data[..]);
+        reader.set_version(version());
+        reader.set_names(NameTable::from(vec![String::from("None"), String::from("/Game/Strings")]));
+        let mut read = || FText::read_from::<LittleEndian, _>(&mut reader).unwrap().history;
+
+        assert_eq!(read(), TextHistory::None { culture_invariant_string: Some(String::from("Raw")) });
+        let TextHistory::AsNumber { source_value, format_options, target_culture } = read() else { panic!() };
+        assert_eq!((source_value, target_culture.as_str()), (FormatArgumentValue::Double(2.5), "en"));
+        assert_eq!(format_options, Some(NumberFormattingOptions {
+            always_sign: true,
+            use_grouping: false,
+            rounding_mode: 0,
+            minimum_integral_digits: 1,
+            maximum_integral_digits: 10,
+            minimum_fractional_digits: 0,
+            maximum_fractional_digits: 2,
+        }));
+        let TextHistory::Transform { source_text, transform_type: 1 } = read() else { panic!() };
+        assert_eq!(source_text.source_string(), Some("title"));
+        let TextHistory::StringTableEntry { table_id, key } = read() else { panic!() };
+        assert_eq!((table_id.name(), key.as_str()), ("/Game/Strings", "Greeting"));
+        assert!(reader.get_ref().is_empty());
+    }
+
+    #[test]
+    fn read_legacy_texts() {
+        // Before histories: the source string, the namespace and key once saved, then the flags.
+        for (ue4, fields) in [(350, ["Hello", "Game", "Greeting"]), (300, ["Hello", "", ""])] {
+            let mut data = Vec::new();
+            for string in fields.iter().take(if ue4 >= ObjectVersion::ADDED_NAMESPACE_AND_KEY_DATA_TO_FTEXT { 3 } else { 1 }) {
+                data.write_fstring::<LittleEndian>(string).unwrap();
+            }
+            data.write_u32_le(2).unwrap();
+
+            let mut reader = Reader::new(&data[..]);
+            reader.set_version(ArchiveVersion::new(PackageFileVersion::new(ue4, 0), 0));
+            let text = FText::read_from::<LittleEndian, _>(&mut reader).unwrap();
+            assert!(reader.get_ref().is_empty());
+            assert_eq!(text.flags, 2);
+            let TextHistory::Base { namespace, key, source_string } = text.history else { panic!("{:?}", text) };
+            assert_eq!([source_string, namespace, key], fields);
+        }
+    }
+
+}
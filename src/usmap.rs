@@ -0,0 +1,441 @@
+//! `.usmap` mappings: the property schemas of a game's classes and structs, needed to
+//! read the unversioned properties of cooked packages.
+
+use byteorder::LittleEndian;
+
+use alloc::collections::BTreeMap;
+use alloc::string::ToString;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::str;
+
+use crate::compression::{self, CompressionMethod};
+use crate::limits;
+use crate::property::PropertyType;
+use crate::types::FName;
+use crate::{ArchiveVersion, CustomVersion, PackageFileVersion, ReadError, ReadExt, ReadFrom, ReaderResult, SliceReader, StringEncoding};
+
+/// How deep property types may nest in a mappings file.
+const MAX_TYPE_DEPTH: u32 = 32;
+
+/// The type names of the usmap property type ids, in id order.
+const PROPERTY_TYPES: [&str; 31] = [
+    "ByteProperty", "BoolProperty", "IntProperty", "FloatProperty", "ObjectProperty", "NameProperty",
+    "DelegateProperty", "DoubleProperty", "ArrayProperty", "StructProperty", "StrProperty", "TextProperty",
+    "InterfaceProperty", "MulticastDelegateProperty", "WeakObjectProperty", "LazyObjectProperty",
+    "AssetObjectProperty", "SoftObjectProperty", "UInt64Property", "UInt32Property", "UInt16Property",
+    "Int64Property", "Int16Property", "Int8Property", "MapProperty", "SetProperty", "EnumProperty",
+    "FieldPathProperty", "OptionalProperty", "Utf8StrProperty", "AnsiStrProperty",
+];
+
+/// An enum and its values.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumSchema {
+    pub name: FName,
+    pub values: Vec<(i64, FName)>,
+}
+
+impl EnumSchema {
+
+    /// Returns the name of the entry with `value`.
+    pub fn value_name(&self, value: i64) -> Option<&FName> {
+        self.values.iter().find(|(v, _)| *v == value).map(|(_, name)| name)
+    }
+
+}
+
+/// A class or struct and the properties it declares, without those of its super struct.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructSchema {
+    pub name: FName,
+    pub super_struct: Option<FName>,
+    /// The number of property slots the struct declares, counting every element of
+    /// static arrays.
+    pub property_count: u16,
+    pub properties: Vec<PropertySchema>,
+}
+
+/// A property of a [`StructSchema`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PropertySchema {
+    pub name: FName,
+    /// The slot of the property within its struct.
+    pub schema_index: u16,
+    /// The number of elements of a static array, 1 for other properties.
+    pub array_dim: u8,
+    pub property_type: PropertyType,
+}
+
+/// The contents of a `.usmap` file.
+///
+/// Enum properties are typed `EnumProperty(EnumName,UnderlyingType)`, as UE 5.4 names them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Mappings {
+    /// The versions of the game the mappings were dumped from, if the file records them.
+    pub archive_version: Option<ArchiveVersion>,
+    pub enums: BTreeMap<Arc<str>, EnumSchema>,
+    pub structs: BTreeMap<Arc<str>, StructSchema>,
+}
+
+impl Mappings {
+
+    pub const MAGIC: u16 = 0x30C4;
+
+    const VERSION_PACKAGE_VERSIONING: u8 = 1;
+    const VERSION_LONG_FNAME: u8 = 2;
+    const VERSION_LARGE_ENUMS: u8 = 3;
+    const VERSION_EXPLICIT_ENUM_VALUES: u8 = 4;
+    const VERSION_LATEST: u8 = 4;
+
+    /// Reads a mappings file, decompressing its payload if it was compressed with a
+    /// method enabled by the crate's features.
+    pub fn read<R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        let offset = reader.offset();
+        let magic = reader.read_u16_le()?;
+        if magic != Self::MAGIC {
+            return Err(ReadError::InvalidMagic { found: magic.into(), offset });
+        }
+
+        let offset = reader.offset();
+        let version = reader.read_num::<LittleEndian, u8>()?;
+        if version > Self::VERSION_LATEST {
+            return Err(ReadError::UnsupportedVersion { version: version.into(), offset });
+        }
+
+        let mut archive_version = None;
+        if version >= Self::VERSION_PACKAGE_VERSIONING && reader.read_bool::<LittleEndian>()? {
+            let package = PackageFileVersion::new(reader.read_i32_le()?, reader.read_i32_le()?);
+            let mut archive = ArchiveVersion::new(package, 0);
+            archive.custom_versions = reader.read_array::<LittleEndian, _>(|r| CustomVersion::read_from::<LittleEndian, _>(r))?;
+            let _net_cl = reader.read_u32_le()?;
+            archive_version = Some(archive);
+        }
+
+        let offset = reader.offset();
+        let method = match reader.read_num::<LittleEndian, u8>()? {
+            0 => CompressionMethod::None,
+            1 => CompressionMethod::Oodle,
+            2 => CompressionMethod::Brotli,
+            3 => CompressionMethod::Zstd,
+            other => return Err(ReadError::UnsupportedCompression { method: other.to_string(), offset }),
+        };
+        let compressed_size = reader.read_u32_le()?;
+        let offset = reader.offset();
+        let uncompressed_size = reader.read_u32_le()?;
+        limits::check_buffer_size(uncompressed_size.into(), reader.limits(), offset)?;
+
+        let compressed = limits::read_buffer(reader, compressed_size.into())?;
+        let offset = reader.offset();
+        reader.allocate(uncompressed_size.into())?;
+        let payload = compression::decompress(method, &compressed, uncompressed_size as usize)
+            .map_err(|e| e.located(offset))?;
+
+        let mut payload_reader = SliceReader::new(&payload);
+        let mut mappings = Self::read_payload(&mut payload_reader, version)?;
+        mappings.archive_version = archive_version;
+        Ok(mappings)
+    }
+
+    /// Returns the struct or class named `name`.
+    #[inline]
+    pub fn get_struct(&self, name: &str) -> Option<&StructSchema> {
+        self.structs.get(name)
+    }
+
+    /// Returns the enum named `name`.
+    #[inline]
+    pub fn get_enum(&self, name: &str) -> Option<&EnumSchema> {
+        self.enums.get(name)
+    }
+
+    fn read_payload(reader: &mut SliceReader<'_>, version: u8) -> ReaderResult<Self> {
+        let names = reader.with_context("Names", |reader| {
+            let count = read_count(reader)?;
+            let mut names = Vec::with_capacity(limits::preallocation::<FName>(count, reader.remaining()));
+            for index in 0..count as usize {
+                names.push(read_name_entry(reader, version).map_err(|e| e.index(index))?);
+            }
+            Ok(names)
+        })?;
+
+        let mut mappings = Mappings::default();
+        reader.with_context("Enums", |reader| {
+            let count = read_count(reader)?;
+            for index in 0..count as usize {
+                let schema = read_enum(reader, &names, version).map_err(|e| e.index(index))?;
+                mappings.enums.insert(schema.name.name().into(), schema);
+            }
+            Ok(())
+        })?;
+        reader.with_context("Structs", |reader| {
+            let count = read_count(reader)?;
+            for index in 0..count as usize {
+                let schema = read_struct(reader, &names).map_err(|e| e.index(index))?;
+                mappings.structs.insert(schema.name.name().into(), schema);
+            }
+            Ok(())
+        })?;
+
+        Ok(mappings)
+    }
+
+}
+
+/// Reads a u32 element count, checking it against the reader's limits.
+fn read_count(reader: &mut SliceReader<'_>) -> ReaderResult<u64> {
+    let offset = reader.offset();
+    let count = reader.read_u32_le()?;
+    let limit = reader.limits().max_array_len;
+    if u64::from(count) > limit {
+        return Err(ReadError::AllocationLimit { requested: count.into(), limit, offset });
+    }
+    Ok(count.into())
+}
+
+fn read_name_entry(reader: &mut SliceReader<'_>, version: u8) -> ReaderResult<FName> {
+    let length = if version >= Mappings::VERSION_LONG_FNAME {
+        reader.read_u16_le()? as usize
+    } else {
+        reader.read_num::<LittleEndian, u8>()? as usize
+    };
+
+    let offset = reader.offset();
+    let bytes = reader.read_bytes(length)?;
+    let name = str::from_utf8(bytes).map_err(|_| ReadError::InvalidString { encoding: StringEncoding::Ansi, offset })?;
+    Ok(FName::new(name, 0))
+}
+
+/// Reads a reference into the name list, where `u32::MAX` stands for `None`.
+fn read_name(reader: &mut SliceReader<'_>, names: &[FName]) -> ReaderResult<Option<FName>> {
+    let offset = reader.offset();
+    let index = reader.read_u32_le()?;
+    if index == u32::MAX {
+        return Ok(None);
+    }
+
+    match names.get(index as usize) {
+        Some(name) => Ok(Some(name.clone())),
+        None => Err(ReadError::InvalidNameIndex { index: index as i32, len: names.len(), offset }),
+    }
+}
+
+fn read_required_name(reader: &mut SliceReader<'_>, names: &[FName]) -> ReaderResult<FName> {
+    Ok(read_name(reader, names)?.unwrap_or_else(|| FName::new("None", 0)))
+}
+
+fn read_enum(reader: &mut SliceReader<'_>, names: &[FName], version: u8) -> ReaderResult<EnumSchema> {
+    let name = read_required_name(reader, names)?;
+    let count = if version >= Mappings::VERSION_LARGE_ENUMS {
+        reader.read_u16_le()? as usize
+    } else {
+        reader.read_num::<LittleEndian, u8>()? as usize
+    };
+
+    let mut values = Vec::with_capacity(count);
+    for index in 0..count {
+        let value = if version >= Mappings::VERSION_EXPLICIT_ENUM_VALUES {
+            reader.read_i64_le()?
+        } else {
+            index as i64
+        };
+        values.push((value, read_required_name(reader, names)?));
+    }
+
+    Ok(EnumSchema { name, values })
+}
+
+fn read_struct(reader: &mut SliceReader<'_>, names: &[FName]) -> ReaderResult<StructSchema> {
+    let name = read_required_name(reader, names)?;
+    let super_struct = read_name(reader, names)?;
+    let property_count = reader.read_u16_le()?;
+    let serializable_count = reader.read_u16_le()?;
+
+    let mut properties = Vec::with_capacity(serializable_count.into());
+    for index in 0..serializable_count as usize {
+        let property = reader.with_context("Properties", |reader| {
+            Ok(PropertySchema {
+                schema_index: reader.read_u16_le()?,
+                array_dim: reader.read_num::<LittleEndian, u8>()?,
+                name: read_required_name(reader, names)?,
+                property_type: read_type(reader, names, 0)?,
+            })
+        });
+        properties.push(property.map_err(|e| e.index(index))?);
+    }
+
+    Ok(StructSchema { name, super_struct, property_count, properties })
+}
+
+fn read_type(reader: &mut SliceReader<'_>, names: &[FName], depth: u32) -> ReaderResult<PropertyType> {
+    let offset = reader.offset();
+    if depth > MAX_TYPE_DEPTH {
+        return Err(ReadError::NestingLimit { limit: MAX_TYPE_DEPTH, offset });
+    }
+
+    let id = reader.read_num::<LittleEndian, u8>()?;
+    let Some(&type_name) = PROPERTY_TYPES.get(id as usize) else {
+        return Err(ReadError::UnsupportedType { name: alloc::format!("usmap property type {}", id), offset });
+    };
+
+    let mut property_type = PropertyType::new(FName::new(type_name, 0));
+    match type_name {
+        "EnumProperty" => {
+            let underlying = read_type(reader, names, depth + 1)?;
+            let enum_name = read_required_name(reader, names)?;
+            property_type.parameters = alloc::vec![PropertyType::new(enum_name), underlying];
+        }
+        "StructProperty" => {
+            property_type.parameters.push(PropertyType::new(read_required_name(reader, names)?));
+        }
+        "ArrayProperty" | "SetProperty" | "OptionalProperty" => {
+            property_type.parameters.push(read_type(reader, names, depth + 1)?);
+        }
+        "MapProperty" => {
+            property_type.parameters.push(read_type(reader, names, depth + 1)?);
+            property_type.parameters.push(read_type(reader, names, depth + 1)?);
+        }
+        _ => {}
+    }
+
+    Ok(property_type)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use byteorder::LittleEndian;
+
+    use crate::types::FGuid;
+    use crate::usmap::Mappings;
+    use crate::{PackageFileVersion, ReadError, Reader, WriteExt};
+
+    /// Writes a mappings file of `version` whose header ends with `versioning`, around a
+    /// payload stored with the compression method `method`.
+    fn write_file(version: u8, versioning: &[u8], method: u8, stored: &[u8], payload_size: usize) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.write_u16_le(Mappings::MAGIC).unwrap();
+        data.write_num::<LittleEndian, u8>(version).unwrap();
+        data.extend_from_slice(versioning);
+        data.write_num::<LittleEndian, u8>(method).unwrap();
+        data.write_u32_le(stored.len() as u32).unwrap();
+        data.write_u32_le(payload_size as u32).unwrap();
+        data.extend_from_slice(stored);
+        data
+    }
+
+    /// Writes an uncompressed version 4 mappings file without package versioning.
+    fn write_mappings(payload: &[u8]) -> Vec<u8> {
+        write_file(4, &[0, 0, 0, 0], 0, payload, payload.len())
+    }
+
+    /// Writes a version 4 payload with the enum `EMode` and the struct `Actor`.
+    fn write_payload() -> Vec<u8> {
+        let mut payload = Vec::new();
+        payload.write_u32_le(6).unwrap();
+        for name in ["EMode", "A", "Actor", "Mode", "Scores", "NameProperty"] {
+            payload.write_u16_le(name.len() as u16).unwrap();
+            payload.extend_from_slice(name.as_bytes());
+        }
+
+        // EMode { A = 4 }
+        payload.write_u32_le(1).unwrap();
+        payload.write_u32_le(0).unwrap();
+        payload.write_u16_le(1).unwrap();
+        payload.write_i64_le(4).unwrap();
+        payload.write_u32_le(1).unwrap();
+
+        // Actor { EnumProperty<EMode, ByteProperty> Mode; TMap<NameProperty, IntProperty> Scores }
+        payload.write_u32_le(1).unwrap();
+        payload.write_u32_le(2).unwrap();
+        payload.write_u32_le(u32::MAX).unwrap();
+        payload.write_u16_le(2).unwrap();
+        payload.write_u16_le(2).unwrap();
+        payload.write_u16_le(0).unwrap();
+        payload.write_num::<LittleEndian, u8>(1).unwrap();
+        payload.write_u32_le(3).unwrap();
+        payload.extend_from_slice(&[26, 0]);
+        payload.write_u32_le(0).unwrap();
+        payload.write_u16_le(1).unwrap();
+        payload.write_num::<LittleEndian, u8>(1).unwrap();
+        payload.write_u32_le(4).unwrap();
+        payload.extend_from_slice(&[24, 5, 2]);
+        payload
+    }
+
+    fn assert_actor_mappings(mappings: &Mappings) {
+        assert_eq!(mappings.get_enum("EMode").unwrap().value_name(4).unwrap().name(), "A");
+
+        let actor = mappings.get_struct("Actor").unwrap();
+        assert_eq!((actor.super_struct.as_ref(), actor.property_count), (None, 2));
+        let types: Vec<_> = actor.properties.iter().map(|p| alloc::format!("{} {}", p.property_type, p.name)).collect();
+        assert_eq!(types, ["EnumProperty(EMode,ByteProperty) Mode", "MapProperty(NameProperty,IntProperty) Scores"]);
+    }
+
+    #[test]
+    fn read_uncompressed_mappings() {
+        let payload = write_payload();
+        let data = write_mappings(&payload);
+        let mappings = Mappings::read(&mut Reader::new(&data[..])).unwrap();
+        assert_eq!(mappings.archive_version, None);
+        assert_actor_mappings(&mappings);
+
+        let mut corrupted = write_mappings(&payload);
+        corrupted[0] = 0;
+        assert!(matches!(Mappings::read(&mut Reader::new(&corrupted[..])), Err(ReadError::InvalidMagic { .. })));
+    }
+
+    #[test]
+    fn reject_huge_uncompressed_size() {
+        let data = write_file(4, &[0, 0, 0, 0], 3, &[0; 4], u32::MAX as usize);
+        let result = Mappings::read(&mut Reader::new(&data[..]));
+        assert!(matches!(result, Err(ReadError::AllocationLimit { requested, offset: Some(12), .. }) if requested == u32::MAX as u64));
+    }
+
+    #[test]
+    fn read_versioned_short_name_mappings() {
+        // Package versioning: the file versions and one custom version, then the changelist.
+        let mut versioning = Vec::new();
+        versioning.write_u32_le(1).unwrap();
+        for value in [522, 1009, 1] {
+            versioning.write_i32_le(value).unwrap();
+        }
+        for value in [1, 2, 3, 4, 7] {
+            versioning.write_u32_le(value).unwrap();
+        }
+        versioning.write_u32_le(12345).unwrap();
+
+        // A version 1 payload: single byte name lengths and enum counts, and enum
+        // values numbered by their position.
+        let mut payload = Vec::new();
+        payload.write_u32_le(3).unwrap();
+        for name in ["EColor", "Red", "Green"] {
+            payload.write_num::<LittleEndian, u8>(name.len() as u8).unwrap();
+            payload.extend_from_slice(name.as_bytes());
+        }
+        payload.write_u32_le(1).unwrap();
+        payload.write_u32_le(0).unwrap();
+        payload.write_num::<LittleEndian, u8>(2).unwrap();
+        payload.write_u32_le(1).unwrap();
+        payload.write_u32_le(2).unwrap();
+        payload.write_u32_le(0).unwrap();
+
+        let data = write_file(1, &versioning, 0, &payload, payload.len());
+        let mappings = Mappings::read(&mut Reader::new(&data[..])).unwrap();
+        let version = mappings.archive_version.as_ref().unwrap();
+        assert_eq!(version.package, PackageFileVersion::new(522, 1009));
+        assert_eq!(version.custom_version(&FGuid::new(1, 2, 3, 4)), Some(7));
+
+        let color = mappings.get_enum("EColor").unwrap();
+        assert_eq!((color.value_name(0).unwrap().name(), color.value_name(1).unwrap().name()), ("Red", "Green"));
+        assert!(mappings.structs.is_empty());
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn read_zstd_mappings() {
+        let payload = write_payload();
+        let compressed = ruzstd::encoding::compress_to_vec(&payload[..], ruzstd::encoding::CompressionLevel::Fastest);
+        let data = write_file(4, &[0, 0, 0, 0], 3, &compressed, payload.len());
+        assert_actor_mappings(&Mappings::read(&mut Reader::new(&data[..])).unwrap());
+    }
+
+}
@@ -0,0 +1,178 @@
+use byteorder::ByteOrder;
+
+use alloc::vec::Vec;
+
+use crate::types::FGuid;
+use crate::{ReadExt, ReadFrom, ReaderResult};
+#[cfg(feature = "std")]
+use crate::{WriteExt, WriteTo, WriterResult};
+
+/// The engine object version of a package, `FPackageFileVersion` in Unreal.
+///
+/// Versions order by their UE4 number first; every UE5 package also carries the final
+/// UE4 version, so the ordering holds across engine generations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PackageFileVersion {
+    pub ue4: i32,
+    pub ue5: i32,
+}
+
+impl PackageFileVersion {
+
+    pub const fn new(ue4: i32, ue5: i32) -> Self {
+        Self { ue4, ue5 }
+    }
+
+}
+
+/// UE4 object versions that change how data is laid out, from `EUnrealEngineObjectUE4Version`.
+#[derive(Debug)]
+pub struct ObjectVersion;
+
+impl ObjectVersion {
+
+    pub const OLDEST_LOADABLE_PACKAGE: i32 = 214;
+    pub const WORLD_LEVEL_INFO: i32 = 224;
+    pub const ADDED_CHUNKID_TO_ASSETDATA_AND_UPACKAGE: i32 = 278;
+    pub const ARRAY_PROPERTY_INNER_TAGS: i32 = 282;
+    pub const CHANGED_CHUNKID_TO_BE_AN_ARRAY_OF_CHUNKIDS: i32 = 326;
+    pub const ADDED_NAMESPACE_AND_KEY_DATA_TO_FTEXT: i32 = 334;
+    pub const ENGINE_VERSION_OBJECT: i32 = 336;
+    pub const LOAD_FOR_EDITOR_GAME: i32 = 365;
+    pub const FTEXT_HISTORY: i32 = 368;
+    pub const ADD_STRING_ASSET_REFERENCES_MAP: i32 = 384;
+    pub const ADDED_CURRENCY_CODE_TO_FTEXT: i32 = 415;
+    pub const FTEXT_HISTORY_DATE_TIMEZONE: i32 = 421;
+    pub const STRUCT_GUID_IN_PROPERTY_TAG: i32 = 441;
+    pub const PACKAGE_SUMMARY_HAS_COMPATIBLE_ENGINE_VERSION: i32 = 444;
+    pub const SERIALIZE_TEXT_IN_PACKAGES: i32 = 459;
+    pub const COOKED_ASSETS_IN_EDITOR_SUPPORT: i32 = 485;
+    pub const PROPERTY_TAG_SET_MAP_SUPPORT: i32 = 500;
+    pub const PROPERTY_GUID_IN_PROPERTY_TAG: i32 = 503;
+    pub const PRELOAD_DEPENDENCIES_IN_COOKED_EXPORTS: i32 = 507;
+    pub const TEMPLATE_INDEX_IN_COOKED_EXPORTS: i32 = 508;
+    pub const ADDED_SEARCHABLE_NAMES: i32 = 510;
+    pub const SIXTY_FOUR_BIT_EXPORTMAP_SERIALSIZES: i32 = 511;
+    pub const ADDED_PACKAGE_SUMMARY_LOCALIZATION_ID: i32 = 516;
+    pub const ADDED_PACKAGE_OWNER: i32 = 518;
+    pub const NON_OUTER_PACKAGE_IMPORT: i32 = 520;
+    pub const ASSETREGISTRY_DEPENDENCYFLAGS: i32 = 521;
+    pub const CORRECT_LICENSEE_FLAG: i32 = 522;
+    /// The newest UE4 version, which every UE5 package also carries.
+    pub const AUTOMATIC_VERSION: i32 = 522;
+
+}
+
+/// UE5 object versions that change how data is laid out, from `EUnrealEngineObjectUE5Version`.
+#[derive(Debug)]
+pub struct ObjectVersionUE5;
+
+impl ObjectVersionUE5 {
+
+    pub const INITIAL_VERSION: i32 = 1000;
+    pub const NAMES_REFERENCED_FROM_EXPORT_DATA: i32 = 1001;
+    pub const PAYLOAD_TOC: i32 = 1002;
+    pub const OPTIONAL_RESOURCES: i32 = 1003;
+    /// Vectors, rotators, quaternions and transforms are serialized with doubles.
+    pub const LARGE_WORLD_COORDINATES: i32 = 1004;
+    pub const REMOVE_OBJECT_EXPORT_PACKAGE_GUID: i32 = 1005;
+    pub const TRACK_OBJECT_EXPORT_IS_INHERITED: i32 = 1006;
+    pub const FSOFTOBJECTPATH_REMOVE_ASSET_PATH_FNAMES: i32 = 1007;
+    pub const ADD_SOFTOBJECTPATH_LIST: i32 = 1008;
+    pub const DATA_RESOURCES: i32 = 1009;
+    pub const SCRIPT_SERIALIZATION_OFFSET: i32 = 1010;
+    pub const PROPERTY_TAG_EXTENSION_AND_OVERRIDABLE_SERIALIZATION: i32 = 1011;
+    pub const PROPERTY_TAG_COMPLETE_TYPE_NAME: i32 = 1012;
+    pub const ASSETREGISTRY_PACKAGEBUILDDEPENDENCIES: i32 = 1013;
+    pub const METADATA_SERIALIZATION_OFFSET: i32 = 1014;
+    pub const VERSE_CELLS: i32 = 1015;
+    pub const PACKAGE_SAVED_HASH: i32 = 1016;
+
+}
+
+/// Editor object versions, from the `FEditorObjectVersion` custom version.
+#[derive(Debug)]
+pub struct EditorObjectVersion;
+
+impl EditorObjectVersion {
+
+    pub const GUID: FGuid = FGuid::new(0xE4B068ED, 0xF49442E9, 0xA231DA0B, 0x2E46BB41);
+
+    pub const TEXT_FORMAT_ARGUMENT_DATA_IS_VARIANT: i32 = 5;
+    pub const ADDED_ALWAYS_SIGN_NUMBER_FORMATTING_OPTION: i32 = 21;
+    pub const CULTURE_INVARIANT_TEXT_SERIALIZATION_KEY_STABILITY: i32 = 32;
+
+}
+
+/// The version of a single engine or plugin subsystem, keyed by a GUID.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CustomVersion {
+    pub key: FGuid,
+    pub version: i32,
+}
+
+impl ReadFrom for CustomVersion {
+    fn read_from<B: ByteOrder, R: ReadExt + ?Sized>(reader: &mut R) -> ReaderResult<Self> {
+        Ok(Self {
+            key: FGuid::read_from::<B, _>(reader)?,
+            version: reader.read_num::<B, i32>()?,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl WriteTo for CustomVersion {
+    fn write_to<B: ByteOrder, W: WriteExt + ?Sized>(&self, writer: &mut W) -> WriterResult<()> {
+        self.key.write_to::<B, _>(writer)?;
+        writer.write_num::<B, i32>(self.version)
+    }
+}
+
+/// The version state that decides how version-dependent data is laid out, like the
+/// versions an `FArchive` carries in Unreal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArchiveVersion {
+    pub package: PackageFileVersion,
+    pub licensee: i32,
+    pub custom_versions: Vec<CustomVersion>,
+}
+
+impl ArchiveVersion {
+
+    /// The version reported by readers without version state: everything zero.
+    pub const UNVERSIONED: ArchiveVersion = ArchiveVersion::new(PackageFileVersion::new(0, 0), 0);
+
+    pub const fn new(package: PackageFileVersion, licensee: i32) -> Self {
+        Self {
+            package,
+            licensee,
+            custom_versions: Vec::new(),
+        }
+    }
+
+    /// Returns true if math types are serialized with doubles, as in UE5 packages saved
+    /// with Large World Coordinates.
+    #[inline]
+    pub fn large_world_coordinates(&self) -> bool {
+        self.package.ue5 >= ObjectVersionUE5::LARGE_WORLD_COORDINATES
+    }
+
+    /// Returns the version registered for `key`, if any.
+    pub fn custom_version(&self, key: &FGuid) -> Option<i32> {
+        self.custom_versions
+            .iter()
+            .find(|custom| custom.key == *key)
+            .map(|custom| custom.version)
+    }
+
+    /// Registers `version` for `key`, replacing any previous version.
+    pub fn set_custom_version(&mut self, key: FGuid, version: i32) {
+        match self.custom_versions.iter_mut().find(|custom| custom.key == key) {
+            Some(custom) => custom.version = version,
+            None => self.custom_versions.push(CustomVersion { key, version }),
+        }
+    }
+
+}
+
+pub(crate) static UNVERSIONED: ArchiveVersion = ArchiveVersion::UNVERSIONED;
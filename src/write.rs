@@ -0,0 +1,307 @@
+use byteorder::{ByteOrder, BigEndian, LittleEndian};
+
+use std::io;
+
+use crate::version;
+use crate::{ArchiveVersion, Primitive, StringEncoding};
+
+pub type WriterResult<T> = io::Result<T>;
+
+pub trait WriteExt {
+
+    /// Writes all of `bytes` to the stream.
+    fn write_all_bytes(&mut self, bytes: &[u8]) -> WriterResult<()>;
+
+    /// Returns the version state that decides how version-dependent data is laid out.
+    /// Plain writers report [`ArchiveVersion::UNVERSIONED`]; see [`Writer::set_version`].
+    #[inline]
+    fn version(&self) -> &ArchiveVersion {
+        &version::UNVERSIONED
+    }
+
+    /// Writes a primitive in the byte order `B`.
+    #[inline(always)]
+    fn write_num<B: ByteOrder, T: Primitive>(&mut self, value: T) -> WriterResult<()> {
+        let mut buffer = [0u8; 16];
+        let bytes = &mut buffer[..T::SIZE];
+        value.to_bytes::<B>(bytes);
+        self.write_all_bytes(bytes)
+    }
+
+    /// Writes `items` prefixed by their count as an i32 in the byte order `B`.
+    #[inline]
+    fn write_array<B: ByteOrder, T>(&mut self, items: &[T], serialize: impl FnMut(&mut Self, &T) -> WriterResult<()>) -> WriterResult<()> {
+        self.write_num::<B, i32>(length_prefix(items.len())?)?;
+        self.write_array_with_length(items, serialize)
+    }
+
+    #[deprecated(note = "use `write_array::<BigEndian, _>` instead")]
+    #[inline]
+    fn write_array_be<T, F>(&mut self, items: &[T], serialize: F) -> WriterResult<()>
+    where
+        F: FnMut(&mut Self, &T) -> WriterResult<()>
+    {
+        self.write_array::<BigEndian, T>(items, serialize)
+    }
+
+    /// Writes `items` without a length prefix.
+    fn write_array_with_length<T, F>(&mut self, items: &[T], mut serialize: F) -> WriterResult<()>
+    where
+        F: FnMut(&mut Self, &T) -> WriterResult<()>
+    {
+        for item in items {
+            serialize(self, item)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes an FString in the byte order `B`, using ANSI when `value` is pure ASCII
+    /// and UTF-16 otherwise, as Unreal does.
+    #[inline]
+    fn write_fstring<B: ByteOrder>(&mut self, value: &str) -> WriterResult<()> {
+        let encoding = if value.is_ascii() { StringEncoding::Ansi } else { StringEncoding::Utf16 };
+        self.write_fstring_with::<B>(value, encoding)
+    }
+
+    /// Writes an FString in the given encoding. ANSI strings are written as their
+    /// UTF-8 bytes, matching what [`ReadExt::read_fstring`](crate::ReadExt::read_fstring) accepts.
+    fn write_fstring_with<B: ByteOrder>(&mut self, value: &str, encoding: StringEncoding) -> WriterResult<()> {
+        if value.is_empty() {
+            return self.write_num::<B, i32>(0);
+        }
+
+        match encoding {
+            StringEncoding::Ansi => {
+                self.write_num::<B, i32>(length_prefix(value.len() + 1)?)?;
+                self.write_all_bytes(value.as_bytes())?;
+                self.write_num::<B, u8>(0)
+            }
+            StringEncoding::Utf16 => {
+                let units: Vec<u16> = value.encode_utf16().chain(Some(0)).collect();
+                self.write_num::<B, i32>(-length_prefix(units.len())?)?;
+                let mut buffer = vec![0u8; units.len() * 2];
+                B::write_u16_into(&units, &mut buffer);
+                self.write_all_bytes(&buffer)
+            }
+        }
+    }
+
+    /// Writes an Unreal `bool` as a 4 byte integer in the byte order `B`.
+    #[inline(always)]
+    fn write_bool<B: ByteOrder>(&mut self, value: bool) -> WriterResult<()> {
+        self.write_num::<B, u32>(value.into())
+    }
+
+    #[inline(always)]
+    fn write_i16_le(&mut self, value: i16) -> WriterResult<()> {
+        self.write_num::<LittleEndian, i16>(value)
+    }
+
+    #[inline(always)]
+    fn write_u16_le(&mut self, value: u16) -> WriterResult<()> {
+        self.write_num::<LittleEndian, u16>(value)
+    }
+
+    #[inline(always)]
+    fn write_i32_le(&mut self, value: i32) -> WriterResult<()> {
+        self.write_num::<LittleEndian, i32>(value)
+    }
+
+    #[inline(always)]
+    fn write_u32_le(&mut self, value: u32) -> WriterResult<()> {
+        self.write_num::<LittleEndian, u32>(value)
+    }
+
+    #[inline(always)]
+    fn write_i64_le(&mut self, value: i64) -> WriterResult<()> {
+        self.write_num::<LittleEndian, i64>(value)
+    }
+
+    #[inline(always)]
+    fn write_u64_le(&mut self, value: u64) -> WriterResult<()> {
+        self.write_num::<LittleEndian, u64>(value)
+    }
+
+    #[inline(always)]
+    fn write_i128_le(&mut self, value: i128) -> WriterResult<()> {
+        self.write_num::<LittleEndian, i128>(value)
+    }
+
+    #[inline(always)]
+    fn write_u128_le(&mut self, value: u128) -> WriterResult<()> {
+        self.write_num::<LittleEndian, u128>(value)
+    }
+
+    #[inline(always)]
+    fn write_f32_le(&mut self, value: f32) -> WriterResult<()> {
+        self.write_num::<LittleEndian, f32>(value)
+    }
+
+    #[inline(always)]
+    fn write_f64_le(&mut self, value: f64) -> WriterResult<()> {
+        self.write_num::<LittleEndian, f64>(value)
+    }
+
+    #[inline(always)]
+    fn write_i16_be(&mut self, value: i16) -> WriterResult<()> {
+        self.write_num::<BigEndian, i16>(value)
+    }
+
+    #[inline(always)]
+    fn write_u16_be(&mut self, value: u16) -> WriterResult<()> {
+        self.write_num::<BigEndian, u16>(value)
+    }
+
+    #[inline(always)]
+    fn write_i32_be(&mut self, value: i32) -> WriterResult<()> {
+        self.write_num::<BigEndian, i32>(value)
+    }
+
+    #[inline(always)]
+    fn write_u32_be(&mut self, value: u32) -> WriterResult<()> {
+        self.write_num::<BigEndian, u32>(value)
+    }
+
+    #[inline(always)]
+    fn write_i64_be(&mut self, value: i64) -> WriterResult<()> {
+        self.write_num::<BigEndian, i64>(value)
+    }
+
+    #[inline(always)]
+    fn write_u64_be(&mut self, value: u64) -> WriterResult<()> {
+        self.write_num::<BigEndian, u64>(value)
+    }
+
+    #[inline(always)]
+    fn write_i128_be(&mut self, value: i128) -> WriterResult<()> {
+        self.write_num::<BigEndian, i128>(value)
+    }
+
+    #[inline(always)]
+    fn write_u128_be(&mut self, value: u128) -> WriterResult<()> {
+        self.write_num::<BigEndian, u128>(value)
+    }
+
+    #[inline(always)]
+    fn write_f32_be(&mut self, value: f32) -> WriterResult<()> {
+        self.write_num::<BigEndian, f32>(value)
+    }
+
+    #[inline(always)]
+    fn write_f64_be(&mut self, value: f64) -> WriterResult<()> {
+        self.write_num::<BigEndian, f64>(value)
+    }
+
+}
+
+impl<Impl> WriteExt for Impl
+where
+    Impl: io::Write
+{
+
+    #[inline(always)]
+    fn write_all_bytes(&mut self, bytes: &[u8]) -> WriterResult<()> {
+        self.write_all(bytes)
+    }
+
+}
+
+/// A writer that carries the version state of the data being written, the
+/// counterpart of [`Reader`](crate::Reader) for version-dependent layouts.
+#[derive(Debug)]
+pub struct Writer<W> {
+    inner: W,
+    version: ArchiveVersion,
+}
+
+impl<W> Writer<W> {
+
+    pub fn new(inner: W) -> Self {
+        Self { inner, version: ArchiveVersion::UNVERSIONED }
+    }
+
+    /// Sets the version state reported by [`WriteExt::version`] to nested writers.
+    pub fn set_version(&mut self, version: ArchiveVersion) {
+        self.version = version;
+    }
+
+    pub fn version_mut(&mut self) -> &mut ArchiveVersion {
+        &mut self.version
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+}
+
+impl<W: WriteExt> WriteExt for Writer<W> {
+
+    #[inline]
+    fn write_all_bytes(&mut self, bytes: &[u8]) -> WriterResult<()> {
+        self.inner.write_all_bytes(bytes)
+    }
+
+    #[inline]
+    fn version(&self) -> &ArchiveVersion {
+        &self.version
+    }
+
+}
+
+fn length_prefix(length: usize) -> WriterResult<i32> {
+    i32::try_from(length).map_err(|_| {
+        io::Error::new(io::ErrorKind::InvalidInput, format!("length {} does not fit in an i32 prefix", length))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::{BigEndian, LittleEndian, ReadExt, StringEncoding, WriteExt};
+
+    #[test]
+    fn write_fstring_round_trip() {
+        for value in ["", "Hello", "H\u{1F600}"] {
+            let mut buffer = Vec::new();
+            buffer.write_fstring::<LittleEndian>(value).unwrap();
+
+            let mut cursor = Cursor::new(buffer.clone());
+            assert_eq!(cursor.read_fstring::<LittleEndian>().unwrap(), value);
+
+            let mut rewritten = Vec::new();
+            rewritten.write_fstring::<LittleEndian>(value).unwrap();
+            assert_eq!(rewritten, buffer);
+        }
+
+        let mut buffer = Vec::new();
+        buffer.write_fstring_with::<LittleEndian>("Hi", StringEncoding::Utf16).unwrap();
+        assert_eq!(buffer, [0xFD, 0xFF, 0xFF, 0xFF, 0x48, 0x00, 0x69, 0x00, 0x00, 0x00]);
+
+        let mut buffer = Vec::new();
+        buffer.write_fstring_with::<BigEndian>("Hi", StringEncoding::Utf16).unwrap();
+        assert_eq!(buffer, [0xFF, 0xFF, 0xFF, 0xFD, 0x00, 0x48, 0x00, 0x69, 0x00, 0x00]);
+        assert_eq!(Cursor::new(buffer).read_fstring::<BigEndian>().unwrap(), "Hi");
+    }
+
+    #[test]
+    fn write_array_round_trip() {
+        let input = vec![2u8, 0, 0, 0, 3, 0, 0, 0, 4, 0, 0, 0];
+        let values = Cursor::new(input.clone()).read_array::<LittleEndian, _>(|r| r.read_i32_le()).unwrap();
+
+        let mut output = Vec::new();
+        output.write_array::<LittleEndian, _>(&values, |w, v| w.write_i32_le(*v)).unwrap();
+        assert_eq!(output, input);
+    }
+
+}